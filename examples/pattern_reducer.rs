@@ -4,9 +4,6 @@
 //! This example shows how to encapsulate state in dioxus components with the reducer pattern.
 //! This pattern is very useful when a single component can handle many types of input that can
 //! be represented by an enum.
-//!
-//! Currently we don't have a reducer pattern hook. If you'd like to add it,
-//! feel free to make a PR.
 
 use dioxus::prelude::*;
 
@@ -15,16 +12,16 @@ fn main() {
 }
 
 fn app(cx: Scope) -> Element {
-    let state = use_state(&cx, PlayerState::new);
+    let state = use_reducer(&cx, PlayerState::new, PlayerState::reduce);
 
     cx.render(rsx!(
         div {
             h1 {"Select an option"}
-            h3 { "The radio is... " [state.is_playing()] "!" }
-            button { onclick: move |_| state.make_mut().reduce(PlayerAction::Pause),
+            h3 { "The radio is... " [state.state().is_playing()] "!" }
+            button { onclick: move |_| state.dispatch(PlayerAction::Pause),
                 "Pause"
             }
-            button { onclick: move |_| state.make_mut().reduce(PlayerAction::Play),
+            button { onclick: move |_| state.dispatch(PlayerAction::Play),
                 "Play"
             }
         }
@@ -0,0 +1,264 @@
+//! Bindings let reactive values update single text nodes and attributes, without re-rendering the
+//! component that rendered them.
+//!
+//! While a text node or an attribute is formatted, reactive values (like the signals of
+//! `dioxus-hooks`) report their reads through [`bind_read`]. The node is then bound to the values
+//! that were read: it remembers the static text around them, and the [`BindingId`] of every read is
+//! handed back to the value. When the value changes, it sends its new text for that read through
+//! [`SchedulerMsg::Binding`], and the [`VirtualDom`] emits a `SetText` or `SetAttribute` edit for
+//! the node the next time it works.
+//!
+//! Bindings are valid until their component re-renders, which creates new bindings, or is unmounted.
+//! [`ScopeState::binding_liveness`] lets values drop the bindings that are no longer valid. Values read
+//! outside of text nodes and attributes - for example in control flow - can't be bound, and should
+//! re-render the component returned by [`rendering_scope`] instead.
+
+use crate::innerlude::*;
+use std::{
+    cell::{Cell, RefCell},
+    fmt::{Arguments, Write},
+    rc::Rc,
+};
+
+/// Identifies a single read of a reactive value in a text node or attribute.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BindingId {
+    pub(crate) scope: ScopeId,
+    /// The generation of the scope after the render that created the binding.
+    pub(crate) generation: u32,
+    /// The index of the binding in the bindings of the scope.
+    pub(crate) index: usize,
+    /// The index of the read in the bound node.
+    pub(crate) slot: usize,
+}
+
+impl BindingId {
+    /// Get the scope that rendered the bound node.
+    pub fn scope(&self) -> ScopeId {
+        self.scope
+    }
+
+    /// Check whether `other` was created by the same render of the same scope.
+    pub fn same_render(&self, other: &BindingId) -> bool {
+        self.scope == other.scope && self.generation == other.generation
+    }
+}
+
+thread_local! {
+    static RENDERING: Cell<Option<ScopeId>> = const { Cell::new(None) };
+    static FORMATTING: RefCell<Option<Formatting>> = const { RefCell::new(None) };
+}
+
+/// Report the read of a reactive value, whose text is `len` bytes long.
+///
+/// Must be called right before the text is written to the formatter. If a text node or attribute
+/// is being formatted, `on_bind` is called with the [`BindingId`] of the read once the node is
+/// bound, and [`true`] is returned. If the node can't be bound after all, `on_bind` is called with
+/// [`None`], in which case the value should re-render the [`rendering_scope`].
+pub fn bind_read(len: usize, on_bind: impl FnOnce(Option<BindingId>) + 'static) -> bool {
+    FORMATTING.with(|formatting| match &mut *formatting.borrow_mut() {
+        Some(formatting) => {
+            formatting.reads.push(Read {
+                start: formatting.len,
+                len,
+                on_bind: Box::new(on_bind),
+            });
+            true
+        }
+        None => false,
+    })
+}
+
+/// Get the scope that is currently rendering, if any.
+pub fn rendering_scope() -> Option<ScopeId> {
+    RENDERING.with(|rendering| rendering.get())
+}
+
+/// Mark `scope` as rendering until the returned guard is dropped.
+pub(crate) fn enter_render(scope: ScopeId) -> RenderGuard {
+    RenderGuard(RENDERING.with(|rendering| rendering.replace(Some(scope))))
+}
+
+pub(crate) struct RenderGuard(Option<ScopeId>);
+
+impl Drop for RenderGuard {
+    fn drop(&mut self) {
+        RENDERING.with(|rendering| rendering.set(self.0));
+    }
+}
+
+struct Formatting {
+    /// The length of the text formatted so far.
+    len: usize,
+    reads: Vec<Read>,
+}
+
+pub(crate) struct Read {
+    start: usize,
+    len: usize,
+    on_bind: Box<dyn FnOnce(Option<BindingId>)>,
+}
+
+/// Format `args` into `bump`, and collect the reads of reactive values while doing so.
+pub(crate) fn format_tracked<'a>(bump: &'a bumpalo::Bump, args: Arguments) -> (&'a str, Vec<Read>) {
+    struct Writer<'a>(bumpalo::collections::String<'a>);
+
+    impl Write for Writer<'_> {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.push_str(s);
+            FORMATTING.with(|formatting| {
+                if let Some(formatting) = &mut *formatting.borrow_mut() {
+                    formatting.len = self.0.len();
+                }
+            });
+            Ok(())
+        }
+    }
+
+    let previous = FORMATTING.with(|formatting| {
+        formatting.replace(Some(Formatting {
+            len: 0,
+            reads: Vec::new(),
+        }))
+    });
+
+    let mut writer = Writer(bumpalo::collections::String::new_in(bump));
+    writer.write_fmt(args).unwrap();
+
+    let formatting = FORMATTING.with(|formatting| formatting.replace(previous));
+    (writer.0.into_bump_str(), formatting.unwrap().reads)
+}
+
+/// The node a [`Binding`] updates.
+pub(crate) enum BoundNode {
+    Text(*const VText<'static>),
+    Attribute {
+        /// The element of the attribute, once it is created.
+        element: Option<*const VElement<'static>>,
+        /// The address of the formatted value, to find the element of the attribute.
+        value: *const u8,
+        name: &'static str,
+        namespace: Option<&'static str>,
+    },
+}
+
+/// A text node or attribute, bound to the reads of reactive values.
+pub(crate) struct Binding {
+    pub(crate) node: BoundNode,
+    /// The text around the reads, one more than there are reads.
+    segments: Vec<String>,
+    /// The current text of every read.
+    slots: Vec<String>,
+}
+
+impl Binding {
+    /// Create a binding for `node`, whose formatted text is `text`.
+    ///
+    /// Returns [`None`] if the reads don't match the text, for example because a value wrote
+    /// something else than it reported.
+    pub(crate) fn new(node: BoundNode, text: &str, reads: &[Read]) -> Option<Self> {
+        let mut segments = Vec::with_capacity(reads.len() + 1);
+        let mut slots = Vec::with_capacity(reads.len());
+        let mut end = 0;
+
+        for read in reads {
+            let slot = text.get(read.start..read.start.checked_add(read.len)?)?;
+            segments.push(text.get(end..read.start)?.to_string());
+            slots.push(slot.to_string());
+            end = read.start + read.len;
+        }
+        segments.push(text.get(end..)?.to_string());
+
+        Some(Self {
+            node,
+            segments,
+            slots,
+        })
+    }
+
+    /// Replace the text of the read `slot`, and get the new text of the node.
+    pub(crate) fn update(&mut self, slot: usize, text: String) -> Option<String> {
+        *self.slots.get_mut(slot)? = text;
+
+        let mut text = self.segments[0].clone();
+        for (slot, segment) in self.slots.iter().zip(&self.segments[1..]) {
+            text.push_str(slot);
+            text.push_str(segment);
+        }
+        Some(text)
+    }
+}
+
+impl ScopeState {
+    /// Get a function that checks whether a [`BindingId`] is still valid.
+    ///
+    /// Bindings are invalid once their component re-rendered or was unmounted. Reactive values use
+    /// this to drop the bindings they no longer need to update.
+    pub fn binding_liveness(&self) -> Rc<dyn Fn(BindingId) -> bool> {
+        let generations = self.generations.clone();
+        Rc::new(move |binding| {
+            generations.borrow().get(&binding.scope) == Some(&binding.generation)
+        })
+    }
+
+    /// Bind `node` to the `reads` of reactive values, which were formatted into `text`.
+    pub(crate) fn bind(&self, node: BoundNode, text: &str, reads: Vec<Read>) {
+        let binding = Binding::new(node, text, &reads);
+
+        let index = {
+            let mut bindings = self.bindings.borrow_mut();
+            binding.map(|binding| {
+                if let BoundNode::Attribute { .. } = binding.node {
+                    self.pending_attributes.borrow_mut().push(bindings.len());
+                }
+                bindings.push(binding);
+                bindings.len() - 1
+            })
+        };
+
+        for (slot, read) in reads.into_iter().enumerate() {
+            (read.on_bind)(index.map(|index| BindingId {
+                scope: self.our_arena_idx,
+                // the generation is bumped once the render is done
+                generation: self.generation.get() + 1,
+                index,
+                slot,
+            }));
+        }
+    }
+
+    /// Attach the bound attributes among `attributes` to `element`.
+    ///
+    /// Only the attribute bindings without an element are checked. Those of the ancestors of
+    /// `element` are created before it, and stay pending until their own element is created.
+    pub(crate) fn bind_attributes(&self, element: &VElement, attributes: &[Attribute]) {
+        let mut pending = self.pending_attributes.borrow_mut();
+        if pending.is_empty() {
+            return;
+        }
+        let mut bindings = self.bindings.borrow_mut();
+
+        pending.retain(|index| {
+            if let BoundNode::Attribute {
+                element: bound @ None,
+                value,
+                name,
+                ..
+            } = &mut bindings[*index].node
+            {
+                let matches = attributes.iter().any(|attribute| match attribute.value {
+                    AttributeValue::Text(text) => {
+                        text.as_ptr() == *value && attribute.name == *name
+                    }
+                    _ => false,
+                });
+                if matches {
+                    *bound = Some((element as *const VElement).cast());
+                }
+                !matches
+            } else {
+                false
+            }
+        });
+    }
+}
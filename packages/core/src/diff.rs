@@ -303,7 +303,8 @@ impl<'b> DiffState<'b> {
             None => self.scopes.reserve_node(new_node),
         };
 
-        if old.text != new.text {
+        let overwritten = self.scopes.overwritten.borrow_mut().remove(&root);
+        if old.text != new.text || overwritten {
             self.mutations.set_text(new.text, root.as_u64());
         }
 
@@ -375,9 +376,10 @@ impl<'b> DiffState<'b> {
         // In these cases, we just completely erase the old set and make a new set
         //
         // TODO: take a more efficient path than this
+        let overwritten = self.scopes.overwritten.borrow_mut().remove(&root);
         if old.attributes.len() == new.attributes.len() {
             for (old_attr, new_attr) in old.attributes.iter().zip(new.attributes.iter()) {
-                if old_attr.value != new_attr.value || new_attr.is_volatile {
+                if old_attr.value != new_attr.value || new_attr.is_volatile || overwritten {
                     self.mutations.set_attribute(new_attr, root.as_u64());
                 }
             }
@@ -4,7 +4,10 @@
 //! cheap and *very* fast to construct - building a full tree should be quick.
 
 use crate::{
-    innerlude::{AttributeValue, ComponentPtr, Element, Properties, Scope, ScopeId, ScopeState},
+    innerlude::{
+        format_tracked, AttributeValue, BoundNode, ComponentPtr, Element, Properties, Scope,
+        ScopeId, ScopeState,
+    },
     lazynodes::LazyNodes,
     AnyEvent, Component,
 };
@@ -540,14 +543,25 @@ impl<'a> NodeFactory<'a> {
 
     /// Create some text that's allocated along with the other vnodes
     ///
+    /// Reactive values that are formatted into the text bind the text node, see [`bind_read`].
     pub fn text(&self, args: Arguments) -> VNode<'a> {
-        let (text, is_static) = self.raw_text(args);
+        if let Some(text) = args.as_str() {
+            return self.static_text(text);
+        }
 
-        VNode::Text(self.bump.alloc(VText {
+        let (text, reads) = format_tracked(self.bump, args);
+        let node = self.bump.alloc(VText {
             text,
-            is_static,
+            is_static: false,
             id: empty_cell(),
-        }))
+        });
+
+        if !reads.is_empty() {
+            let ptr: *const VText<'static> = (node as *const VText).cast();
+            self.scope.bind(BoundNode::Text(ptr), text, reads);
+        }
+
+        VNode::Text(node)
     }
 
     /// Create a new [`VNode::Element`]
@@ -589,7 +603,7 @@ impl<'a> NodeFactory<'a> {
             items.listeners.push(long_listener);
         }
 
-        VNode::Element(self.bump.alloc(VElement {
+        let element = self.bump.alloc(VElement {
             tag: tag_name,
             key,
             namespace,
@@ -598,10 +612,18 @@ impl<'a> NodeFactory<'a> {
             children,
             id: empty_cell(),
             parent: empty_cell(),
-        }))
+        });
+
+        if !attributes.is_empty() {
+            self.scope.bind_attributes(element, attributes);
+        }
+
+        VNode::Element(element)
     }
 
     /// Create a new [`Attribute`]
+    ///
+    /// Reactive values that are formatted into the value bind the attribute, see [`bind_read`].
     pub fn attr(
         &self,
         name: &'static str,
@@ -609,7 +631,23 @@ impl<'a> NodeFactory<'a> {
         namespace: Option<&'static str>,
         is_volatile: bool,
     ) -> Attribute<'a> {
-        let (value, is_static) = self.raw_text(val);
+        let (value, is_static) = match val.as_str() {
+            Some(value) => (value, true),
+            None => {
+                let (value, reads) = format_tracked(self.bump, val);
+                if !reads.is_empty() {
+                    let node = BoundNode::Attribute {
+                        element: None,
+                        value: value.as_ptr(),
+                        name,
+                        namespace,
+                    };
+                    self.scope.bind(node, value, reads);
+                }
+                (value, false)
+            }
+        };
+
         Attribute {
             name,
             value: AttributeValue::Text(value),
@@ -1,7 +1,7 @@
 use crate::{innerlude::*, unsafe_utils::extend_vnode};
 use bumpalo::Bump;
 use futures_channel::mpsc::UnboundedSender;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use slab::Slab;
 use std::{
     any::{Any, TypeId},
@@ -35,6 +35,10 @@ pub(crate) struct ScopeArena {
     pub free_scopes: RefCell<Vec<*mut ScopeState>>,
     pub nodes: RefCell<Slab<*const VNode<'static>>>,
     pub tasks: Rc<TaskQueue>,
+    /// Nodes whose text or attributes were changed by a binding since they were last diffed.
+    pub overwritten: RefCell<FxHashSet<ElementId>>,
+    /// The generations of the mounted scopes, shared with the scopes to check bindings.
+    pub generations: Rc<RefCell<FxHashMap<ScopeId, u32>>>,
 }
 
 impl ScopeArena {
@@ -74,6 +78,8 @@ impl ScopeArena {
                 gen: Cell::new(0),
                 sender,
             }),
+            overwritten: RefCell::default(),
+            generations: Rc::default(),
         }
     }
 
@@ -130,6 +136,8 @@ impl ScopeArena {
             scope.shared_contexts.get_mut().clear();
             scope.items.get_mut().listeners.clear();
             scope.items.get_mut().borrowed_props.clear();
+            scope.bindings.get_mut().clear();
+            scope.pending_attributes.get_mut().clear();
             scope.hook_idx.set(0);
             scope.hook_vals.get_mut().clear();
 
@@ -162,12 +170,15 @@ impl ScopeArena {
                     generation: 0.into(),
 
                     tasks: self.tasks.clone(),
+                    generations: self.generations.clone(),
                     shared_contexts: RefCell::default(),
 
                     items: RefCell::new(SelfReferentialItems {
                         listeners: Vec::default(),
                         borrowed_props: Vec::default(),
                     }),
+                    bindings: RefCell::default(),
+                    pending_attributes: RefCell::default(),
 
                     hook_arena: Bump::new(),
                     hook_vals: RefCell::new(Vec::with_capacity(hook_capacity)),
@@ -197,6 +208,7 @@ impl ScopeArena {
         // - this raw pointer is removed from the map
         let scope = unsafe { &mut *self.scopes.borrow_mut().remove(&id).unwrap() };
         scope.reset();
+        self.generations.borrow_mut().remove(&id);
 
         self.free_scopes.borrow_mut().push(scope);
     }
@@ -280,6 +292,10 @@ impl ScopeArena {
             // guarantee that we haven't screwed up - there should be no latent references anywhere
             debug_assert!(items.listeners.is_empty());
             debug_assert!(items.borrowed_props.is_empty());
+
+            // the bindings point into the frame that is about to be replaced
+            scope.bindings.get_mut().clear();
+            scope.pending_attributes.get_mut().clear();
         }
 
         /*
@@ -293,6 +309,7 @@ impl ScopeArena {
         I'm not sure if React lets you abort the component early, but we let you do that.
         */
 
+        let _rendering = enter_render(id);
         let props = scope.props.borrow();
         let render = props.as_ref().unwrap();
         if let Some(node) = render.render(scope) {
@@ -312,6 +329,9 @@ impl ScopeArena {
         // make the "wip frame" contents the "finished frame"
         // any future dipping into completed nodes after "render" will go through "fin head"
         scope.cycle_frame();
+        self.generations
+            .borrow_mut()
+            .insert(id, scope.generation.get());
     }
 
     pub fn call_listener_with_bubbling(&self, event: &UserEvent, element: ElementId) {
@@ -477,6 +497,9 @@ pub struct ScopeState {
     pub(crate) frames: [BumpFrame; 2],
     pub(crate) generation: Cell<u32>,
     pub(crate) items: RefCell<SelfReferentialItems<'static>>,
+    pub(crate) bindings: RefCell<Vec<Binding>>,
+    /// The indices of the attribute bindings whose element isn't created yet.
+    pub(crate) pending_attributes: RefCell<Vec<usize>>,
 
     // hooks
     pub(crate) hook_arena: Bump,
@@ -486,6 +509,7 @@ pub struct ScopeState {
     // shared state -> todo: move this out of scopestate
     pub(crate) shared_contexts: RefCell<HashMap<TypeId, Box<dyn Any>>>,
     pub(crate) tasks: Rc<TaskQueue>,
+    pub(crate) generations: Rc<RefCell<FxHashMap<ScopeId, u32>>>,
 }
 
 pub struct SelfReferentialItems<'a> {
@@ -916,6 +940,8 @@ impl ScopeState {
         } = self.items.get_mut();
         borrowed_props.clear();
         listeners.clear();
+        self.bindings.get_mut().clear();
+        self.pending_attributes.get_mut().clear();
         self.frames[0].reset();
         self.frames[1].reset();
 
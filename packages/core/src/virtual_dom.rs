@@ -4,6 +4,7 @@
 
 use crate::diff::DiffState;
 use crate::innerlude::*;
+use bumpalo::Bump;
 use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures_util::{future::poll_fn, StreamExt};
 use fxhash::FxHashSet;
@@ -108,6 +109,11 @@ pub struct VirtualDom {
     pending_messages: VecDeque<SchedulerMsg>,
     dirty_scopes: IndexSet<ScopeId>,
 
+    /// Updates of bound text nodes and attributes, see [`bind_read`].
+    pending_bindings: Vec<(BindingId, String)>,
+    /// Holds the texts of the edits of bindings, until the next call to `work_with_deadline`.
+    binding_text: Bump,
+
     channel: (
         UnboundedSender<SchedulerMsg>,
         UnboundedReceiver<SchedulerMsg>,
@@ -130,6 +136,9 @@ pub enum SchedulerMsg {
 
     /// New tasks from components that should be polled when the next poll is ready
     NewTask(ScopeId),
+
+    /// The new text of a read of a reactive value in a bound text node or attribute
+    Binding(BindingId, String),
 }
 
 // Methods to create the VirtualDom
@@ -235,6 +244,8 @@ impl VirtualDom {
             channel,
             dirty_scopes: IndexSet::from_iter([ScopeId(0)]),
             pending_messages: VecDeque::new(),
+            pending_bindings: Vec::new(),
+            binding_text: Bump::new(),
         }
     }
 
@@ -312,7 +323,9 @@ impl VirtualDom {
     /// assert!(dom.has_any_work());
     /// ```
     pub fn has_work(&self) -> bool {
-        !(self.dirty_scopes.is_empty() && self.pending_messages.is_empty())
+        !(self.dirty_scopes.is_empty()
+            && self.pending_messages.is_empty()
+            && self.pending_bindings.is_empty())
     }
 
     /// Wait for the scheduler to have any work.
@@ -330,7 +343,8 @@ impl VirtualDom {
     /// ```
     pub async fn wait_for_work(&mut self) {
         loop {
-            if !self.dirty_scopes.is_empty() && self.pending_messages.is_empty() {
+            let has_updates = !self.dirty_scopes.is_empty() || !self.pending_bindings.is_empty();
+            if has_updates && self.pending_messages.is_empty() {
                 break;
             }
 
@@ -398,6 +412,9 @@ impl VirtualDom {
                     self.dirty_scopes.insert(*id);
                 }
             }
+            SchedulerMsg::Binding(binding, text) => {
+                self.pending_bindings.push((binding, text));
+            }
         }
     }
 
@@ -449,6 +466,7 @@ impl VirtualDom {
     #[allow(unused)]
     pub fn work_with_deadline(&mut self, mut deadline: impl FnMut() -> bool) -> Vec<Mutations> {
         let mut committed_mutations = vec![];
+        self.binding_text.reset();
 
         while !self.dirty_scopes.is_empty() {
             let scopes = &self.scopes;
@@ -502,6 +520,13 @@ impl VirtualDom {
             }
         }
 
+        // bindings of scopes that were just re-rendered are outdated, and skipped
+        let pending_bindings = std::mem::take(&mut self.pending_bindings);
+        let mutations = apply_bindings(&self.scopes, &self.binding_text, pending_bindings);
+        if !mutations.edits.is_empty() {
+            committed_mutations.push(mutations);
+        }
+
         committed_mutations
     }
 
@@ -746,3 +771,69 @@ impl Drop for VirtualDom {
         }
     }
 }
+
+/// Create the edits for the updates of bound text nodes and attributes.
+///
+/// Updates are skipped if their scope re-rendered since the binding was created.
+fn apply_bindings<'a>(
+    scopes: &'a ScopeArena,
+    bump: &'a Bump,
+    updates: Vec<(BindingId, String)>,
+) -> Mutations<'a> {
+    let mut mutations = Mutations::new();
+
+    for (binding, text) in updates {
+        let scope = match scopes.get_scope(binding.scope) {
+            Some(scope) if scope.generation.get() == binding.generation => scope,
+            _ => continue,
+        };
+
+        let mut bindings = scope.bindings.borrow_mut();
+        let bound = match bindings.get_mut(binding.index) {
+            Some(bound) => bound,
+            None => continue,
+        };
+        let text = match bound.update(binding.slot, text) {
+            Some(text) => &*bump.alloc_str(&text),
+            None => continue,
+        };
+
+        // Safety:
+        // - bound nodes live in the finished frame of their scope
+        // - the bindings of a scope are cleared before that frame is reset
+        let id = match bound.node {
+            BoundNode::Text(node) => {
+                let id = unsafe { &*node }.id.get();
+                if let Some(id) = id {
+                    mutations.set_text(text, id.as_u64());
+                }
+                id
+            }
+            BoundNode::Attribute {
+                element: Some(element),
+                name,
+                namespace,
+                ..
+            } => {
+                let id = unsafe { &*element }.id.get();
+                if let Some(id) = id {
+                    mutations.edits.push(DomEdit::SetAttribute {
+                        root: id.as_u64(),
+                        field: name,
+                        value: AttributeValue::Text(text),
+                        ns: namespace,
+                    });
+                }
+                id
+            }
+            BoundNode::Attribute { element: None, .. } => None,
+        };
+
+        // the next diff can't rely on the old text anymore
+        if let Some(id) = id {
+            scopes.overwritten.borrow_mut().insert(id);
+        }
+    }
+
+    mutations
+}
@@ -0,0 +1,209 @@
+use super::selector::compute;
+use crate::{AtomId, AtomRoot, Derivation, Readable, SelectorBuilder, Slot};
+use std::{any::Any, future::Future, pin::Pin, rc::Rc};
+
+/// The future that resolves the value of an [`AsyncAtom`] or
+/// [`AsyncSelector`](crate::AsyncSelector).
+pub type AtomFuture<V, E> = Pin<Box<dyn Future<Output = Result<V, E>>>>;
+
+/// An atom whose value is resolved by a future, for example by fetching it from an API.
+///
+/// The future is started when the atom is first used, and runs on the task system of the
+/// `VirtualDom`. All components reading the atom share the same future, and are re-rendered once it
+/// resolves. Until then, they read [`AsyncValue::Loading`].
+///
+/// Calling [`AtomRoot::refresh`] (or the function returned by [`use_refresh`]) starts a new future,
+/// which replaces the current one.
+///
+/// ```rust, ignore
+/// static USER: AsyncAtom<User, reqwest::Error> = |_| Box::pin(async {
+///     reqwest::get("https://example.com/user").await?.json().await
+/// });
+///
+/// fn UserName(cx: Scope) -> Element {
+///     cx.render(match use_read(&cx, USER) {
+///         AsyncValue::Loading => rsx!{ "loading..." },
+///         AsyncValue::Ready(user) => rsx!{ "{user.name}" },
+///         AsyncValue::Error(e) => rsx!{ "failed to load user: {e}" },
+///     })
+/// }
+/// ```
+///
+/// [`use_refresh`]: crate::use_refresh
+pub type AsyncAtom<V, E> = fn(AsyncAtomBuilder) -> AtomFuture<V, E>;
+pub struct AsyncAtomBuilder;
+
+/// The value of an [`AsyncAtom`] or [`AsyncSelector`](crate::AsyncSelector).
+///
+/// Dioxus doesn't support suspense yet, so components decide what to render while the value is
+/// loading themselves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AsyncValue<V, E> {
+    /// The future hasn't resolved yet.
+    Loading,
+    /// The future resolved successfully.
+    Ready(V),
+    /// The future failed.
+    Error(E),
+}
+
+impl<V, E> AsyncValue<V, E> {
+    /// Check whether the value is still loading.
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    /// Get the value, if the future resolved successfully.
+    pub fn value(&self) -> Option<&V> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the error, if the future failed.
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            Self::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<V, E> From<Result<V, E>> for AsyncValue<V, E> {
+    fn from(result: Result<V, E>) -> Self {
+        match result {
+            Ok(value) => Self::Ready(value),
+            Err(error) => Self::Error(error),
+        }
+    }
+}
+
+/// Create the slot of the async atom with the id `id`, whose future is created by `f`.
+///
+/// The atoms `f` reads are tracked, and a new future is started whenever one of them changes.
+pub(crate) fn async_slot<V: 'static, E: 'static>(
+    id: AtomId,
+    f: impl Fn(SelectorBuilder) -> AtomFuture<V, E> + 'static,
+    root: &AtomRoot,
+) -> Slot {
+    let derivation = Derivation {
+        compute: Rc::new(move |root| {
+            let (future, dependencies) = compute(&f, root);
+            root.queue(
+                id,
+                Box::pin(async move { Rc::new(AsyncValue::from(future.await)) as Rc<dyn Any> }),
+            );
+            (
+                Rc::new(AsyncValue::<V, E>::Loading) as Rc<dyn Any>,
+                dependencies,
+            )
+        }),
+        // a new future is started, so the value is always loading again
+        eq: |_, _| false,
+    };
+
+    let (value, dependencies) = (derivation.compute)(root);
+    let mut slot = Slot::new(value);
+    slot.dependencies = dependencies;
+    slot.derivation = Some(derivation);
+    slot
+}
+
+impl<V: 'static, E: 'static> Readable<AsyncValue<V, E>> for AsyncAtom<V, E> {
+    fn read(&self, root: AtomRoot) -> Option<AsyncValue<V, E>> {
+        root.take(*self)
+    }
+
+    fn init(&self) -> AsyncValue<V, E> {
+        AsyncValue::Loading
+    }
+
+    fn unique_id(&self) -> AtomId {
+        *self as *const ()
+    }
+
+    fn init_slot(&self, root: &AtomRoot) -> Slot {
+        let atom = *self;
+        async_slot(self.unique_id(), move |_| atom(AsyncAtomBuilder), root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_core::ScopeId;
+    use futures::executor::block_on;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    };
+
+    static FETCHES: AtomicU32 = AtomicU32::new(0);
+    static FETCHED: AsyncAtom<u32, String> = |_| {
+        let fetch = FETCHES.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move { Ok(fetch) })
+    };
+
+    fn test_root() -> (Rc<AtomRoot>, Arc<Mutex<Vec<ScopeId>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let root = AtomRoot::new({
+            let updates = updates.clone();
+            Arc::new(move |id| updates.lock().unwrap().push(id))
+        });
+        (Rc::new(root), updates)
+    }
+
+    #[test]
+    fn loading_and_ready() {
+        static USER: AsyncAtom<&str, String> = |_| Box::pin(async { Ok("dioxus") });
+
+        let (root, updates) = test_root();
+        assert_eq!(*root.register(USER, ScopeId(1)), AsyncValue::Loading);
+        // concurrent reads share the same future
+        assert_eq!(*root.register(USER, ScopeId(2)), AsyncValue::Loading);
+
+        let mut tasks = root.take_tasks();
+        assert_eq!(tasks.len(), 1);
+        block_on(tasks.remove(0));
+
+        assert_eq!(*root.read(USER), AsyncValue::Ready("dioxus"));
+        let mut updates = updates.lock().unwrap().clone();
+        updates.sort_by_key(|id| id.0);
+        assert_eq!(updates, vec![ScopeId(1), ScopeId(2)]);
+    }
+
+    #[test]
+    fn error() {
+        static FAILING: AsyncAtom<u32, &str> = |_| Box::pin(async { Err("failed") });
+
+        let (root, _) = test_root();
+        root.read(FAILING);
+        for task in root.take_tasks() {
+            block_on(task);
+        }
+
+        assert_eq!(root.read(FAILING).error(), Some(&"failed"));
+
+        let root = Rc::try_unwrap(root).ok().unwrap();
+        assert_eq!(FAILING.read(root), Some(AsyncValue::Error("failed")));
+    }
+
+    #[test]
+    fn refresh() {
+        let (root, updates) = test_root();
+        root.register(FETCHED, ScopeId(1));
+        let first = root.take_tasks();
+
+        root.refresh(FETCHED.unique_id());
+        assert!(root.read(FETCHED).is_loading());
+        assert_eq!(*updates.lock().unwrap(), vec![ScopeId(1)]);
+        let second = root.take_tasks();
+
+        // the first future is outdated, its value is discarded
+        for task in second.into_iter().chain(first) {
+            block_on(task);
+        }
+        assert_eq!(root.read(FETCHED).value(), Some(&1));
+    }
+}
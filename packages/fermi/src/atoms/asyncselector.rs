@@ -0,0 +1,83 @@
+use super::asyncatom::async_slot;
+use crate::{AsyncValue, AtomFuture, AtomId, AtomRoot, Readable, SelectorBuilder, Slot};
+
+/// A value that is derived from other atoms asynchronously.
+///
+/// The selector reads other atoms through the [`SelectorBuilder`] it is given, and returns a future
+/// resolving its value. Whenever one of the atoms it read changes, a new future is started and the
+/// value is loading again. Values of outdated futures are discarded.
+///
+/// Atoms can only be read before the future is created, so their values are moved into it.
+///
+/// ```rust, ignore
+/// static USER_ID: Atom<u32> = |_| 0;
+/// static USER: AsyncSelector<User, reqwest::Error> = |s| {
+///     let id = *s.get(USER_ID);
+///     Box::pin(async move {
+///         reqwest::get(format!("https://example.com/user/{id}")).await?.json().await
+///     })
+/// };
+/// ```
+pub type AsyncSelector<V, E> = fn(SelectorBuilder<'_>) -> AtomFuture<V, E>;
+
+impl<V: 'static, E: 'static> Readable<AsyncValue<V, E>> for AsyncSelector<V, E> {
+    fn read(&self, root: AtomRoot) -> Option<AsyncValue<V, E>> {
+        root.take(*self)
+    }
+
+    fn init(&self) -> AsyncValue<V, E> {
+        AsyncValue::Loading
+    }
+
+    fn unique_id(&self) -> AtomId {
+        *self as *const ()
+    }
+
+    fn init_slot(&self, root: &AtomRoot) -> Slot {
+        async_slot(self.unique_id(), *self, root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Atom, Selector};
+    use dioxus_core::ScopeId;
+    use futures::executor::block_on;
+    use std::{rc::Rc, sync::Arc};
+
+    static USER_ID: Atom<u32> = |_| 1;
+    static USER: AsyncSelector<String, ()> = |s| {
+        let id = *s.get(USER_ID);
+        Box::pin(async move { Ok(format!("user #{id}")) })
+    };
+    static USER_LOADED: Selector<bool> = |s| !s.get(USER).is_loading();
+
+    #[test]
+    fn async_selector() {
+        let root = Rc::new(AtomRoot::new(Arc::new(|_| {})));
+        root.register(USER, ScopeId(1));
+        assert!(!*root.read(USER_LOADED));
+
+        for task in root.take_tasks() {
+            block_on(task);
+        }
+        assert_eq!(root.read(USER).value().unwrap(), "user #1");
+        // selectors can depend on async selectors
+        assert!(*root.read(USER_LOADED));
+
+        root.set(USER_ID.unique_id(), 2u32);
+        assert!(root.read(USER).is_loading());
+        assert!(!*root.read(USER_LOADED));
+
+        for task in root.take_tasks() {
+            block_on(task);
+        }
+        assert_eq!(root.read(USER).value().unwrap(), "user #2");
+        assert!(*root.read(USER_LOADED));
+
+        // reading from a root that doesn't run tasks leaves the selector loading
+        let root = AtomRoot::new(Arc::new(|_| {}));
+        assert_eq!(USER.read(root), Some(AsyncValue::Loading));
+    }
+}
@@ -1,25 +1,135 @@
-use crate::{AtomId, AtomRoot, Readable, Writable};
-use im_rc::HashMap as ImMap;
+use crate::{member_id, AtomId, AtomRoot, Readable, Writable};
+use std::hash::Hash;
 
+/// A family of atoms, with one atom per key.
+///
+/// A member of the family is selected by pairing the family with a key. Every member has its own
+/// value and its own subscribers, so setting the value of one member only re-renders the components
+/// reading that member. Members start out with the value the family computes for their key.
+///
+/// Members that no component reads anymore are removed from the [`AtomRoot`], and start over with
+/// their initial value when they are used again.
+///
+/// ```rust, ignore
+/// static TODO: AtomFamily<u32, String> = |_, id| format!("todo #{id}");
+///
+/// fn Todo(cx: Scope<TodoProps>) -> Element {
+///     let todo = use_read(&cx, (TODO, cx.props.id));
+///     cx.render(rsx!{ li { "{todo}" } })
+/// }
+/// ```
+pub type AtomFamily<K, V> = fn(AtomFamilyBuilder, &K) -> V;
 pub struct AtomFamilyBuilder;
-pub type AtomFamily<K, V> = fn(AtomFamilyBuilder) -> ImMap<K, V>;
 
-impl<K, V> Readable<ImMap<K, V>> for AtomFamily<K, V> {
-    fn read(&self, _root: AtomRoot) -> Option<ImMap<K, V>> {
-        todo!()
+impl<K, V> Readable<V> for (AtomFamily<K, V>, K)
+where
+    K: Clone + Eq + Hash + 'static,
+    V: 'static,
+{
+    fn read(&self, root: AtomRoot) -> Option<V> {
+        root.take(self.clone())
     }
 
-    fn init(&self) -> ImMap<K, V> {
-        (*self)(AtomFamilyBuilder)
+    fn init(&self) -> V {
+        (self.0)(AtomFamilyBuilder, &self.1)
     }
 
     fn unique_id(&self) -> AtomId {
-        *self as *const ()
+        member_id(self.0 as *const (), &self.1)
     }
 }
 
-impl<K, V> Writable<ImMap<K, V>> for AtomFamily<K, V> {
-    fn write(&self, _root: AtomRoot, _value: ImMap<K, V>) {
-        todo!()
+impl<K, V> Writable<V> for (AtomFamily<K, V>, K)
+where
+    K: Clone + Eq + Hash + 'static,
+    V: 'static,
+{
+    fn write(&self, root: AtomRoot, value: V) {
+        root.set(self.unique_id(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_core::ScopeId;
+    use std::sync::{Arc, Mutex};
+
+    static TODO: AtomFamily<u32, String> = |_, id| format!("todo #{id}");
+
+    fn test_root() -> (AtomRoot, Arc<Mutex<Vec<ScopeId>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let root = AtomRoot::new({
+            let updates = updates.clone();
+            Arc::new(move |id| updates.lock().unwrap().push(id))
+        });
+        (root, updates)
+    }
+
+    #[test]
+    fn atom_family_compiles() {
+        assert_eq!((TODO, 42).init(), "todo #42");
+        assert_ne!((TODO, 1).unique_id(), (TODO, 2).unique_id());
+        assert_eq!((TODO, 1).unique_id(), (TODO, 1).unique_id());
+    }
+
+    #[test]
+    fn members_with_colliding_hashes() {
+        // every key has the same hash, but is still a member of its own
+        #[derive(Clone, PartialEq, Eq)]
+        struct Key(u32);
+
+        impl Hash for Key {
+            fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+        }
+
+        static NAME: AtomFamily<Key, u32> = |_, key| key.0;
+
+        let (root, _) = test_root();
+        root.set((NAME, Key(1)).unique_id(), 10u32);
+        assert_eq!(*root.read((NAME, Key(1))), 10);
+        assert_eq!(*root.read((NAME, Key(2))), 2);
+        assert_eq!((NAME, Key(2)).read(root), Some(2));
+    }
+
+    #[test]
+    fn members_have_own_subscribers() {
+        let (root, updates) = test_root();
+        assert_eq!(*root.register((TODO, 1), ScopeId(1)), "todo #1");
+        assert_eq!(*root.register((TODO, 42), ScopeId(2)), "todo #42");
+
+        root.set((TODO, 42).unique_id(), String::from("done"));
+        assert_eq!(*updates.lock().unwrap(), vec![ScopeId(2)]);
+        assert_eq!(*root.read((TODO, 42)), "done");
+        assert_eq!(*root.read((TODO, 1)), "todo #1");
+    }
+
+    #[test]
+    fn unsubscribed_members_are_collected() {
+        let (root, _) = test_root();
+        root.register((TODO, 1), ScopeId(1));
+        root.register((TODO, 1), ScopeId(2));
+        root.set((TODO, 1).unique_id(), String::from("done"));
+
+        root.unsubscribe((TODO, 1).unique_id(), ScopeId(1));
+        assert_eq!(*root.read((TODO, 1)), "done");
+
+        let id = (TODO, 1).unique_id();
+        root.unsubscribe(id, ScopeId(2));
+        assert!(!root.atoms.borrow().contains_key(&id));
+        // the key is forgotten as well, and gets a new id
+        assert_ne!((TODO, 1).unique_id(), id);
+        assert_eq!(*root.read((TODO, 1)), "todo #1");
+    }
+
+    #[test]
+    fn observed_members_are_kept() {
+        let (root, _) = test_root();
+        root.register((TODO, 1), ScopeId(1));
+        root.add_effect((TODO, 1), |_, _: &String| {});
+        root.set((TODO, 1).unique_id(), String::from("done"));
+
+        root.unsubscribe((TODO, 1).unique_id(), ScopeId(1));
+        assert_eq!(*root.read((TODO, 1)), "done");
     }
 }
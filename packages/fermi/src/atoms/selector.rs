@@ -1 +1,217 @@
+use crate::{AtomId, AtomRoot, Derivation, Readable, Slot};
+use im_rc::HashSet;
+use std::{any::Any, cell::RefCell, rc::Rc, sync::Arc};
 
+/// A value derived from other atoms.
+///
+/// Selectors read other atoms (and selectors) through the [`SelectorBuilder`] they are given. The
+/// atoms they read are tracked, and the selector is only recomputed when one of them changes. Its
+/// subscribers are only re-rendered if the derived value actually changed.
+///
+/// ```rust, ignore
+/// static COUNT: Atom<u32> = |_| 0;
+/// static DOUBLED: Selector<u32> = |s| *s.get(COUNT) * 2;
+///
+/// fn Doubled(cx: Scope) -> Element {
+///     let doubled = use_read(&cx, DOUBLED);
+///     cx.render(rsx!{ "{doubled}" })
+/// }
+/// ```
+pub type Selector<V> = fn(SelectorBuilder<'_>) -> V;
+
+/// The getter API of [`Selector`]s and [`SelectorFamily`](crate::SelectorFamily)s.
+pub struct SelectorBuilder<'a> {
+    root: &'a AtomRoot,
+    dependencies: &'a RefCell<HashSet<AtomId>>,
+}
+
+impl SelectorBuilder<'_> {
+    /// Get the current value of `atom`, and recompute the selector whenever it changes.
+    pub fn get<V: 'static>(&self, atom: impl Readable<V>) -> Rc<V> {
+        self.dependencies.borrow_mut().insert(atom.unique_id());
+        self.root.read(atom)
+    }
+}
+
+/// Compute a value with `f`, reading atoms from `root`.
+///
+/// Returns the value and the ids of the atoms it was derived from.
+pub(crate) fn compute<V>(
+    f: impl FnOnce(SelectorBuilder) -> V,
+    root: &AtomRoot,
+) -> (V, HashSet<AtomId>) {
+    let dependencies = RefCell::new(HashSet::new());
+    let value = f(SelectorBuilder {
+        root,
+        dependencies: &dependencies,
+    });
+    (value, dependencies.into_inner())
+}
+
+/// Create the slot of a value that is computed with `f`, reading atoms from `root`.
+///
+/// The value is recomputed whenever one of the atoms it read changes.
+pub(crate) fn derived_slot<V: PartialEq + 'static>(
+    f: impl Fn(SelectorBuilder) -> V + 'static,
+    root: &AtomRoot,
+) -> Slot {
+    fn any_eq<V: PartialEq + 'static>(old: &dyn Any, new: &dyn Any) -> bool {
+        old.downcast_ref::<V>() == new.downcast_ref::<V>()
+    }
+
+    let f = Rc::new(f);
+    let (value, dependencies) = compute(&*f, root);
+
+    let mut slot = Slot::new(Rc::new(value));
+    slot.dependencies = dependencies;
+    slot.derivation = Some(Derivation {
+        compute: Rc::new(move |root| {
+            let (value, dependencies) = compute(&*f, root);
+            (Rc::new(value) as Rc<dyn Any>, dependencies)
+        }),
+        eq: any_eq::<V>,
+    });
+    slot
+}
+
+impl<V: PartialEq + 'static> Readable<V> for Selector<V> {
+    fn read(&self, root: AtomRoot) -> Option<V> {
+        root.take(*self)
+    }
+
+    /// The value of the selector when all atoms it reads have their initial values.
+    fn init(&self) -> V {
+        compute(*self, &AtomRoot::new(Arc::new(|_| {}))).0
+    }
+
+    fn unique_id(&self) -> AtomId {
+        *self as *const ()
+    }
+
+    fn init_slot(&self, root: &AtomRoot) -> Slot {
+        derived_slot(*self, root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Atom;
+    use dioxus_core::ScopeId;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    fn test_root() -> (AtomRoot, Arc<Mutex<Vec<ScopeId>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let root = AtomRoot::new({
+            let updates = updates.clone();
+            Arc::new(move |id| updates.lock().unwrap().push(id))
+        });
+        (root, updates)
+    }
+
+    fn take(updates: &Mutex<Vec<ScopeId>>) -> Vec<ScopeId> {
+        let mut updates = std::mem::take(&mut *updates.lock().unwrap());
+        updates.sort_by_key(|id| id.0);
+        updates
+    }
+
+    #[test]
+    fn selector_compiles() {
+        static TEST_ATOM: Atom<u32> = |_| 21;
+        static TEST_SELECTOR: Selector<u32> = |s| *s.get(TEST_ATOM) * 2;
+        assert_eq!(TEST_SELECTOR.init(), 42);
+    }
+
+    #[test]
+    fn read() {
+        static COUNT: Atom<u32> = |_| 1;
+        static DOUBLED: Selector<u32> = |s| *s.get(COUNT) * 2;
+
+        let (root, _) = test_root();
+        root.set(COUNT.unique_id(), 21u32);
+        assert_eq!(DOUBLED.read(root), Some(42));
+    }
+
+    #[test]
+    fn diamond() {
+        static COUNT: Atom<u32> = |_| 1;
+        static PLUS_ONE: Selector<u32> = |s| *s.get(COUNT) + 1;
+        static TIMES_TWO: Selector<u32> = |s| *s.get(COUNT) * 2;
+        static COMPUTED: AtomicUsize = AtomicUsize::new(0);
+        static SUM: Selector<u32> = |s| {
+            COMPUTED.fetch_add(1, Ordering::SeqCst);
+            *s.get(PLUS_ONE) + *s.get(TIMES_TWO)
+        };
+
+        let (root, updates) = test_root();
+        assert_eq!(*root.register(SUM, ScopeId(1)), 4);
+        assert_eq!(COMPUTED.load(Ordering::SeqCst), 1);
+
+        root.set(COUNT.unique_id(), 2u32);
+        assert_eq!(*root.read(SUM), 7);
+        assert_eq!(*root.read(PLUS_ONE), 3);
+        assert_eq!(*root.read(TIMES_TWO), 4);
+        // recomputed once, after both branches are up to date
+        assert_eq!(COMPUTED.load(Ordering::SeqCst), 2);
+        assert_eq!(take(&updates), vec![ScopeId(1)]);
+    }
+
+    #[test]
+    fn unchanged_values_dont_notify() {
+        static COUNT: Atom<u32> = |_| 0;
+        static IS_EVEN: Selector<bool> = |s| *s.get(COUNT) % 2 == 0;
+        static COMPUTED: AtomicUsize = AtomicUsize::new(0);
+        static LABEL: Selector<&str> = |s| {
+            COMPUTED.fetch_add(1, Ordering::SeqCst);
+            match *s.get(IS_EVEN) {
+                true => "even",
+                false => "odd",
+            }
+        };
+
+        let (root, updates) = test_root();
+        root.register(COUNT, ScopeId(1));
+        root.register(IS_EVEN, ScopeId(2));
+        assert_eq!(*root.register(LABEL, ScopeId(3)), "even");
+
+        root.set(COUNT.unique_id(), 2u32);
+        assert_eq!(take(&updates), vec![ScopeId(1)]);
+        // `IS_EVEN` didn't change, so `LABEL` isn't recomputed
+        assert_eq!(COMPUTED.load(Ordering::SeqCst), 1);
+
+        root.set(COUNT.unique_id(), 3u32);
+        assert_eq!(take(&updates), vec![ScopeId(1), ScopeId(2), ScopeId(3)]);
+        assert_eq!(*root.read(LABEL), "odd");
+        assert_eq!(COMPUTED.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn dynamic_dependencies() {
+        static USE_FIRST: Atom<bool> = |_| true;
+        static FIRST: Atom<&str> = |_| "first";
+        static SECOND: Atom<&str> = |_| "second";
+        static CHOSEN: Selector<&str> = |s| match *s.get(USE_FIRST) {
+            true => *s.get(FIRST),
+            false => *s.get(SECOND),
+        };
+
+        let (root, updates) = test_root();
+        assert_eq!(*root.register(CHOSEN, ScopeId(1)), "first");
+
+        // not read yet, so not a dependency
+        root.set(SECOND.unique_id(), "other second");
+        assert!(take(&updates).is_empty());
+
+        root.set(USE_FIRST.unique_id(), false);
+        assert_eq!(*root.read(CHOSEN), "other second");
+        assert_eq!(take(&updates), vec![ScopeId(1)]);
+
+        // no longer read, so no longer a dependency
+        root.set(FIRST.unique_id(), "other first");
+        assert!(take(&updates).is_empty());
+        assert_eq!(*root.read(CHOSEN), "other second");
+    }
+}
@@ -1 +1,84 @@
+use super::selector::{compute, derived_slot};
+use crate::{member_id, AtomId, AtomRoot, Readable, SelectorBuilder, Slot};
+use std::{hash::Hash, sync::Arc};
 
+/// A family of selectors, with one selector per key.
+///
+/// A member of the family is selected by pairing the family with a key, just like the members of
+/// an [`AtomFamily`](crate::AtomFamily). Every member tracks the atoms it reads on its own, and is
+/// removed from the [`AtomRoot`] when no component reads it anymore.
+///
+/// ```rust, ignore
+/// static TODO: AtomFamily<u32, String> = |_, id| format!("todo #{id}");
+/// static TODO_LENGTH: SelectorFamily<u32, usize> = |s, id| s.get((TODO, *id)).len();
+/// ```
+pub type SelectorFamily<K, V> = fn(SelectorBuilder<'_>, &K) -> V;
+
+impl<K, V> Readable<V> for (SelectorFamily<K, V>, K)
+where
+    K: Clone + Eq + Hash + 'static,
+    V: PartialEq + 'static,
+{
+    fn read(&self, root: AtomRoot) -> Option<V> {
+        root.take(self.clone())
+    }
+
+    /// The value of the selector when all atoms it reads have their initial values.
+    fn init(&self) -> V {
+        let (family, key) = self;
+        compute(|s| family(s, key), &AtomRoot::new(Arc::new(|_| {}))).0
+    }
+
+    fn unique_id(&self) -> AtomId {
+        member_id(self.0 as *const (), &self.1)
+    }
+
+    fn init_slot(&self, root: &AtomRoot) -> Slot {
+        let (family, key) = self.clone();
+        derived_slot(move |s| family(s, &key), root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AtomFamily;
+    use dioxus_core::ScopeId;
+    use std::sync::Mutex;
+
+    static TODO: AtomFamily<u32, String> = |_, id| format!("todo #{id}");
+    static TODO_LENGTH: SelectorFamily<u32, usize> = |s, id| s.get((TODO, *id)).len();
+
+    #[test]
+    fn selector_family() {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let root = AtomRoot::new({
+            let updates = updates.clone();
+            Arc::new(move |id| updates.lock().unwrap().push(id))
+        });
+
+        assert_eq!(*root.register((TODO_LENGTH, 1), ScopeId(1)), 7);
+        assert_eq!(*root.register((TODO_LENGTH, 42), ScopeId(2)), 8);
+
+        root.set((TODO, 42).unique_id(), String::from("done"));
+        assert_eq!(*updates.lock().unwrap(), vec![ScopeId(2)]);
+        assert_eq!(*root.read((TODO_LENGTH, 42)), 4);
+        assert_eq!(*root.read((TODO_LENGTH, 1)), 7);
+        assert_eq!((TODO_LENGTH, 42).read(root), Some(4));
+    }
+
+    #[test]
+    fn members_keep_their_dependencies_alive() {
+        let root = AtomRoot::new(Arc::new(|_| {}));
+        root.register((TODO_LENGTH, 1), ScopeId(1));
+        root.set((TODO, 1).unique_id(), String::from("done"));
+
+        // the todo is still read by its length
+        assert_eq!(*root.read((TODO, 1)), "done");
+
+        root.unsubscribe((TODO_LENGTH, 1).unique_id(), ScopeId(1));
+        let atoms = root.atoms.borrow();
+        assert!(!atoms.contains_key(&(TODO_LENGTH, 1).unique_id()));
+        assert!(!atoms.contains_key(&(TODO, 1).unique_id()));
+    }
+}
@@ -0,0 +1,192 @@
+use std::{any::Any, future::Future, pin::Pin, rc::Rc};
+
+use crate::{AtomId, AtomRoot, Readable};
+
+/// A function that runs whenever the value of an atom changes, outside of any component.
+///
+/// It is given the old value, if the atom had one, and the new value. Effects can be declared
+/// next to the atom they observe, and are added to the root with [`AtomRoot::add_effect`] or
+/// [`use_atom_effect`](crate::use_atom_effect).
+///
+/// ```rust, ignore
+/// static COUNT: Atom<u32> = |_| 0;
+/// static LOG_COUNT: AtomEffect<u32> = |old, new| log::info!("count: {:?} -> {}", old, new);
+/// ```
+pub type AtomEffect<V> = fn(Option<&V>, &V);
+
+/// A function that runs asynchronously whenever the value of an atom changes, for example to sync
+/// the value to a backend.
+///
+/// The returned future runs on the task system of the `VirtualDom`, like the futures of async
+/// atoms.
+///
+/// ```rust, ignore
+/// static TODOS: Atom<Vec<Todo>> = |_| Vec::new();
+/// static SYNC_TODOS: AsyncAtomEffect<Vec<Todo>> = |_, todos| Box::pin(async move {
+///     reqwest::Client::new().put("https://example.com/todos").json(&*todos).send().await.ok();
+/// });
+/// ```
+pub type AsyncAtomEffect<V> = fn(Option<Rc<V>>, Rc<V>) -> Pin<Box<dyn Future<Output = ()>>>;
+
+/// Identifies an effect added to an [`AtomRoot`], to remove it again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EffectId {
+    atom: AtomId,
+    id: usize,
+}
+
+/// An effect with the type of the observed value erased.
+pub(crate) type ErasedEffect = Rc<dyn Fn(&AtomRoot, Option<&Rc<dyn Any>>, &Rc<dyn Any>)>;
+
+impl AtomRoot {
+    /// Run `effect` whenever the value of `atom` changes.
+    ///
+    /// Effects run after the value is set and the subscribers are marked dirty, but before the
+    /// selectors derived from the atom are recomputed. Changes of selectors and async atoms run
+    /// effects as well, as do undone and redone writes.
+    ///
+    /// The effect is dropped together with the root, or when it is removed via
+    /// [`remove_effect`](AtomRoot::remove_effect).
+    pub fn add_effect<V: 'static>(
+        &self,
+        atom: impl Readable<V>,
+        effect: impl Fn(Option<&V>, &V) + 'static,
+    ) -> EffectId {
+        self.insert_effect(
+            atom.unique_id(),
+            Rc::new(move |_, old, new| {
+                let old = old.and_then(|old| old.downcast_ref());
+                if let Some(new) = new.downcast_ref() {
+                    effect(old, new);
+                }
+            }),
+        )
+    }
+
+    /// Run the future returned by `effect` whenever the value of `atom` changes.
+    ///
+    /// The futures run on the `VirtualDom` as soon as they are created, like the futures of async
+    /// atoms.
+    /// See [`add_effect`](AtomRoot::add_effect) for details.
+    pub fn add_async_effect<V: 'static, F: Future<Output = ()> + 'static>(
+        &self,
+        atom: impl Readable<V>,
+        effect: impl Fn(Option<Rc<V>>, Rc<V>) -> F + 'static,
+    ) -> EffectId {
+        self.insert_effect(
+            atom.unique_id(),
+            Rc::new(move |root, old, new| {
+                let old = old.and_then(|old| old.clone().downcast().ok());
+                if let Ok(new) = new.clone().downcast() {
+                    let future = effect(old, new);
+                    root.queue_task(Box::new(move |_| Box::pin(future)));
+                }
+            }),
+        )
+    }
+
+    /// Remove the effect with the id `id`, so that it no longer runs.
+    pub fn remove_effect(&self, id: EffectId) {
+        let mut effects = self.effects.borrow_mut();
+        if let Some(atom_effects) = effects.get_mut(&id.atom) {
+            atom_effects.retain(|(effect_id, _)| *effect_id != id.id);
+            if atom_effects.is_empty() {
+                effects.remove(&id.atom);
+            }
+        }
+    }
+
+    fn insert_effect(&self, atom: AtomId, effect: ErasedEffect) -> EffectId {
+        let id = self.next_effect.get();
+        self.next_effect.set(id + 1);
+
+        self.effects
+            .borrow_mut()
+            .entry(atom)
+            .or_default()
+            .push((id, effect));
+        EffectId { atom, id }
+    }
+
+    /// Run the effects of the atom with the id `id`, whose value changed from `old` to `new`.
+    ///
+    /// The atoms must not be borrowed, as effects may use the root.
+    pub(crate) fn run_effects(&self, id: AtomId, old: Option<&Rc<dyn Any>>, new: &Rc<dyn Any>) {
+        // effects may add or remove effects
+        let effects: Vec<_> = match self.effects.borrow().get(&id) {
+            Some(effects) => effects.iter().map(|(_, effect)| effect.clone()).collect(),
+            None => return,
+        };
+
+        for effect in effects {
+            effect(self, old, new);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Atom, Selector};
+    use futures::executor::block_on;
+    use std::{cell::RefCell, sync::Arc};
+
+    static COUNT: Atom<u32> = |_| 0;
+    static DOUBLED: Selector<u32> = |s| *s.get(COUNT) * 2;
+
+    #[test]
+    fn effects() {
+        let root = AtomRoot::new(Arc::new(|_| {}));
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let effect = root.add_effect(COUNT, {
+            let changes = changes.clone();
+            move |old: Option<&u32>, new: &u32| changes.borrow_mut().push((old.copied(), *new))
+        });
+        root.add_effect(DOUBLED, {
+            let changes = changes.clone();
+            move |old: Option<&u32>, new: &u32| changes.borrow_mut().push((old.copied(), *new))
+        });
+
+        root.read(DOUBLED);
+        root.set(COUNT.unique_id(), 1u32);
+        assert_eq!(*changes.borrow(), vec![(Some(0), 1), (Some(0), 2)]);
+
+        root.remove_effect(effect);
+        root.set(COUNT.unique_id(), 2u32);
+        assert_eq!(changes.borrow().last(), Some(&(Some(2), 4)));
+        assert_eq!(changes.borrow().len(), 3);
+    }
+
+    #[test]
+    fn async_effects() {
+        static SYNCED: Atom<u32> = |_| 0;
+        static SYNC: AsyncAtomEffect<u32> = |_, new| Box::pin(async move { assert_eq!(*new, 1) });
+
+        let root = Rc::new(AtomRoot::new(Arc::new(|_| {})));
+        root.add_async_effect(SYNCED, SYNC);
+        // setting an atom that wasn't read yet has no old value
+        root.set(SYNCED.unique_id(), 1u32);
+
+        let tasks = root.take_tasks();
+        assert_eq!(tasks.len(), 1);
+        for task in tasks {
+            block_on(task);
+        }
+    }
+
+    #[test]
+    fn dropped_with_root() {
+        let root = AtomRoot::new(Arc::new(|_| {}));
+        let state = Rc::new(());
+        root.add_effect(COUNT, {
+            let state = state.clone();
+            move |_, _: &u32| {
+                let _ = &state;
+            }
+        });
+
+        assert_eq!(Rc::strong_count(&state), 2);
+        drop(root);
+        assert_eq!(Rc::strong_count(&state), 1);
+    }
+}
@@ -0,0 +1,385 @@
+//! Record the writes to atoms, and travel back and forth between them.
+//!
+//! Recording is opt-in, and starts with [`AtomRoot::start_recording`]. Every write through
+//! [`AtomRoot::set`] (and the hooks built on top of it) is then recorded, together with the
+//! component it came from. The recorded writes can be undone and redone, which re-renders all
+//! components reading the affected atoms.
+//!
+//! Atoms are identified by their [`AtomId`], which is hard to read. Giving them a debug name via
+//! [`AtomRoot::set_debug_name`] makes the recorded writes include the name and the new value.
+
+use std::{
+    any::Any,
+    collections::HashSet,
+    fmt::{Debug, Formatter},
+    rc::Rc,
+    time::Duration,
+};
+
+use dioxus_core::ScopeId;
+use instant::Instant;
+
+use crate::{AtomId, AtomRoot, Readable};
+
+/// The debug name of an atom.
+pub(crate) struct DebugInfo {
+    name: &'static str,
+    /// Formats the value of the atom, if it has the right type.
+    format: fn(&dyn Any) -> Option<String>,
+}
+
+/// A recorded write to an atom.
+#[derive(Clone)]
+pub struct HistoryEntry {
+    /// The id of the atom that was written to.
+    pub atom: AtomId,
+    /// The debug name of the atom, if it has one.
+    pub name: Option<&'static str>,
+    /// The new value formatted via [`Debug`], if the atom has a debug name.
+    pub value: Option<String>,
+    /// The time of the write, relative to the start of the recording.
+    pub time: Duration,
+    /// The component the write came from, if it is known.
+    pub source: Option<ScopeId>,
+    old: Option<Rc<dyn Any>>,
+    new: Rc<dyn Any>,
+}
+
+impl HistoryEntry {
+    /// Get the debug name of the atom, or its address if it has no debug name.
+    pub fn atom_name(&self) -> String {
+        match self.name {
+            Some(name) => name.to_string(),
+            None => format!("{:?}", self.atom),
+        }
+    }
+}
+
+// [`Any`] (in `old` and `new`) doesn't implement [`Debug`]
+impl Debug for HistoryEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistoryEntry")
+            .field("atom", &self.atom)
+            .field("name", &self.name)
+            .field("value", &self.value)
+            .field("time", &self.time)
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The recorded writes of an [`AtomRoot`].
+pub(crate) struct Recorder {
+    /// All recorded writes, oldest first.
+    entries: Vec<HistoryEntry>,
+    /// The amount of entries that are currently applied.
+    position: usize,
+    max_entries: usize,
+    started: Instant,
+    /// The components that want to be updated when the history changes.
+    subscribers: HashSet<ScopeId>,
+}
+
+impl AtomRoot {
+    /// Give the atom `atom` a debug name.
+    ///
+    /// Recorded writes to the atom include the name, and the new value formatted via [`Debug`].
+    pub fn set_debug_name<V: Debug + 'static>(&self, atom: impl Readable<V>, name: &'static str) {
+        fn format<V: Debug + 'static>(value: &dyn Any) -> Option<String> {
+            value.downcast_ref::<V>().map(|v| format!("{:?}", v))
+        }
+
+        self.debug_info.borrow_mut().insert(
+            atom.unique_id(),
+            DebugInfo {
+                name,
+                format: format::<V>,
+            },
+        );
+    }
+
+    /// Get the debug name of the atom with the id `id`, if it has one.
+    pub fn debug_name(&self, id: AtomId) -> Option<&'static str> {
+        self.debug_info.borrow().get(&id).map(|info| info.name)
+    }
+
+    /// Start recording writes, keeping at most the latest `max_entries`.
+    ///
+    /// Does nothing if writes are already recorded.
+    pub fn start_recording(&self, max_entries: usize) {
+        let mut recorder = self.recorder.borrow_mut();
+        if recorder.is_none() {
+            *recorder = Some(Recorder {
+                entries: Vec::new(),
+                position: 0,
+                max_entries,
+                started: Instant::now(),
+                subscribers: HashSet::new(),
+            });
+        }
+    }
+
+    /// Stop recording writes, and drop the recorded writes.
+    pub fn stop_recording(&self) {
+        self.recorder.borrow_mut().take();
+    }
+
+    /// Check whether writes are recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.borrow().is_some()
+    }
+
+    /// Get all recorded writes, oldest first.
+    ///
+    /// The writes after the [`history_position`](AtomRoot::history_position) are undone, and
+    /// can be redone.
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        match &*self.recorder.borrow() {
+            Some(recorder) => recorder.entries.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the amount of recorded writes that are currently applied.
+    pub fn history_position(&self) -> usize {
+        match &*self.recorder.borrow() {
+            Some(recorder) => recorder.position,
+            None => 0,
+        }
+    }
+
+    /// Check whether there is a recorded write that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history_position() > 0
+    }
+
+    /// Check whether there is an undone write that can be redone.
+    pub fn can_redo(&self) -> bool {
+        match &*self.recorder.borrow() {
+            Some(recorder) => recorder.position < recorder.entries.len(),
+            None => false,
+        }
+    }
+
+    /// Undo the latest applied write.
+    pub fn undo(&self) {
+        if self.can_undo() {
+            self.travel_to(self.history_position() - 1);
+        }
+    }
+
+    /// Redo the earliest undone write.
+    pub fn redo(&self) {
+        if self.can_redo() {
+            self.travel_to(self.history_position() + 1);
+        }
+    }
+
+    /// Undo or redo writes until exactly the first `position` recorded writes are applied.
+    ///
+    /// All components reading the affected atoms are re-rendered. New writes while writes are
+    /// undone drop the undone writes.
+    pub fn travel_to(&self, position: usize) {
+        let writes = {
+            let mut recorder = self.recorder.borrow_mut();
+            let recorder = match &mut *recorder {
+                Some(recorder) => recorder,
+                None => return,
+            };
+
+            let position = position.min(recorder.entries.len());
+            let writes: Vec<_> = match position < recorder.position {
+                true => recorder.entries[position..recorder.position]
+                    .iter()
+                    .rev()
+                    .map(|entry| (entry.atom, entry.old.clone()))
+                    .collect(),
+                false => recorder.entries[recorder.position..position]
+                    .iter()
+                    .map(|entry| (entry.atom, Some(entry.new.clone())))
+                    .collect(),
+            };
+
+            recorder.position = position;
+            writes
+        };
+
+        for (id, value) in writes {
+            match value {
+                Some(value) => self.write(id, value),
+                // undoing the write that initialized an atom initializes it again
+                None => self.reset(id),
+            }
+        }
+        self.update_history_subscribers();
+    }
+
+    /// Update the component with the id `scope` whenever the recorded writes change.
+    pub fn subscribe_history(&self, scope: ScopeId) {
+        if let Some(recorder) = &mut *self.recorder.borrow_mut() {
+            recorder.subscribers.insert(scope);
+        }
+    }
+
+    /// Stop updating the component with the id `scope` when the recorded writes change.
+    pub fn unsubscribe_history(&self, scope: ScopeId) {
+        if let Some(recorder) = &mut *self.recorder.borrow_mut() {
+            recorder.subscribers.remove(&scope);
+        }
+    }
+
+    /// Record the write of `value` to the atom with the id `id`, if writes are recorded.
+    pub(crate) fn record(&self, id: AtomId, value: &Rc<dyn Any>, source: Option<ScopeId>) {
+        {
+            let mut recorder = self.recorder.borrow_mut();
+            let recorder = match &mut *recorder {
+                Some(recorder) => recorder,
+                None => return,
+            };
+
+            let debug_info = self.debug_info.borrow();
+            let debug_info = debug_info.get(&id);
+
+            // a new write replaces the undone writes
+            recorder.entries.truncate(recorder.position);
+            recorder.entries.push(HistoryEntry {
+                atom: id,
+                name: debug_info.map(|info| info.name),
+                value: debug_info.and_then(|info| (info.format)(value.as_ref())),
+                time: recorder.started.elapsed(),
+                source,
+                old: self.atoms.borrow().get(&id).map(|slot| slot.value.clone()),
+                new: value.clone(),
+            });
+
+            if recorder.entries.len() > recorder.max_entries {
+                recorder.entries.remove(0);
+            }
+            recorder.position = recorder.entries.len();
+        }
+
+        self.update_history_subscribers();
+    }
+
+    fn update_history_subscribers(&self) {
+        if let Some(recorder) = &*self.recorder.borrow() {
+            for scope in &recorder.subscribers {
+                (self.update_any)(*scope);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Atom, Selector};
+    use std::sync::{Arc, Mutex};
+
+    static COUNT: Atom<u32> = |_| 0;
+    static DOUBLED: Selector<u32> = |s| *s.get(COUNT) * 2;
+
+    fn test_root() -> (AtomRoot, Arc<Mutex<Vec<ScopeId>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let root = AtomRoot::new({
+            let updates = updates.clone();
+            Arc::new(move |id| updates.lock().unwrap().push(id))
+        });
+        (root, updates)
+    }
+
+    #[test]
+    fn record() {
+        let (root, _) = test_root();
+        root.read(COUNT);
+        root.set(COUNT.unique_id(), 1u32);
+        assert!(root.history().is_empty());
+
+        root.start_recording(10);
+        root.set_debug_name(COUNT, "COUNT");
+        root.set_from(COUNT.unique_id(), 2u32, ScopeId(1));
+        root.set(COUNT.unique_id(), 3u32);
+
+        let history = root.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].name, Some("COUNT"));
+        assert_eq!(history[0].value.as_deref(), Some("2"));
+        assert_eq!(history[0].source, Some(ScopeId(1)));
+        assert_eq!(history[1].atom_name(), "COUNT");
+        assert_eq!(history[1].source, None);
+        assert!(history[0].time <= history[1].time);
+
+        root.stop_recording();
+        assert!(root.history().is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let (root, updates) = test_root();
+        root.start_recording(10);
+        root.register(DOUBLED, ScopeId(1));
+        root.set(COUNT.unique_id(), 1u32);
+        root.set(COUNT.unique_id(), 2u32);
+        updates.lock().unwrap().clear();
+
+        root.undo();
+        assert_eq!(*root.read(COUNT), 1);
+        assert_eq!(root.history_position(), 1);
+        // selectors follow, and their subscribers are re-rendered
+        assert_eq!(*root.read(DOUBLED), 2);
+        assert_eq!(*updates.lock().unwrap(), vec![ScopeId(1)]);
+
+        root.travel_to(0);
+        assert_eq!(*root.read(COUNT), 0);
+        assert!(!root.can_undo());
+
+        root.redo();
+        root.redo();
+        assert_eq!(*root.read(COUNT), 2);
+        assert!(!root.can_redo());
+
+        // new writes drop the undone writes
+        root.undo();
+        root.set(COUNT.unique_id(), 5u32);
+        assert_eq!(root.history().len(), 2);
+        assert!(!root.can_redo());
+    }
+
+    #[test]
+    fn undo_initializing_write() {
+        let (root, updates) = test_root();
+        root.start_recording(10);
+        root.set(COUNT.unique_id(), 1u32);
+        root.register(COUNT, ScopeId(1));
+        root.register(DOUBLED, ScopeId(2));
+        updates.lock().unwrap().clear();
+
+        root.undo();
+        assert_eq!(root.history_position(), 0);
+        assert_eq!(*root.read(COUNT), 0);
+        assert_eq!(*root.read(DOUBLED), 0);
+        assert!(updates.lock().unwrap().contains(&ScopeId(1)));
+        assert!(updates.lock().unwrap().contains(&ScopeId(2)));
+
+        // the selector still follows the atom
+        root.redo();
+        assert_eq!(*root.read(COUNT), 1);
+        assert_eq!(*root.read(DOUBLED), 2);
+    }
+
+    #[test]
+    fn max_entries() {
+        let (root, _) = test_root();
+        root.start_recording(2);
+        root.subscribe_history(ScopeId(1));
+        root.read(COUNT);
+
+        for i in 1..=3u32 {
+            root.set(COUNT.unique_id(), i);
+        }
+
+        assert_eq!(root.history().len(), 2);
+        root.travel_to(0);
+        assert_eq!(*root.read(COUNT), 1);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{use_atom_root, AtomRoot, EffectId, Readable};
+use dioxus_core::ScopeState;
+use std::rc::Rc;
+
+/// Run `effect` whenever the value of `atom` changes, for as long as the component is mounted.
+///
+/// The effect is added on the first render, later renders don't replace it. See
+/// [`AtomRoot::add_effect`] for details.
+///
+/// ```rust, ignore
+/// static COUNT: Atom<u32> = |_| 0;
+/// static LOG_COUNT: AtomEffect<u32> = |old, new| log::info!("count: {:?} -> {}", old, new);
+///
+/// fn App(cx: Scope) -> Element {
+///     use_atom_effect(&cx, COUNT, LOG_COUNT);
+///     cx.render(rsx!{ Counter {} })
+/// }
+/// ```
+pub fn use_atom_effect<V: 'static>(
+    cx: &ScopeState,
+    atom: impl Readable<V>,
+    effect: impl Fn(Option<&V>, &V) + 'static,
+) {
+    let root = use_atom_root(cx);
+    cx.use_hook(|| UseAtomEffect {
+        id: root.add_effect(atom, effect),
+        root: root.clone(),
+    });
+}
+
+/// Run the future returned by `effect` whenever the value of `atom` changes, for as long as the
+/// component is mounted.
+///
+/// See [`AtomRoot::add_async_effect`] for details.
+pub fn use_async_atom_effect<V, F>(
+    cx: &ScopeState,
+    atom: impl Readable<V>,
+    effect: impl Fn(Option<Rc<V>>, Rc<V>) -> F + 'static,
+) where
+    V: 'static,
+    F: std::future::Future<Output = ()> + 'static,
+{
+    let root = use_atom_root(cx);
+    cx.use_hook(|| UseAtomEffect {
+        id: root.add_async_effect(atom, effect),
+        root: root.clone(),
+    });
+}
+
+struct UseAtomEffect {
+    root: Rc<AtomRoot>,
+    id: EffectId,
+}
+
+impl Drop for UseAtomEffect {
+    fn drop(&mut self) {
+        self.root.remove_effect(self.id)
+    }
+}
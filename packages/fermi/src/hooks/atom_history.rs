@@ -0,0 +1,84 @@
+use crate::{history::HistoryEntry, use_atom_root, AtomRoot};
+use dioxus_core::{ScopeId, ScopeState};
+use std::rc::Rc;
+
+/// Access the recorded writes to atoms, for example to build a devtools UI.
+///
+/// The component is re-rendered whenever a write is recorded, undone or redone. Writes are only
+/// recorded after [`AtomRoot::start_recording`] was called.
+///
+/// ```rust, ignore
+/// fn Devtools(cx: Scope) -> Element {
+///     let history = use_atom_history(&cx);
+///
+///     cx.render(rsx!{
+///         button { onclick: move |_| history.undo(), "undo" }
+///         button { onclick: move |_| history.redo(), "redo" }
+///         ul {
+///             history.entries().into_iter().map(|entry| rsx!{
+///                 li { "{entry.atom_name()}: {entry.value:?}" }
+///             })
+///         }
+///     })
+/// }
+/// ```
+pub fn use_atom_history(cx: &ScopeState) -> &UseAtomHistory {
+    let root = use_atom_root(cx);
+
+    let history = cx.use_hook(|| UseAtomHistory {
+        root: root.clone(),
+        scope_id: cx.scope_id(),
+    });
+
+    // recording may start after the first render
+    history.root.subscribe_history(history.scope_id);
+    history
+}
+
+pub struct UseAtomHistory {
+    root: Rc<AtomRoot>,
+    scope_id: ScopeId,
+}
+
+impl Drop for UseAtomHistory {
+    fn drop(&mut self) {
+        self.root.unsubscribe_history(self.scope_id)
+    }
+}
+
+impl UseAtomHistory {
+    /// Get all recorded writes, oldest first.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.root.history()
+    }
+
+    /// Get the amount of recorded writes that are currently applied.
+    pub fn position(&self) -> usize {
+        self.root.history_position()
+    }
+
+    /// Check whether there is a recorded write that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.root.can_undo()
+    }
+
+    /// Check whether there is an undone write that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.root.can_redo()
+    }
+
+    /// Undo the latest applied write.
+    pub fn undo(&self) {
+        self.root.undo()
+    }
+
+    /// Redo the earliest undone write.
+    pub fn redo(&self) {
+        self.root.redo()
+    }
+
+    /// Undo or redo writes until exactly the first `position` recorded writes are applied.
+    pub fn travel_to(&self, position: usize) {
+        self.root.travel_to(position)
+    }
+}
@@ -81,6 +81,6 @@ impl<T: 'static> UseAtomRef<T> {
 
     pub fn set(&self, new: T) {
         self.root.force_update(self.ptr);
-        self.root.set(self.ptr, new);
+        self.root.set_from(self.ptr, new, self.scope_id);
     }
 }
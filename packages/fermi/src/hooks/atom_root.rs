@@ -4,8 +4,19 @@ use std::rc::Rc;
 
 // Returns the atom root, initiaizing it at the root of the app if it does not exist.
 pub fn use_atom_root(cx: &ScopeState) -> &Rc<AtomRoot> {
-    cx.use_hook(|| match cx.consume_context::<Rc<AtomRoot>>() {
+    let root = cx.use_hook(|| match cx.consume_context::<Rc<AtomRoot>>() {
         Some(root) => root,
         None => cx.provide_root_context(Rc::new(AtomRoot::new(cx.schedule_update_any()))),
-    })
+    });
+    spawn_driver(cx, root);
+    root
+}
+
+// Spawns the task running the futures of async atoms and the saves of persisted atoms, once per root.
+//
+// It is spawned on the root scope, so it keeps running when this scope is dropped.
+pub(crate) fn spawn_driver(cx: &ScopeState, root: &Rc<AtomRoot>) {
+    if let Some(driver) = root.driver() {
+        cx.spawn_forever(driver);
+    }
 }
@@ -0,0 +1,26 @@
+use crate::{use_atom_root, Writable};
+use dioxus_core::ScopeState;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Persist the value of an atom under `key`.
+///
+/// See [`AtomRoot::persist`](crate::AtomRoot::persist) for details.
+///
+/// ```rust, ignore
+/// static THEME: Atom<Theme> = |_| Theme::Light;
+///
+/// fn App(cx: Scope) -> Element {
+///     use_persist(&cx, THEME, "theme");
+///     let root = use_atom_root(&cx);
+///     cx.use_hook(|| root.set_storage(FileStorage::new("atoms.json"), Duration::from_secs(1)));
+///
+///     cx.render(rsx!{ ThemeToggle {} })
+/// }
+/// ```
+pub fn use_persist<V>(cx: &ScopeState, atom: impl Writable<V>, key: &'static str)
+where
+    V: Serialize + DeserializeOwned + 'static,
+{
+    let root = use_atom_root(cx);
+    cx.use_hook(|| root.persist(atom, key));
+}
@@ -29,6 +29,13 @@ pub fn use_read_rc<V: 'static>(cx: &ScopeState, f: impl Readable<V>) -> &Rc<V> {
         id: f.unique_id(),
     });
 
+    // components may read a different member of a family in every render
+    let id = f.unique_id();
+    if id != inner.id {
+        inner.root.unsubscribe(inner.id, inner.scope_id);
+        inner.id = id;
+    }
+
     let value = inner.root.register(f, cx.scope_id());
 
     inner.value = Some(value);
@@ -0,0 +1,18 @@
+use crate::{use_atom_root, Readable};
+use dioxus_core::ScopeState;
+use std::rc::Rc;
+
+/// Get a function that refreshes an async atom or a selector.
+///
+/// Refreshing an async atom starts a new future. See [`AtomRoot::refresh`] for details.
+///
+/// [`AtomRoot::refresh`]: crate::AtomRoot::refresh
+pub fn use_refresh<T: 'static>(cx: &ScopeState, f: impl Readable<T>) -> &Rc<dyn Fn()> {
+    let root = use_atom_root(cx);
+    cx.use_hook(|| {
+        let id = f.unique_id();
+        let root = root.clone();
+        root.initialize(f);
+        Rc::new(move || root.refresh(id)) as Rc<dyn Fn()>
+    })
+}
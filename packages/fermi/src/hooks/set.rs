@@ -1,13 +1,59 @@
 use crate::{use_atom_root, Writable};
 use dioxus_core::ScopeState;
-use std::rc::Rc;
+use std::{cell::Cell, rc::Rc};
 
 pub fn use_set<T: 'static>(cx: &ScopeState, f: impl Writable<T>) -> &Rc<dyn Fn(T)> {
     let root = use_atom_root(cx);
-    cx.use_hook(|| {
-        let id = f.unique_id();
+    let (id, set) = cx.use_hook(|| {
+        let id = Rc::new(Cell::new(f.unique_id()));
+        let scope_id = cx.scope_id();
         let root = root.clone();
-        root.initialize(f);
-        Rc::new(move |new| root.set(id, new)) as Rc<dyn Fn(T)>
-    })
+        let set = {
+            let id = id.clone();
+            Rc::new(move |new| root.set_from(id.get(), new, scope_id)) as Rc<dyn Fn(T)>
+        };
+        (id, set)
+    });
+
+    // components may set a different member of a family in every render
+    id.set(f.unique_id());
+    root.initialize(f);
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AtomFamily, AtomRoot};
+    use dioxus_core::{prelude::*, SchedulerMsg};
+    use std::cell::RefCell;
+
+    static TODO: AtomFamily<u32, String> = |_, id| format!("todo #{id}");
+
+    #[test]
+    fn test_use_set() {
+        // the key of the todo, and the setter of the latest render
+        type Todo = Rc<(Cell<u32>, RefCell<Option<Rc<dyn Fn(String)>>>)>;
+
+        fn app(cx: Scope<Todo>) -> Element {
+            let set = use_set(&cx, (TODO, cx.props.0.get()));
+            cx.props.1.replace(Some(set.clone()));
+            None
+        }
+
+        let todo = Todo::default();
+        todo.0.set(1);
+        let mut dom = VirtualDom::new_with_props(app, todo.clone());
+        dom.rebuild();
+        let root = dom.base_scope().consume_context::<Rc<AtomRoot>>().unwrap();
+
+        todo.0.set(2);
+        dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+        dom.work_with_deadline(|| false);
+
+        let set = todo.1.borrow().clone().unwrap();
+        set(String::from("done"));
+        assert_eq!(*root.read((TODO, 2)), "done");
+        assert_eq!(*root.read((TODO, 1)), "todo #1");
+    }
 }
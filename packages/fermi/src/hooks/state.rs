@@ -40,6 +40,13 @@ pub fn use_atom_state<T: 'static>(cx: &ScopeState, f: impl Writable<T>) -> &Atom
         id: f.unique_id(),
     });
 
+    // components may use a different member of a family in every render
+    let id = f.unique_id();
+    if id != inner.id {
+        inner.root.unsubscribe(inner.id, inner.scope_id);
+        inner.id = id;
+    }
+
     inner.value = Some(inner.root.register(f, cx.scope_id()));
 
     inner
@@ -61,7 +68,7 @@ impl<V> Drop for AtomState<V> {
 impl<T: 'static> AtomState<T> {
     /// Set the state to a new value.
     pub fn set(&self, new: T) {
-        self.root.set(self.id, new)
+        self.root.set_from(self.id, new, self.scope_id)
     }
 
     /// Get the current value of the state by cloning its container Rc.
@@ -114,7 +121,8 @@ impl<T: 'static> AtomState<T> {
     pub fn setter(&self) -> Rc<dyn Fn(T)> {
         let root = self.root.clone();
         let id = self.id;
-        Rc::new(move |new_val| root.set(id, new_val))
+        let scope_id = self.scope_id;
+        Rc::new(move |new_val| root.set_from(id, new_val, scope_id))
     }
 
     /// Set the state to a new value, using the current state value as a reference.
@@ -145,10 +153,14 @@ impl<T: 'static> AtomState<T> {
     /// }
     /// ```
     pub fn modify(&self, f: impl FnOnce(&T) -> T) {
-        self.root.clone().set(self.id, {
-            let current = self.value.as_ref().unwrap();
-            f(current.as_ref())
-        });
+        self.root.clone().set_from(
+            self.id,
+            {
+                let current = self.value.as_ref().unwrap();
+                f(current.as_ref())
+            },
+            self.scope_id,
+        );
     }
 
     /// Get the value of the state when this handle was created.
@@ -4,19 +4,30 @@ pub mod prelude {
     pub use crate::*;
 }
 
+mod effects;
+pub mod history;
+#[cfg(feature = "persist")]
+pub mod persist;
 mod root;
 
+use std::rc::Rc;
+
 pub use atoms::*;
+pub use effects::*;
 pub use hooks::*;
 pub use root::*;
 
 mod atoms {
+    mod asyncatom;
+    mod asyncselector;
     mod atom;
     mod atomfamily;
     mod atomref;
     mod selector;
     mod selectorfamily;
 
+    pub use asyncatom::*;
+    pub use asyncselector::*;
     pub use atom::*;
     pub use atomfamily::*;
     pub use atomref::*;
@@ -25,16 +36,26 @@ mod atoms {
 }
 
 pub mod hooks {
+    mod atom_effect;
+    mod atom_history;
     mod atom_ref;
     mod atom_root;
     mod init_atom_root;
+    #[cfg(feature = "persist")]
+    mod persist;
     mod read;
+    mod refresh;
     mod set;
     mod state;
+    pub use atom_effect::*;
+    pub use atom_history::*;
     pub use atom_ref::*;
     pub use atom_root::*;
     pub use init_atom_root::*;
+    #[cfg(feature = "persist")]
+    pub use persist::*;
     pub use read::*;
+    pub use refresh::*;
     pub use set::*;
     pub use state::*;
 }
@@ -47,6 +68,18 @@ pub trait Readable<V> {
     fn read(&self, root: AtomRoot) -> Option<V>;
     fn init(&self) -> V;
     fn unique_id(&self) -> AtomId;
+
+    /// Create the slot that holds the value in `root`.
+    ///
+    /// Most atoms start out with their [`init`](Readable::init) value. Atoms that are derived from
+    /// other atoms, like [`Selector`]s, compute their value from `root` instead.
+    fn init_slot(&self, root: &AtomRoot) -> Slot
+    where
+        V: 'static,
+    {
+        let _ = root;
+        Slot::new(Rc::new(self.init()))
+    }
 }
 
 /// All Atoms are `Writable` - they support writing their value.
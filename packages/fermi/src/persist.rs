@@ -0,0 +1,475 @@
+//! Save the values of atoms, and restore them later.
+//!
+//! Atoms are persisted under a key of their own, which stays the same across restarts of the app,
+//! unlike their [`AtomId`]. Only atoms that are registered via [`AtomRoot::persist`] (or
+//! [`use_persist`](crate::use_persist)) are persisted.
+//!
+//! This allows desktop apps to keep state like user preferences across restarts, and server side
+//! rendered apps to transfer the values of their atoms to the client.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+    time::Duration,
+};
+
+use futures_timer::Delay;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{AtomId, AtomRoot, Writable};
+
+/// The values of persisted atoms, by their key.
+///
+/// Snapshots are serializable, so a snapshot taken on the server can be sent to the client and
+/// restored there.
+pub type Snapshot = BTreeMap<String, Value>;
+
+/// Where an [`AtomRoot`] saves its [`Snapshot`]s to.
+///
+/// Implementations handle their errors themselves, usually by logging them.
+pub trait Storage {
+    /// Load the last saved data, if there is any.
+    fn load(&self) -> Option<String>;
+
+    /// Save `data`, replacing previously saved data.
+    fn save(&self, data: &str);
+}
+
+/// A [`Storage`] that keeps the data in memory.
+///
+/// Clones share the same data, which makes it useful for tests.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    data: Rc<RefCell<Option<String>>>,
+}
+
+impl MemoryStorage {
+    /// Create a new, empty [`MemoryStorage`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the saved data.
+    pub fn data(&self) -> Option<String> {
+        self.data.borrow().clone()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self) -> Option<String> {
+        self.data()
+    }
+
+    fn save(&self, data: &str) {
+        *self.data.borrow_mut() = Some(data.to_string());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use file::FileStorage;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file {
+    use std::{
+        fs,
+        io::{self, ErrorKind},
+        path::{Path, PathBuf},
+    };
+
+    use super::Storage;
+
+    /// A [`Storage`] that saves the data to a file.
+    #[derive(Clone, Debug)]
+    pub struct FileStorage {
+        file: PathBuf,
+    }
+
+    impl FileStorage {
+        /// Create a new [`FileStorage`] that saves to `file`.
+        pub fn new(file: impl Into<PathBuf>) -> Self {
+            Self { file: file.into() }
+        }
+
+        /// Get the path of the file the [`FileStorage`] saves to.
+        pub fn file(&self) -> &Path {
+            &self.file
+        }
+    }
+
+    impl Storage for FileStorage {
+        fn load(&self) -> Option<String> {
+            match fs::read_to_string(&self.file) {
+                Ok(data) => Some(data),
+                Err(e) if e.kind() == ErrorKind::NotFound => None,
+                Err(e) => {
+                    log::error!("failed to load atoms from {:?}: {}", self.file, e);
+                    None
+                }
+            }
+        }
+
+        fn save(&self, data: &str) {
+            if let Err(e) = write(&self.file, data) {
+                log::error!("failed to save atoms to {:?}: {}", self.file, e);
+            }
+        }
+    }
+
+    /// Write `data` to a temporary file first, which then replaces `file`.
+    ///
+    /// This ensures `file` is never left in a partially written state.
+    fn write(file: &Path, data: &str) -> io::Result<()> {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut temporary = file.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, data)?;
+        fs::rename(&temporary, file)
+    }
+}
+
+#[cfg(feature = "web")]
+pub use web::LocalStorage;
+
+#[cfg(feature = "web")]
+mod web {
+    use super::Storage;
+
+    /// A [`Storage`] that saves the data in the `localStorage` of the browser.
+    #[derive(Clone, Debug)]
+    pub struct LocalStorage {
+        key: String,
+    }
+
+    impl LocalStorage {
+        /// Create a new [`LocalStorage`] that saves under `key`.
+        pub fn new(key: impl Into<String>) -> Self {
+            Self { key: key.into() }
+        }
+
+        fn storage() -> Option<web_sys::Storage> {
+            let storage = web_sys::window().and_then(|w| w.local_storage().ok().flatten());
+            if storage.is_none() {
+                log::error!("localStorage is not available");
+            }
+            storage
+        }
+    }
+
+    impl Storage for LocalStorage {
+        fn load(&self) -> Option<String> {
+            Self::storage()?.get_item(&self.key).ok().flatten()
+        }
+
+        fn save(&self, data: &str) {
+            if let Some(storage) = Self::storage() {
+                if storage.set_item(&self.key, data).is_err() {
+                    log::error!("failed to save atoms to localStorage");
+                }
+            }
+        }
+    }
+}
+
+/// Serializes the value of an atom, if it has the right type.
+type SerializeFn = fn(&dyn Any) -> Option<Value>;
+
+/// Sets the value of an atom to a serialized value.
+type RestoreFn = Rc<dyn Fn(&AtomRoot, Value)>;
+
+/// An atom that is persisted.
+struct PersistedAtom {
+    id: AtomId,
+    serialize: SerializeFn,
+    restore: RestoreFn,
+}
+
+/// The persistence state of an [`AtomRoot`].
+#[derive(Default)]
+pub(crate) struct Persistence {
+    atoms: BTreeMap<&'static str, PersistedAtom>,
+    keys: HashMap<AtomId, &'static str>,
+    /// Restored values of atoms that aren't registered yet.
+    pending: Snapshot,
+    storage: Option<(Rc<dyn Storage>, Duration)>,
+    save_queued: bool,
+}
+
+impl Persistence {
+    /// Check whether the atom with the id `id` is persisted.
+    pub(crate) fn is_persisted(&self, id: AtomId) -> bool {
+        self.keys.contains_key(&id)
+    }
+}
+
+impl AtomRoot {
+    /// Persist the value of `atom` under `key`.
+    ///
+    /// If a value for `key` was restored before, the atom is set to it.
+    pub fn persist<V>(&self, atom: impl Writable<V>, key: &'static str)
+    where
+        V: Serialize + DeserializeOwned + 'static,
+    {
+        fn serialize<V: Serialize + 'static>(value: &dyn Any) -> Option<Value> {
+            serde_json::to_value(value.downcast_ref::<V>()?)
+                .map_err(|e| log::error!("failed to serialize atom: {}", e))
+                .ok()
+        }
+
+        let id = atom.unique_id();
+        let restore = Rc::new(move |root: &AtomRoot, value: Value| {
+            match serde_json::from_value::<V>(value) {
+                Ok(value) => root.set(id, value),
+                Err(e) => log::error!("failed to restore atom {:?}: {}", key, e),
+            }
+        });
+
+        let pending = {
+            let mut persistence = self.persistence.borrow_mut();
+            if let Some(previous) = persistence.atoms.get(key).map(|atom| atom.id) {
+                if previous == id {
+                    return;
+                }
+                log::error!("atom key {:?} is used by multiple atoms", key);
+                persistence.keys.remove(&previous);
+            }
+
+            persistence.keys.insert(id, key);
+            persistence.atoms.insert(
+                key,
+                PersistedAtom {
+                    id,
+                    serialize: serialize::<V>,
+                    restore: restore.clone(),
+                },
+            );
+            persistence.pending.remove(key)
+        };
+
+        if let Some(value) = pending {
+            restore(self, value);
+        }
+    }
+
+    /// Take a [`Snapshot`] of all persisted atoms.
+    ///
+    /// Restored values of atoms that aren't registered yet are included as well.
+    pub fn snapshot(&self) -> Snapshot {
+        let persistence = self.persistence.borrow();
+        let atoms = self.atoms.borrow();
+
+        let mut snapshot = persistence.pending.clone();
+        for (key, atom) in &persistence.atoms {
+            if let Some(value) = atoms
+                .get(&atom.id)
+                .and_then(|slot| (atom.serialize)(slot.value.as_ref()))
+            {
+                snapshot.insert(key.to_string(), value);
+            }
+        }
+        snapshot
+    }
+
+    /// Restore the persisted atoms from `snapshot`.
+    ///
+    /// Values of atoms that aren't registered yet are kept until they are registered.
+    pub fn restore(&self, snapshot: &Snapshot) {
+        for (key, value) in snapshot {
+            let restore = {
+                let mut persistence = self.persistence.borrow_mut();
+                match persistence.atoms.get(key.as_str()) {
+                    Some(atom) => atom.restore.clone(),
+                    None => {
+                        persistence.pending.insert(key.clone(), value.clone());
+                        continue;
+                    }
+                }
+            };
+
+            restore(self, value.clone());
+        }
+    }
+
+    /// Restore the persisted atoms from `storage`, and save them to it whenever they change.
+    ///
+    /// Saving is debounced: once a persisted atom changes, it is saved after `debounce` has passed,
+    /// including all other changes made in the meantime. The saves run on the `VirtualDom`, just
+    /// like the futures of async atoms.
+    pub fn set_storage(&self, storage: impl Storage + 'static, debounce: Duration) {
+        let snapshot = storage.load().and_then(|data| {
+            serde_json::from_str(&data)
+                .map_err(|e| log::error!("failed to load atoms: {}", e))
+                .ok()
+        });
+
+        self.persistence.borrow_mut().storage = Some((Rc::new(storage), debounce));
+
+        if let Some(snapshot) = snapshot {
+            self.restore(&snapshot);
+        }
+    }
+
+    /// Save all persisted atoms to the storage immediately.
+    ///
+    /// Does nothing if no storage is set.
+    pub fn save(&self) {
+        let storage = match &self.persistence.borrow().storage {
+            Some((storage, _)) => storage.clone(),
+            None => return,
+        };
+
+        match serde_json::to_string(&self.snapshot()) {
+            Ok(data) => storage.save(&data),
+            Err(e) => log::error!("failed to save atoms: {}", e),
+        }
+    }
+
+    /// Queue a save if the atom with the id `id` is persisted, and no save is queued yet.
+    pub(crate) fn persist_changed(&self, id: AtomId) {
+        let debounce = {
+            let mut persistence = self.persistence.borrow_mut();
+            let debounce = match &persistence.storage {
+                Some((_, debounce)) if persistence.keys.contains_key(&id) => *debounce,
+                _ => return,
+            };
+            if persistence.save_queued {
+                return;
+            }
+            persistence.save_queued = true;
+            debounce
+        };
+
+        self.queue_task(Box::new(move |root| {
+            Box::pin(async move {
+                Delay::new(debounce).await;
+                root.persistence.borrow_mut().save_queued = false;
+                root.save();
+            })
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Atom, Readable};
+    use futures::executor::block_on;
+    use std::sync::Arc;
+
+    static THEME: Atom<String> = |_| String::from("light");
+    static FONT_SIZE: Atom<u32> = |_| 12;
+
+    fn test_root() -> Rc<AtomRoot> {
+        Rc::new(AtomRoot::new(Arc::new(|_| {})))
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let root = test_root();
+        root.persist(THEME, "theme");
+        root.persist(FONT_SIZE, "font_size");
+        root.read(THEME);
+        root.set(FONT_SIZE.unique_id(), 16u32);
+
+        let snapshot = root.snapshot();
+        assert_eq!(
+            serde_json::to_string(&snapshot).unwrap(),
+            r#"{"font_size":16,"theme":"light"}"#
+        );
+
+        let restored = test_root();
+        restored.restore(&snapshot);
+        // values of atoms that aren't registered yet are kept
+        assert_eq!(restored.snapshot(), snapshot);
+
+        restored.persist(FONT_SIZE, "font_size");
+        assert_eq!(*restored.read(FONT_SIZE), 16);
+        assert_eq!(restored.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn invalid_values_are_skipped() {
+        let root = test_root();
+        root.persist(FONT_SIZE, "font_size");
+
+        let mut snapshot = Snapshot::new();
+        snapshot.insert(String::from("font_size"), Value::from("large"));
+        root.restore(&snapshot);
+
+        assert_eq!(*root.read(FONT_SIZE), 12);
+    }
+
+    #[test]
+    fn debounced_save() {
+        let storage = MemoryStorage::new();
+        storage.save(r#"{"theme":"dark"}"#);
+
+        let root = test_root();
+        root.persist(THEME, "theme");
+        root.persist(FONT_SIZE, "font_size");
+        root.set_storage(storage.clone(), Duration::from_millis(1));
+        assert_eq!(*root.read(THEME), "dark");
+        // restoring queues a save as well
+        for task in root.take_tasks() {
+            block_on(task);
+        }
+
+        root.set(FONT_SIZE.unique_id(), 14u32);
+        root.set(FONT_SIZE.unique_id(), 16u32);
+        let tasks = root.take_tasks();
+        assert_eq!(tasks.len(), 1);
+
+        assert_eq!(storage.data().unwrap(), r#"{"theme":"dark"}"#);
+        for task in tasks {
+            block_on(task);
+        }
+        assert_eq!(
+            storage.data().unwrap(),
+            r#"{"font_size":16,"theme":"dark"}"#
+        );
+    }
+
+    #[test]
+    fn save_without_render() {
+        use crate::use_atom_root;
+        use dioxus_core::prelude::*;
+        use futures::future::select;
+
+        fn app(cx: Scope<MemoryStorage>) -> Element {
+            let root = use_atom_root(&cx);
+            cx.use_hook(|| {
+                root.persist(FONT_SIZE, "font_size");
+                root.set_storage(cx.props.clone(), Duration::ZERO);
+            });
+            None
+        }
+
+        let storage = MemoryStorage::new();
+        let mut dom = VirtualDom::new_with_props(app, storage.clone());
+        dom.rebuild();
+        let root = dom.base_scope().consume_context::<Rc<AtomRoot>>().unwrap();
+
+        // nothing re-renders, the save runs on the VirtualDom anyway
+        root.set(FONT_SIZE.unique_id(), 16u32);
+        block_on(select(
+            Box::pin(dom.wait_for_work()),
+            Delay::new(Duration::from_millis(50)),
+        ));
+        assert_eq!(storage.data().unwrap(), r#"{"font_size":16}"#);
+    }
+
+    #[test]
+    fn file_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorage::new(dir.path().join("nested").join("atoms.json"));
+        assert_eq!(storage.load(), None);
+
+        storage.save("data");
+        assert_eq!(storage.load(), Some(String::from("data")));
+    }
+}
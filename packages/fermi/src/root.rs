@@ -1,4 +1,14 @@
-use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc, sync::Arc};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
 
 use dioxus_core::ScopeId;
 use im_rc::HashSet;
@@ -7,14 +17,183 @@ use crate::Readable;
 
 pub type AtomId = *const ();
 
+/// The ids of the members of atom families.
+#[derive(Default)]
+struct Members {
+    /// The ids of the members of every family by their key, by the address of the family.
+    keys: HashMap<AtomId, Box<dyn Any>>,
+    /// The members, by their id.
+    ids: HashMap<AtomId, Member>,
+    /// The number of ids handed out so far.
+    next: usize,
+}
+
+/// A member of an atom family.
+struct Member {
+    family: AtomId,
+    key: Box<dyn Any>,
+    /// Removes `key` from the keys of `family`.
+    forget: fn(&mut dyn Any, &dyn Any),
+    /// The number of slots holding the value of the member, in all roots of the thread.
+    slots: usize,
+}
+
+thread_local! {
+    static MEMBERS: RefCell<Members> = RefCell::new(Members::default());
+}
+
+/// Get the id of the member with the key `key` of the family at `family`.
+///
+/// Keys are compared by equality, so members with different keys never share a value. The key is
+/// forgotten once no root holds a slot for the member anymore. Ids are taken from the top of the
+/// address space, where no atom lives, and are never handed out twice.
+pub(crate) fn member_id<K>(family: AtomId, key: &K) -> AtomId
+where
+    K: Clone + Eq + Hash + 'static,
+{
+    fn forget<K: Eq + Hash + 'static>(keys: &mut dyn Any, key: &dyn Any) {
+        if let (Some(keys), Some(key)) = (
+            keys.downcast_mut::<HashMap<K, AtomId>>(),
+            key.downcast_ref::<K>(),
+        ) {
+            keys.remove(key);
+        }
+    }
+
+    MEMBERS.with(|members| {
+        let members = &mut *members.borrow_mut();
+        let keys = members
+            .keys
+            .entry(family)
+            .or_insert_with(|| Box::new(HashMap::<K, AtomId>::new()))
+            .downcast_mut::<HashMap<K, AtomId>>()
+            .expect("the keys of a family have a single type");
+
+        if let Some(id) = keys.get(key) {
+            return *id;
+        }
+        let id = (usize::MAX - members.next) as AtomId;
+        members.next += 1;
+        keys.insert(key.clone(), id);
+        members.ids.insert(
+            id,
+            Member {
+                family,
+                key: Box::new(key.clone()),
+                forget: forget::<K>,
+                slots: 0,
+            },
+        );
+        id
+    })
+}
+
+/// Check whether the id `id` belongs to a family member.
+fn is_family_member(id: AtomId) -> bool {
+    MEMBERS.with(|members| members.borrow().ids.contains_key(&id))
+}
+
+/// Count a new slot of the atom with the id `id`, if it is a family member.
+fn retain_member(id: AtomId) {
+    MEMBERS.with(|members| {
+        if let Some(member) = members.borrow_mut().ids.get_mut(&id) {
+            member.slots += 1;
+        }
+    })
+}
+
+/// Count a removed slot of the atom with the id `id`, and forget its key once it has no slots left.
+fn release_member(id: AtomId) {
+    // the members might be dropped already, if the root is dropped while the thread exits
+    let _ = MEMBERS.try_with(|members| {
+        let members = &mut *members.borrow_mut();
+        let member = match members.ids.get_mut(&id) {
+            Some(member) => member,
+            None => return,
+        };
+        member.slots = member.slots.saturating_sub(1);
+        if member.slots > 0 {
+            return;
+        }
+
+        let member = members.ids.remove(&id).unwrap();
+        if let Some(keys) = members.keys.get_mut(&member.family) {
+            (member.forget)(keys.as_mut(), member.key.as_ref());
+        }
+    });
+}
+
+/// Count a removed slot of the atom with the id `id`, but keep its key, because the member is
+/// initialized again under the same id.
+fn suspend_member(id: AtomId) {
+    MEMBERS.with(|members| {
+        if let Some(member) = members.borrow_mut().ids.get_mut(&id) {
+            member.slots = member.slots.saturating_sub(1);
+        }
+    })
+}
+
 pub struct AtomRoot {
     pub atoms: RefCell<HashMap<AtomId, Slot>>,
     pub update_any: Arc<dyn Fn(ScopeId)>,
+    /// Tasks that aren't run by the [`Driver`] yet, like the futures resolving the values of async
+    /// atoms.
+    tasks: RefCell<Vec<RootTask>>,
+    /// Wakes the [`Driver`] when tasks are queued.
+    waker: RefCell<Option<Waker>>,
+    /// Whether the [`Driver`] was created.
+    driven: Cell<bool>,
+    /// The generation of the latest future of every async atom that is still loading.
+    generations: RefCell<HashMap<AtomId, u64>>,
+    next_generation: Cell<u64>,
+    /// Debug names of atoms, by their id.
+    pub(crate) debug_info: RefCell<HashMap<AtomId, crate::history::DebugInfo>>,
+    /// The recorded writes, if writes are recorded.
+    pub(crate) recorder: RefCell<Option<crate::history::Recorder>>,
+    /// The effects observing atoms, by the id of the atom.
+    pub(crate) effects: RefCell<HashMap<AtomId, Vec<(usize, crate::effects::ErasedEffect)>>>,
+    pub(crate) next_effect: Cell<usize>,
+    #[cfg(feature = "persist")]
+    pub(crate) persistence: RefCell<crate::persist::Persistence>,
 }
 
+/// A future resolving the value of an async atom.
+pub(crate) type PendingValue = Pin<Box<dyn Future<Output = Rc<dyn Any>>>>;
+
+/// Creates a task of the [`AtomRoot`] it is given.
+pub(crate) type RootTask = Box<dyn FnOnce(Rc<AtomRoot>) -> Pin<Box<dyn Future<Output = ()>>>>;
+
 pub struct Slot {
     pub value: Rc<dyn Any>,
     pub subscribers: HashSet<ScopeId>,
+    /// The atoms the value was derived from, if the slot belongs to a selector.
+    pub dependencies: HashSet<AtomId>,
+    /// The selectors derived from the value.
+    pub dependents: HashSet<AtomId>,
+    pub(crate) derivation: Option<Derivation>,
+}
+
+impl Slot {
+    pub fn new(value: Rc<dyn Any>) -> Self {
+        Self {
+            value,
+            subscribers: HashSet::new(),
+            dependencies: HashSet::new(),
+            dependents: HashSet::new(),
+            derivation: None,
+        }
+    }
+}
+
+/// Computes a derived value, and the ids of the atoms it was derived from.
+type Compute = Rc<dyn Fn(&AtomRoot) -> (Rc<dyn Any>, HashSet<AtomId>)>;
+
+/// How to recompute a derived value.
+#[derive(Clone)]
+pub(crate) struct Derivation {
+    pub(crate) compute: Compute,
+    /// Compare two values, to only notify subscribers about actual changes.
+    pub(crate) eq: fn(&dyn Any, &dyn Any) -> bool,
 }
 
 impl AtomRoot {
@@ -22,65 +201,128 @@ impl AtomRoot {
         Self {
             update_any,
             atoms: RefCell::new(HashMap::new()),
+            tasks: RefCell::new(Vec::new()),
+            waker: RefCell::new(None),
+            driven: Cell::new(false),
+            generations: RefCell::new(HashMap::new()),
+            next_generation: Cell::new(0),
+            debug_info: Default::default(),
+            recorder: Default::default(),
+            effects: Default::default(),
+            next_effect: Cell::new(0),
+            #[cfg(feature = "persist")]
+            persistence: Default::default(),
         }
     }
 
     pub fn initialize<V: 'static>(&self, f: impl Readable<V>) {
         let id = f.unique_id();
         if self.atoms.borrow().get(&id).is_none() {
-            self.atoms.borrow_mut().insert(
-                id,
-                Slot {
-                    value: Rc::new(f.init()),
-                    subscribers: HashSet::new(),
-                },
-            );
+            // selectors read other atoms while they are initialized, so the atoms can't be borrowed
+            let slot = f.init_slot(self);
+            self.insert_slot(id, slot);
         }
     }
 
     pub fn register<V: 'static>(&self, f: impl Readable<V>, scope: ScopeId) -> Rc<V> {
+        let id = f.unique_id();
+        self.initialize(f);
+
         let mut atoms = self.atoms.borrow_mut();
+        let slot = atoms.get_mut(&id).unwrap();
+        slot.subscribers.insert(scope);
+        slot.value.clone().downcast().unwrap()
+    }
 
-        // initialize the value if it's not already initialized
-        if let Some(slot) = atoms.get_mut(&f.unique_id()) {
-            slot.subscribers.insert(scope);
-            slot.value.clone().downcast().unwrap()
-        } else {
-            let value = Rc::new(f.init());
-            let mut subscribers = HashSet::new();
-            subscribers.insert(scope);
+    pub fn set<V: 'static>(&self, ptr: AtomId, value: V) {
+        let value: Rc<dyn Any> = Rc::new(value);
+        self.record(ptr, &value, None);
+        self.write(ptr, value);
+    }
 
-            atoms.insert(
-                f.unique_id(),
-                Slot {
-                    value: value.clone(),
-                    subscribers,
-                },
-            );
-            value
-        }
+    /// Set the value of the atom with the id `ptr`, on behalf of the component with the id `scope`.
+    ///
+    /// The component is recorded as the source of the write in the history of the root.
+    pub fn set_from<V: 'static>(&self, ptr: AtomId, value: V, scope: ScopeId) {
+        let value: Rc<dyn Any> = Rc::new(value);
+        self.record(ptr, &value, Some(scope));
+        self.write(ptr, value);
     }
 
-    pub fn set<V: 'static>(&self, ptr: AtomId, value: V) {
-        let mut atoms = self.atoms.borrow_mut();
+    /// Replace the value of the atom with the id `ptr`, and update everything that depends on it.
+    pub(crate) fn write(&self, ptr: AtomId, value: Rc<dyn Any>) {
+        let old = {
+            let mut atoms = self.atoms.borrow_mut();
 
-        if let Some(slot) = atoms.get_mut(&ptr) {
-            slot.value = Rc::new(value);
-            log::trace!("found item with subscribers {:?}", slot.subscribers);
+            if let Some(slot) = atoms.get_mut(&ptr) {
+                let old = std::mem::replace(&mut slot.value, value.clone());
+                log::trace!("found item with subscribers {:?}", slot.subscribers);
 
-            for scope in &slot.subscribers {
-                log::trace!("updating subcsriber");
-                (self.update_any)(*scope);
+                for scope in &slot.subscribers {
+                    log::trace!("updating subcsriber");
+                    (self.update_any)(*scope);
+                }
+                Some(old)
+            } else {
+                log::trace!("no atoms found for {:?}", ptr);
+                retain_member(ptr);
+                atoms.insert(ptr, Slot::new(value.clone()));
+                None
+            }
+        };
+
+        self.run_effects(ptr, old.as_ref(), &value);
+        self.update_dependents(ptr);
+
+        #[cfg(feature = "persist")]
+        self.persist_changed(ptr);
+    }
+
+    /// Remove the value of the atom with the id `id`, so that it is initialized again the next time
+    /// it is read.
+    ///
+    /// Subscribers are updated, and the selectors derived from the atom are recomputed, which
+    /// initializes it again right away.
+    pub(crate) fn reset(&self, id: AtomId) {
+        let dependents = self.sorted_dependents(id);
+        let slot = match self.atoms.borrow_mut().remove(&id) {
+            Some(slot) => slot,
+            None => return,
+        };
+        suspend_member(id);
+        for scope in &slot.subscribers {
+            (self.update_any)(*scope);
+        }
+
+        for dependent in dependents {
+            self.recompute(dependent);
+        }
+
+        // recomputing doesn't link selectors that read the atom before to its new slot
+        let value = {
+            let mut atoms = self.atoms.borrow_mut();
+            let linked: Vec<_> = slot
+                .dependents
+                .iter()
+                .filter(|dependent| {
+                    matches!(atoms.get(dependent), Some(slot) if slot.dependencies.contains(&id))
+                })
+                .copied()
+                .collect();
+            match atoms.get_mut(&id) {
+                Some(new_slot) => {
+                    new_slot.dependents.extend(linked);
+                    Some(new_slot.value.clone())
+                }
+                None => None,
             }
-        } else {
-            log::trace!("no atoms found for {:?}", ptr);
-            atoms.insert(
-                ptr,
-                Slot {
-                    value: Rc::new(value),
-                    subscribers: HashSet::new(),
-                },
-            );
+        };
+
+        if let Some(value) = value {
+            self.run_effects(id, Some(&slot.value), &value);
+
+            #[cfg(feature = "persist")]
+            self.persist_changed(id);
         }
     }
 
@@ -90,6 +332,7 @@ impl AtomRoot {
         if let Some(slot) = atoms.get_mut(&ptr) {
             slot.subscribers.remove(&scope);
         }
+        collect_garbage(&mut atoms, ptr, &|id| self.is_retained(id));
     }
 
     // force update of all subscribers
@@ -103,21 +346,308 @@ impl AtomRoot {
     }
 
     pub fn read<V: 'static>(&self, f: impl Readable<V>) -> Rc<V> {
+        let id = f.unique_id();
+        self.initialize(f);
+
+        let atoms = self.atoms.borrow();
+        atoms[&id].value.clone().downcast().unwrap()
+    }
+
+    /// Take the value of `f` out of the root, initializing it first.
+    ///
+    /// This is how [`Readable::read`] gets an owned value from the root it is given.
+    pub(crate) fn take<V: 'static>(self, f: impl Readable<V>) -> Option<V> {
+        let id = f.unique_id();
+        self.initialize(f);
+
+        let slot = self.atoms.borrow_mut().remove(&id)?;
+        release_member(id);
+        Rc::try_unwrap(slot.value.downcast().ok()?).ok()
+    }
+
+    fn insert_slot(&self, id: AtomId, slot: Slot) {
         let mut atoms = self.atoms.borrow_mut();
 
-        // initialize the value if it's not already initialized
-        if let Some(slot) = atoms.get_mut(&f.unique_id()) {
-            slot.value.clone().downcast().unwrap()
-        } else {
-            let value = Rc::new(f.init());
-            atoms.insert(
-                f.unique_id(),
-                Slot {
-                    value: value.clone(),
-                    subscribers: HashSet::new(),
-                },
-            );
-            value
+        for dependency in &slot.dependencies {
+            if let Some(dependency) = atoms.get_mut(dependency) {
+                dependency.dependents.insert(id);
+            }
+        }
+
+        retain_member(id);
+        atoms.insert(id, slot);
+    }
+
+    /// Check whether the atom with the id `id` is kept even if no component or selector uses it,
+    /// because effects observe it or it is persisted.
+    fn is_retained(&self, id: AtomId) -> bool {
+        let retained = self.effects.borrow().contains_key(&id);
+        #[cfg(feature = "persist")]
+        let retained = retained || self.persistence.borrow().is_persisted(id);
+        retained
+    }
+
+    /// Recompute the atom with the id `id`, if it is derived from other atoms or resolved
+    /// asynchronously.
+    ///
+    /// Async atoms start loading again, and selectors are recomputed even if the atoms they read
+    /// didn't change. Subscribers and dependents are updated if the value changed.
+    pub fn refresh(&self, id: AtomId) {
+        if self.recompute(id) {
+            self.update_dependents(id);
+        }
+    }
+
+    /// Take the tasks that aren't run yet, like the futures that resolve the values of async atoms.
+    ///
+    /// The returned futures must be run for async atoms to finish loading. Apps don't need to call
+    /// this: the hooks of fermi spawn a task on the root scope, which runs the tasks of the root as
+    /// soon as they are queued.
+    pub fn take_tasks(self: &Rc<Self>) -> Vec<Pin<Box<dyn Future<Output = ()>>>> {
+        self.tasks
+            .take()
+            .into_iter()
+            .map(|task| task(self.clone()))
+            .collect()
+    }
+
+    /// Get the [`Driver`] of the root, unless it was created already.
+    pub(crate) fn driver(self: &Rc<Self>) -> Option<Driver> {
+        match self.driven.replace(true) {
+            false => Some(Driver {
+                root: Rc::downgrade(self),
+                running: Vec::new(),
+            }),
+            true => None,
+        }
+    }
+
+    /// Queue `task` to be run by the [`Driver`].
+    pub(crate) fn queue_task(&self, task: RootTask) {
+        self.tasks.borrow_mut().push(task);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Queue `future` to resolve the value of the async atom with the id `id`.
+    ///
+    /// Values of previously queued futures for the same atom are discarded.
+    pub(crate) fn queue(&self, id: AtomId, future: PendingValue) {
+        let generation = self.next_generation.get();
+        self.next_generation.set(generation + 1);
+
+        self.generations.borrow_mut().insert(id, generation);
+        self.queue_task(Box::new(move |root| {
+            Box::pin(async move {
+                let value = future.await;
+                root.resolve(id, generation, value);
+            })
+        }));
+    }
+
+    /// Set the value of the async atom with the id `id`, if `generation` is its latest future.
+    fn resolve(&self, id: AtomId, generation: u64, value: Rc<dyn Any>) {
+        {
+            let mut generations = self.generations.borrow_mut();
+            if generations.get(&id) != Some(&generation) {
+                log::trace!("discarding outdated value of {:?}", id);
+                return;
+            }
+            generations.remove(&id);
+        }
+
+        let old = {
+            let mut atoms = self.atoms.borrow_mut();
+            // the atom was collected while it was loading
+            let slot = match atoms.get_mut(&id) {
+                Some(slot) => slot,
+                None => return,
+            };
+
+            for scope in &slot.subscribers {
+                log::trace!("updating subcsriber");
+                (self.update_any)(*scope);
+            }
+            std::mem::replace(&mut slot.value, value.clone())
+        };
+
+        self.run_effects(id, Some(&old), &value);
+        self.update_dependents(id);
+    }
+
+    /// Recompute the selectors derived from the atom with the id `changed`.
+    ///
+    /// Every selector is recomputed at most once, after all the selectors it depends on. Selectors
+    /// whose dependencies didn't change are skipped, even if they are derived from `changed`
+    /// indirectly. Subscribers are only updated if the value of their selector changed.
+    fn update_dependents(&self, changed: AtomId) {
+        let mut changed_ids = HashSet::unit(changed);
+
+        for id in self.sorted_dependents(changed) {
+            let changed = match self.atoms.borrow().get(&id) {
+                Some(slot) => slot.dependencies.iter().any(|d| changed_ids.contains(d)),
+                None => false,
+            };
+
+            if changed && self.recompute(id) {
+                changed_ids.insert(id);
+            }
+        }
+    }
+
+    /// Recompute the value of the atom with the id `id`, if it has a [`Derivation`].
+    ///
+    /// Returns [`true`] if the value changed, in which case the subscribers are updated.
+    fn recompute(&self, id: AtomId) -> bool {
+        let (dependencies, derivation) = match self.atoms.borrow().get(&id) {
+            Some(Slot {
+                dependencies,
+                derivation: Some(derivation),
+                ..
+            }) => (dependencies.clone(), derivation.clone()),
+            _ => return false,
+        };
+
+        let (value, new_dependencies) = (derivation.compute)(self);
+
+        let mut atoms = self.atoms.borrow_mut();
+        for removed in dependencies
+            .clone()
+            .relative_complement(new_dependencies.clone())
+        {
+            if let Some(slot) = atoms.get_mut(&removed) {
+                slot.dependents.remove(&id);
+            }
+            collect_garbage(&mut atoms, removed, &|id| self.is_retained(id));
+        }
+        for added in new_dependencies.clone().relative_complement(dependencies) {
+            if let Some(slot) = atoms.get_mut(&added) {
+                slot.dependents.insert(id);
+            }
+        }
+
+        let slot = match atoms.get_mut(&id) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        slot.dependencies = new_dependencies;
+        if (derivation.eq)(slot.value.as_ref(), value.as_ref()) {
+            return false;
+        }
+
+        let old = std::mem::replace(&mut slot.value, value.clone());
+        for scope in &slot.subscribers {
+            log::trace!("updating subcsriber");
+            (self.update_any)(*scope);
+        }
+        drop(atoms);
+
+        self.run_effects(id, Some(&old), &value);
+        true
+    }
+
+    /// Get all selectors derived from the atom with the id `id`, directly or indirectly.
+    ///
+    /// Selectors come after all other selectors they are derived from.
+    fn sorted_dependents(&self, id: AtomId) -> Vec<AtomId> {
+        fn visit(
+            atoms: &HashMap<AtomId, Slot>,
+            id: AtomId,
+            visited: &mut HashSet<AtomId>,
+            sorted: &mut Vec<AtomId>,
+        ) {
+            if visited.insert(id).is_some() {
+                return;
+            }
+            if let Some(slot) = atoms.get(&id) {
+                for dependent in &slot.dependents {
+                    visit(atoms, *dependent, visited, sorted);
+                }
+            }
+            sorted.push(id);
+        }
+
+        let mut sorted = Vec::new();
+        visit(&self.atoms.borrow(), id, &mut HashSet::new(), &mut sorted);
+
+        sorted.pop();
+        sorted.reverse();
+        sorted
+    }
+}
+
+impl Drop for AtomRoot {
+    fn drop(&mut self) {
+        for id in self.atoms.get_mut().keys() {
+            release_member(*id);
+        }
+    }
+}
+
+/// Runs the tasks of an [`AtomRoot`], for as long as the root exists.
+///
+/// Tasks are run as soon as they are queued, without waiting for a component to render.
+pub(crate) struct Driver {
+    root: Weak<AtomRoot>,
+    running: Vec<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+impl Future for Driver {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let root = match self.root.upgrade() {
+            Some(root) => root,
+            None => return Poll::Ready(()),
+        };
+        root.waker.replace(Some(cx.waker().clone()));
+        let queued = root.take_tasks();
+        drop(root);
+
+        let running = &mut self.running;
+        running.extend(queued);
+        let mut index = 0;
+        while index < running.len() {
+            match running[index].as_mut().poll(cx) {
+                Poll::Ready(()) => drop(running.swap_remove(index)),
+                Poll::Pending => index += 1,
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Remove the slot of the family member with the id `id` if it is no longer used.
+///
+/// Members are no longer used when they have neither subscribers nor dependents, and aren't
+/// `retained`. Removing a member can leave the members it was derived from unused, so those are
+/// removed as well.
+fn collect_garbage(
+    atoms: &mut HashMap<AtomId, Slot>,
+    id: AtomId,
+    retained: &dyn Fn(AtomId) -> bool,
+) {
+    let unused = match atoms.get(&id) {
+        Some(slot) => {
+            is_family_member(id)
+                && slot.subscribers.is_empty()
+                && slot.dependents.is_empty()
+                && !retained(id)
+        }
+        None => false,
+    };
+    if !unused {
+        return;
+    }
+
+    log::trace!("collecting unused family member {:?}", id);
+    let slot = atoms.remove(&id).unwrap();
+    release_member(id);
+    for dependency in slot.dependencies {
+        if let Some(dependency_slot) = atoms.get_mut(&dependency) {
+            dependency_slot.dependents.remove(&id);
         }
+        collect_garbage(atoms, dependency, retained);
     }
 }
@@ -19,5 +19,17 @@ pub use usefuture::*;
 mod useeffect;
 pub use useeffect::*;
 
+mod usereducer;
+pub use usereducer::*;
+
+mod usesignal;
+pub use usesignal::*;
+
+mod usecollection;
+pub use usecollection::*;
+
+mod usequery;
+pub use usequery::*;
+
 // mod usesuspense;
 // pub use usesuspense::*;
@@ -1,76 +1,771 @@
-/*
-a form of use_state explicitly for map-style collections (BTreeMap, HashMap, etc).
-
-Why?
----
-Traditionally, it's possible to use the "use_state" hook for collections in the React world.
-Adding a new entry would look something similar to:
-
-```js
-let (map, set_map) = useState({});
-set_map({ ...map, [key]: value });
-```
-The new value then causes the appropriate update when passed into children.
-
-This is moderately efficient because the fields of the map are moved, but the data itself is not cloned.
-However, if you used similar approach with Dioxus:
-
-```rust
-let (map, set_map) = use_state(&cx, || HashMap::new());
-set_map({
-    let mut newmap = map.clone();
-    newmap.set(key, value);
-    newmap
-})
-```
-Unfortunately, you'd be cloning the entire state every time a value is changed. The obvious solution is to
-wrap every element in the HashMap with an Rc. That way, cloning the HashMap is on par with its JS equivalent.
-
-Fortunately, we can make this operation even more efficient in Dioxus, leveraging the borrow rules of Rust.
-
-This hook provides a memoized collection, memoized setters, and memoized getters. This particular hook is
-extremely powerful for implementing lists and supporting core state management needs for small apps.
-
-If you need something even more powerful, check out the dedicated atomic state management Dioxus Dataflow, which
-uses the same memoization on top of the use_context API.
-
-Here's a fully-functional todo app using the use_map API:
-```rust
-static TodoList: Component = |cx| {
-    let todos = use_map(cx, || HashMap::new());
-    let input = use_ref(|| None);
-
-    cx.render(rsx!{
-        div {
-            button {
-                "Add todo"
-                onclick: move |_| {
-                    let new_todo = TodoItem::new(input.contents());
-                    todos.insert(new_todo.id.clone(), new_todo);
-                    input.clear();
+//! Collection hooks with granular updates.
+//!
+//! It's possible to keep a collection in `use_state`, but every change then clones the whole
+//! collection and re-renders everything that reads it:
+//!
+//! ```rust, ignore
+//! let map = use_state(&cx, || HashMap::new());
+//! map.set({
+//!     let mut new_map = (**map).clone();
+//!     new_map.insert(key, value);
+//!     new_map
+//! });
+//! ```
+//!
+//! [`use_vec`] and [`use_map`] modify their collection in place instead, and keep track of which
+//! keys changed. Structural changes - inserting, removing, sorting - re-render the component that
+//! owns the collection. Updates of a single entry only re-render the components that subscribed to
+//! that entry with [`use_vec_entry`] or [`use_map_entry`], which makes them a good fit for large
+//! keyed lists.
+
+use dioxus_core::{ScopeId, ScopeState};
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    rc::Rc,
+    sync::Arc,
+};
+
+/// A change of a collection kept by [`use_vec`] or [`use_map`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CollectionChange<K> {
+    /// An entry was inserted.
+    Inserted(K),
+    /// An entry was removed.
+    Removed(K),
+    /// The value of an entry was modified.
+    Updated(K),
+    /// The order of the entries changed.
+    Reordered,
+    /// All entries were removed.
+    Cleared,
+}
+
+/// The stable key of an entry of a [`UseVec`].
+///
+/// Keys are assigned when entries are added, and don't change when other entries are inserted,
+/// removed or sorted. This makes them usable as the `key` of the component rendering the entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntryKey(u64);
+
+impl Display for EntryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Tracks the changes of a collection, and the components to notify about them.
+struct Tracker<K> {
+    owner: ScopeId,
+    update_any: Arc<dyn Fn(ScopeId)>,
+    /// The components subscribed to a single entry.
+    entries: RefCell<HashMap<K, HashSet<ScopeId>>>,
+    /// The changes since the owner rendered, which re-render the owner.
+    pending: RefCell<Vec<CollectionChange<K>>>,
+    /// The changes before the latest render of the owner.
+    rendered: RefCell<Vec<CollectionChange<K>>>,
+}
+
+impl<K: Clone + Eq + Hash> Tracker<K> {
+    fn new(cx: &ScopeState) -> Self {
+        Self {
+            owner: cx.scope_id(),
+            update_any: cx.schedule_update_any(),
+            entries: RefCell::new(HashMap::new()),
+            pending: RefCell::new(Vec::new()),
+            rendered: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Called whenever the owner renders.
+    fn render(&self) {
+        *self.rendered.borrow_mut() = self.pending.take();
+    }
+
+    /// Record a change of the collection, which re-renders the owner.
+    fn structure(&self, change: CollectionChange<K>) {
+        self.pending.borrow_mut().push(change);
+        (self.update_any)(self.owner);
+    }
+
+    /// Record an update of the entry `key`, which re-renders its subscribers - or the owner, if
+    /// there are none.
+    ///
+    /// Updates are only recorded for the owner if it re-renders.
+    fn update(&self, key: K) {
+        match self.entries.borrow().get(&key) {
+            Some(scopes) if !scopes.is_empty() => {
+                for scope in scopes {
+                    (self.update_any)(*scope);
                 }
             }
-            button {
-                "Clear todos"
-                onclick: move |_| todos.clear()
+            _ => self.structure(CollectionChange::Updated(key)),
+        }
+    }
+
+    fn subscribe(&self, key: K, scope: ScopeId) {
+        self.entries
+            .borrow_mut()
+            .entry(key)
+            .or_default()
+            .insert(scope);
+    }
+
+    fn unsubscribe(&self, key: &K, scope: ScopeId) {
+        let mut entries = self.entries.borrow_mut();
+        if let Some(scopes) = entries.get_mut(key) {
+            scopes.remove(&scope);
+            if scopes.is_empty() {
+                entries.remove(key);
             }
-            input {
-                placeholder: "What needs to be done?"
-                ref: input
+        }
+    }
+}
+
+/// Keeps the subscription of a component to an entry, until the component is dropped.
+struct EntrySubscription<K: Clone + Eq + Hash> {
+    tracker: Rc<Tracker<K>>,
+    scope: ScopeId,
+    key: RefCell<Option<K>>,
+}
+
+impl<K: Clone + Eq + Hash> EntrySubscription<K> {
+    /// Subscribe to `key`, replacing the key of earlier renders.
+    fn set_key(&self, key: K) {
+        let mut current = self.key.borrow_mut();
+        if current.as_ref() != Some(&key) {
+            if let Some(previous) = current.take() {
+                self.tracker.unsubscribe(&previous, self.scope);
+            }
+            self.tracker.subscribe(key.clone(), self.scope);
+            *current = Some(key);
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> Drop for EntrySubscription<K> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.get_mut() {
+            self.tracker.unsubscribe(key, self.scope);
+        }
+    }
+}
+
+fn use_entry_subscription<K: Clone + Eq + Hash + 'static>(
+    cx: &ScopeState,
+    tracker: &Rc<Tracker<K>>,
+    key: K,
+) {
+    let subscription = cx.use_hook(|| EntrySubscription {
+        tracker: tracker.clone(),
+        scope: cx.scope_id(),
+        key: RefCell::new(None),
+    });
+
+    // the component might have been handed another collection
+    if !Rc::ptr_eq(&subscription.tracker, tracker) {
+        if let Some(previous) = subscription.key.take() {
+            subscription
+                .tracker
+                .unsubscribe(&previous, subscription.scope);
+        }
+        subscription.tracker = tracker.clone();
+    }
+    subscription.set_key(key);
+}
+
+/// Store a list that is modified in place, and that re-renders only what changed.
+///
+/// Every entry gets a stable [`EntryKey`]. Adding, removing and reordering entries re-renders
+/// the component calling `use_vec`, while [`UseVec::update`] only re-renders the components that
+/// read the entry with [`use_vec_entry`].
+///
+/// ```rust, ignore
+/// fn TodoList(cx: Scope) -> Element {
+///     let todos = use_vec(&cx, Vec::new);
+///
+///     cx.render(rsx!{
+///         button { onclick: move |_| { todos.push(Todo::default()); }, "Add todo" }
+///         button { onclick: move |_| todos.clear(), "Clear todos" }
+///         ul {
+///             todos.keys().into_iter().map(|key| rsx!(
+///                 TodoItem { key: "{key}", todos: todos.clone(), id: key }
+///             ))
+///         }
+///     })
+/// }
+///
+/// #[inline_props]
+/// fn TodoItem(cx: Scope, todos: UseVec<Todo>, id: EntryKey) -> Element {
+///     // only re-rendered when this todo is updated
+///     let todo = use_vec_entry(&cx, todos, *id)?;
+///
+///     cx.render(rsx!{
+///         li {
+///             input {
+///                 r#type: "checkbox",
+///                 checked: "{todo.done}",
+///                 onclick: move |_| todos.update(*id, |todo| todo.done = !todo.done),
+///             }
+///             "{todo.content}"
+///             button { onclick: move |_| { todos.remove(*id); }, "x" }
+///         }
+///     })
+/// }
+/// ```
+pub fn use_vec<T: 'static>(cx: &ScopeState, initial: impl FnOnce() -> Vec<T>) -> &UseVec<T> {
+    let vec = cx.use_hook(|| {
+        let values = initial();
+        let keys = (0..values.len() as u64).map(EntryKey).collect();
+        UseVec {
+            inner: Rc::new(VecInner {
+                next_key: Cell::new(values.len() as u64),
+                entries: RefCell::new(Entries::new(keys, values)),
+                tracker: Rc::new(Tracker::new(cx)),
+            }),
+        }
+    });
+    vec.inner.tracker.render();
+    vec
+}
+
+/// Read the entry `key` of `vec`, and re-render the component whenever the entry is updated.
+///
+/// Returns [`None`] if the entry was removed.
+pub fn use_vec_entry<'a, T: 'static>(
+    cx: &ScopeState,
+    vec: &'a UseVec<T>,
+    key: EntryKey,
+) -> Option<Ref<'a, T>> {
+    use_entry_subscription(cx, &vec.inner.tracker, key);
+    vec.get(key)
+}
+
+/// A list kept by [`use_vec`].
+///
+/// Cloning the handle is cheap, and clones compare equal - so passing it to keyed child
+/// components doesn't re-render them.
+pub struct UseVec<T: 'static> {
+    inner: Rc<VecInner<T>>,
+}
+
+struct VecInner<T> {
+    next_key: Cell<u64>,
+    entries: RefCell<Entries<T>>,
+    tracker: Rc<Tracker<EntryKey>>,
+}
+
+struct Entries<T> {
+    keys: Vec<EntryKey>,
+    values: Vec<T>,
+    /// The index of every key in `keys`.
+    indices: HashMap<EntryKey, usize>,
+}
+
+impl<T> Entries<T> {
+    fn new(keys: Vec<EntryKey>, values: Vec<T>) -> Self {
+        let mut entries = Entries {
+            keys,
+            values,
+            indices: HashMap::new(),
+        };
+        entries.reindex(0);
+        entries
+    }
+
+    fn position(&self, key: EntryKey) -> Option<usize> {
+        self.indices.get(&key).copied()
+    }
+
+    fn insert(&mut self, index: usize, key: EntryKey, value: T) {
+        self.keys.insert(index, key);
+        self.values.insert(index, value);
+        self.reindex(index);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        let key = self.keys.remove(index);
+        self.indices.remove(&key);
+        self.reindex(index);
+        self.values.remove(index)
+    }
+
+    /// Update the indices of the keys from `start` on, after they moved.
+    fn reindex(&mut self, start: usize) {
+        for (index, key) in self.keys.iter().enumerate().skip(start) {
+            self.indices.insert(*key, index);
+        }
+    }
+}
+
+impl<T: 'static> UseVec<T> {
+    /// Read the values of the list.
+    pub fn read(&self) -> Ref<'_, [T]> {
+        Ref::map(self.inner.entries.borrow(), |entries| &entries.values[..])
+    }
+
+    /// Get the keys of the entries, in order.
+    pub fn keys(&self) -> Vec<EntryKey> {
+        self.inner.entries.borrow().keys.clone()
+    }
+
+    /// Get the key of the entry at `index`.
+    pub fn key_at(&self, index: usize) -> Option<EntryKey> {
+        self.inner.entries.borrow().keys.get(index).copied()
+    }
+
+    /// Read the value of the entry `key`.
+    pub fn get(&self, key: EntryKey) -> Option<Ref<'_, T>> {
+        let entries = self.inner.entries.borrow();
+        let index = entries.position(key)?;
+        Some(Ref::map(entries, |entries| &entries.values[index]))
+    }
+
+    /// Get the amount of entries.
+    pub fn len(&self) -> usize {
+        self.inner.entries.borrow().values.len()
+    }
+
+    /// Check whether the list has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the changes that happened before the latest render of the component owning the list.
+    ///
+    /// Updates that only re-rendered the components reading the entry are not included.
+    pub fn changes(&self) -> Ref<'_, [CollectionChange<EntryKey>]> {
+        Ref::map(self.inner.tracker.rendered.borrow(), |changes| &changes[..])
+    }
+
+    /// Add `value` to the end of the list, and get the key of its entry.
+    pub fn push(&self, value: T) -> EntryKey {
+        let len = self.len();
+        self.insert(len, value)
+    }
+
+    /// Insert `value` at `index`, and get the key of its entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&self, index: usize, value: T) -> EntryKey {
+        let key = EntryKey(self.inner.next_key.get());
+        self.inner.next_key.set(key.0 + 1);
+        self.inner.entries.borrow_mut().insert(index, key, value);
+        self.inner
+            .tracker
+            .structure(CollectionChange::Inserted(key));
+        key
+    }
+
+    /// Remove the entry `key`, and get its value.
+    pub fn remove(&self, key: EntryKey) -> Option<T> {
+        let value = {
+            let mut entries = self.inner.entries.borrow_mut();
+            let index = entries.position(key)?;
+            entries.remove(index)
+        };
+        self.inner.tracker.structure(CollectionChange::Removed(key));
+        Some(value)
+    }
+
+    /// Remove the last entry, and get its value.
+    pub fn pop(&self) -> Option<T> {
+        let key = self.inner.entries.borrow().keys.last().copied()?;
+        self.remove(key)
+    }
+
+    /// Keep only the entries for which `f` returns `true`.
+    pub fn retain(&self, mut f: impl FnMut(&T) -> bool) {
+        let mut removed = Vec::new();
+        {
+            let mut entries = self.inner.entries.borrow_mut();
+            let Entries {
+                keys,
+                values,
+                indices,
+            } = &mut *entries;
+            let kept: Vec<_> = values.iter().map(&mut f).collect();
+            let mut keep = kept.iter();
+            values.retain(|_| *keep.next().unwrap());
+            let mut keep = kept.iter();
+            keys.retain(|key| {
+                let keep = *keep.next().unwrap();
+                if !keep {
+                    removed.push(*key);
+                    indices.remove(key);
+                }
+                keep
+            });
+            entries.reindex(0);
+        }
+        for key in removed {
+            self.inner.tracker.structure(CollectionChange::Removed(key));
+        }
+    }
+
+    /// Remove all entries.
+    pub fn clear(&self) {
+        {
+            let mut entries = self.inner.entries.borrow_mut();
+            entries.keys.clear();
+            entries.values.clear();
+            entries.indices.clear();
+        }
+        self.inner.tracker.structure(CollectionChange::Cleared);
+    }
+
+    /// Sort the entries with the comparator `compare`. Entries keep their keys.
+    pub fn sort_by(&self, mut compare: impl FnMut(&T, &T) -> std::cmp::Ordering) {
+        {
+            let mut entries = self.inner.entries.borrow_mut();
+            let Entries { keys, values, .. } = &mut *entries;
+            let mut sorted: Vec<_> = keys.drain(..).zip(values.drain(..)).collect();
+            sorted.sort_by(|(_, a), (_, b)| compare(a, b));
+            for (key, value) in sorted {
+                keys.push(key);
+                values.push(value);
             }
-            ul {
-                {todos.iter().map(|todo| rsx!(
-                    li {
-                        key: todo.id
-                        span { "{todo.content}" }
-                        button {"x", onclick: move |_| todos.remove(todo.key.clone())}
-                    }
-                ))}
+            entries.reindex(0);
+        }
+        self.inner.tracker.structure(CollectionChange::Reordered);
+    }
+
+    /// Sort the entries by the key `f` extracts from the values. Entries keep their keys.
+    pub fn sort_by_key<O: Ord>(&self, mut f: impl FnMut(&T) -> O) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    /// Modify the value of the entry `key` in place.
+    ///
+    /// Only the components reading the entry with [`use_vec_entry`] are re-rendered. If there are
+    /// none, the component owning the list is. Returns `false` if there is no such entry.
+    pub fn update(&self, key: EntryKey, f: impl FnOnce(&mut T)) -> bool {
+        {
+            let mut entries = self.inner.entries.borrow_mut();
+            let index = match entries.position(key) {
+                Some(index) => index,
+                None => return false,
+            };
+            f(&mut entries.values[index]);
+        }
+        self.inner.tracker.update(key);
+        true
+    }
+}
+
+impl<T: Ord + 'static> UseVec<T> {
+    /// Sort the entries by their values. Entries keep their keys.
+    pub fn sort(&self) {
+        self.sort_by(T::cmp)
+    }
+}
+
+impl<T: 'static> Clone for UseVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: 'static> PartialEq for UseVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+/// Store a map that is modified in place, and that re-renders only what changed.
+///
+/// Entries are kept in the order of their keys. Inserting and removing keys re-renders the
+/// component calling `use_map`, while modifying the value of an existing key only re-renders the
+/// components that read it with [`use_map_entry`].
+pub fn use_map<K, V>(cx: &ScopeState, initial: impl FnOnce() -> BTreeMap<K, V>) -> &UseMap<K, V>
+where
+    K: Ord + Hash + Clone + 'static,
+    V: 'static,
+{
+    let map = cx.use_hook(|| UseMap {
+        inner: Rc::new(MapInner {
+            values: RefCell::new(initial()),
+            tracker: Rc::new(Tracker::new(cx)),
+        }),
+    });
+    map.inner.tracker.render();
+    map
+}
+
+/// Read the entry `key` of `map`, and re-render the component whenever its value is modified.
+///
+/// Returns [`None`] if there is no such entry.
+pub fn use_map_entry<'a, K, V>(cx: &ScopeState, map: &'a UseMap<K, V>, key: K) -> Option<Ref<'a, V>>
+where
+    K: Ord + Hash + Clone + 'static,
+    V: 'static,
+{
+    use_entry_subscription(cx, &map.inner.tracker, key.clone());
+    map.get(&key)
+}
+
+/// A map kept by [`use_map`].
+///
+/// Cloning the handle is cheap, and clones compare equal - so passing it to keyed child
+/// components doesn't re-render them.
+pub struct UseMap<K: 'static, V: 'static> {
+    inner: Rc<MapInner<K, V>>,
+}
+
+struct MapInner<K, V> {
+    values: RefCell<BTreeMap<K, V>>,
+    tracker: Rc<Tracker<K>>,
+}
+
+impl<K: Ord + Hash + Clone + 'static, V: 'static> UseMap<K, V> {
+    /// Read the map.
+    pub fn read(&self) -> Ref<'_, BTreeMap<K, V>> {
+        self.inner.values.borrow()
+    }
+
+    /// Get the keys of the entries, in order.
+    pub fn keys(&self) -> Vec<K> {
+        self.inner.values.borrow().keys().cloned().collect()
+    }
+
+    /// Read the value of the entry `key`.
+    pub fn get(&self, key: &K) -> Option<Ref<'_, V>> {
+        let values = self.inner.values.borrow();
+        values
+            .contains_key(key)
+            .then(|| Ref::map(values, |values| &values[key]))
+    }
+
+    /// Check whether there is an entry `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.values.borrow().contains_key(key)
+    }
+
+    /// Get the amount of entries.
+    pub fn len(&self) -> usize {
+        self.inner.values.borrow().len()
+    }
+
+    /// Check whether the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the changes that happened before the latest render of the component owning the map.
+    ///
+    /// Updates that only re-rendered the components reading the entry are not included.
+    pub fn changes(&self) -> Ref<'_, [CollectionChange<K>]> {
+        Ref::map(self.inner.tracker.rendered.borrow(), |changes| &changes[..])
+    }
+
+    /// Set the value of the entry `key`, and get its previous value.
+    ///
+    /// Replacing the value of an existing entry counts as an update, see [`UseMap::update`].
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let previous = self.inner.values.borrow_mut().insert(key.clone(), value);
+        match previous {
+            Some(_) => self.inner.tracker.update(key),
+            None => self
+                .inner
+                .tracker
+                .structure(CollectionChange::Inserted(key)),
+        }
+        previous
+    }
+
+    /// Remove the entry `key`, and get its value.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let value = self.inner.values.borrow_mut().remove(key)?;
+        self.inner
+            .tracker
+            .structure(CollectionChange::Removed(key.clone()));
+        Some(value)
+    }
+
+    /// Keep only the entries for which `f` returns `true`.
+    pub fn retain(&self, mut f: impl FnMut(&K, &V) -> bool) {
+        let mut removed = Vec::new();
+        self.inner.values.borrow_mut().retain(|key, value| {
+            let keep = f(key, value);
+            if !keep {
+                removed.push(key.clone());
             }
+            keep
+        });
+        for key in removed {
+            self.inner.tracker.structure(CollectionChange::Removed(key));
+        }
+    }
+
+    /// Remove all entries.
+    pub fn clear(&self) {
+        self.inner.values.borrow_mut().clear();
+        self.inner.tracker.structure(CollectionChange::Cleared);
+    }
+
+    /// Modify the value of the entry `key` in place.
+    ///
+    /// Only the components reading the entry with [`use_map_entry`] are re-rendered. If there are
+    /// none, the component owning the map is. Returns `false` if there is no such entry.
+    pub fn update(&self, key: &K, f: impl FnOnce(&mut V)) -> bool {
+        match self.inner.values.borrow_mut().get_mut(key) {
+            Some(value) => f(value),
+            None => return false,
+        }
+        self.inner.tracker.update(key.clone());
+        true
+    }
+}
+
+impl<K: 'static, V: 'static> Clone for UseMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
         }
-    })
+    }
 }
 
-```
+impl<K: 'static, V: 'static> PartialEq for UseMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_vec() {
+        use dioxus_core::prelude::*;
+
+        type List = Rc<RefCell<Option<UseVec<&'static str>>>>;
+
+        fn app(cx: Scope<List>) -> Element {
+            let list = use_vec(&cx, || vec!["a", "b"]);
+            cx.props.replace(Some(list.clone()));
+
+            cx.render(LazyNodes::new(|f| {
+                f.fragment_from_iter(list.keys().into_iter().map(|key| {
+                    let props = ItemProps {
+                        list: list.clone(),
+                        key,
+                    };
+                    f.component(item, props, Some(format_args!("{}", key)), "item")
+                }))
+            }))
+        }
+
+        struct ItemProps {
+            list: UseVec<&'static str>,
+            key: EntryKey,
+        }
+
+        impl Properties for ItemProps {
+            type Builder = ();
+            const IS_STATIC: bool = true;
+            fn builder() -> Self::Builder {}
+            unsafe fn memoize(&self, other: &Self) -> bool {
+                self.list == other.list && self.key == other.key
+            }
+        }
 
-*/
+        fn item(cx: Scope<ItemProps>) -> Element {
+            let value = use_vec_entry(&cx, &cx.props.list, cx.props.key)?.to_string();
+            cx.render(LazyNodes::new(move |f| f.text(format_args!("{}", value))))
+        }
+
+        // render the dirty components, and get the ones that rendered
+        fn work(dom: &mut VirtualDom) -> Vec<ScopeId> {
+            dom.process_all_messages();
+            let mut rendered: Vec<_> = dom
+                .work_with_deadline(|| false)
+                .into_iter()
+                .flat_map(|mutations| mutations.dirty_scopes)
+                .collect();
+            rendered.sort_by_key(|scope| scope.0);
+            rendered
+        }
+
+        let list = List::default();
+        let mut dom = VirtualDom::new_with_props(app, list.clone());
+        dom.rebuild();
+        let list = list.borrow().clone().unwrap();
+
+        // updates render only the component reading the entry, and aren't kept for the list
+        let b = list.key_at(1).unwrap();
+        assert!(list.update(b, |value| *value = "B"));
+        assert_eq!(work(&mut dom), [ScopeId(2)]);
+        assert!(list.inner.tracker.pending.borrow().is_empty());
+
+        // structural changes render the list and new entries, the existing entries stay memoized
+        let c = list.push("c");
+        assert_eq!(work(&mut dom), [ScopeId(0), ScopeId(3)]);
+        assert_eq!(*list.changes(), [CollectionChange::Inserted(c)]);
+
+        let a = list.key_at(0).unwrap();
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list.keys(), [c, a, b]);
+        assert_eq!(&*list.read(), ["c", "a", "B"]);
+
+        list.retain(|value| *value != "a");
+        assert_eq!(list.keys(), [c, b]);
+        // entries are still found by their key after moving
+        assert_eq!(list.get(b).as_deref(), Some(&"B"));
+        assert!(list.get(a).is_none());
+        assert_eq!(list.remove(c), Some("c"));
+        assert_eq!(list.get(b).as_deref(), Some(&"B"));
+        assert_eq!(work(&mut dom), [ScopeId(0)]);
+        assert_eq!(
+            *list.changes(),
+            [
+                CollectionChange::Reordered,
+                CollectionChange::Removed(a),
+                CollectionChange::Removed(c)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_use_map() {
+        use dioxus_core::prelude::*;
+
+        type Map = Rc<RefCell<Option<UseMap<u32, &'static str>>>>;
+
+        fn app(cx: Scope<Map>) -> Element {
+            let map = use_map(&cx, BTreeMap::new);
+            cx.props.replace(Some(map.clone()));
+            None
+        }
+
+        let map = Map::default();
+        let mut dom = VirtualDom::new_with_props(app, map.clone());
+        dom.rebuild();
+        let map = map.borrow().clone().unwrap();
+
+        // without components reading the entries, updates render the map
+        assert_eq!(map.insert(2, "two"), None);
+        assert_eq!(map.insert(1, "one"), None);
+        assert_eq!(map.insert(1, "uno"), Some("one"));
+        assert_eq!(map.remove(&2), Some("two"));
+        dom.process_all_messages();
+        dom.work_with_deadline(|| false);
+
+        assert_eq!(map.keys(), [1]);
+        assert_eq!(
+            *map.changes(),
+            [
+                CollectionChange::Inserted(2),
+                CollectionChange::Inserted(1),
+                CollectionChange::Updated(1),
+                CollectionChange::Removed(2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1059 @@
+//! A cache for async data, shared by every component that fetches the same resource.
+//!
+//! Queries are identified by a [`QueryKey`]. Components that use the same key share one cache
+//! entry and one request: the first one to mount starts the fetch, the others wait for its result.
+//! Cached values are served right away, and refetched in the background once they are stale.
+//!
+//! Nothing here depends on a particular async runtime. Fetches run on the scheduler of the
+//! [`VirtualDom`](dioxus_core::VirtualDom), and timers use `futures-timer` - enable the `web`
+//! feature when targeting the browser.
+
+use dioxus_core::{ScopeId, ScopeState};
+use futures_timer::Delay;
+use instant::Instant;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// The key of a query, made of segments like `["users", "42"]`.
+///
+/// Keys can be invalidated by prefix: invalidating `["users"]` invalidates `["users", "42"]` too.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct QueryKey(Vec<String>);
+
+impl QueryKey {
+    /// Create a key from its segments.
+    pub fn new(segments: impl IntoIterator<Item = impl ToString>) -> Self {
+        Self(segments.into_iter().map(|s| s.to_string()).collect())
+    }
+
+    /// Get the segments of the key.
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Check whether the segments of `prefix` are the first segments of this key.
+    pub fn starts_with(&self, prefix: &QueryKey) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+}
+
+impl Display for QueryKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.join("/"))
+    }
+}
+
+impl From<&str> for QueryKey {
+    fn from(key: &str) -> Self {
+        Self(vec![key.to_string()])
+    }
+}
+
+impl From<String> for QueryKey {
+    fn from(key: String) -> Self {
+        Self(vec![key])
+    }
+}
+
+impl From<Vec<String>> for QueryKey {
+    fn from(segments: Vec<String>) -> Self {
+        Self(segments)
+    }
+}
+
+impl<T: ToString, const N: usize> From<[T; N]> for QueryKey {
+    fn from(segments: [T; N]) -> Self {
+        Self::new(segments)
+    }
+}
+
+impl From<&QueryKey> for QueryKey {
+    fn from(key: &QueryKey) -> Self {
+        key.clone()
+    }
+}
+
+/// Configures how a query is cached and fetched.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryOptions {
+    /// How long a fetched value is fresh. Stale values are refetched when a component using the
+    /// query mounts. Defaults to zero.
+    pub stale_time: Duration,
+    /// How often a failed fetch is retried. Defaults to 3.
+    pub retries: u32,
+    /// The delay before the first retry, which doubles with every further retry. Defaults to one
+    /// second.
+    pub retry_delay: Duration,
+    /// The longest delay between retries. Defaults to 30 seconds.
+    pub max_retry_delay: Duration,
+    /// Refetch the value in the background this often, while components use the query.
+    pub refetch_interval: Option<Duration>,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            stale_time: Duration::ZERO,
+            retries: 3,
+            retry_delay: Duration::from_secs(1),
+            max_retry_delay: Duration::from_secs(30),
+            refetch_interval: None,
+        }
+    }
+}
+
+impl QueryOptions {
+    /// Set [`QueryOptions::stale_time`].
+    pub fn with_stale_time(mut self, stale_time: Duration) -> Self {
+        self.stale_time = stale_time;
+        self
+    }
+
+    /// Set [`QueryOptions::retries`].
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set [`QueryOptions::retry_delay`].
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Set [`QueryOptions::max_retry_delay`].
+    pub fn with_max_retry_delay(mut self, max_retry_delay: Duration) -> Self {
+        self.max_retry_delay = max_retry_delay;
+        self
+    }
+
+    /// Set [`QueryOptions::refetch_interval`].
+    pub fn with_refetch_interval(mut self, refetch_interval: Duration) -> Self {
+        self.refetch_interval = Some(refetch_interval);
+        self
+    }
+
+    /// Get the delay before the retry `attempt`, starting at zero.
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.retry_delay
+            .checked_mul(factor)
+            .map_or(self.max_retry_delay, |delay| {
+                delay.min(self.max_retry_delay)
+            })
+    }
+}
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+type Erased = Rc<dyn Any>;
+type Fetcher = Rc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<Erased, Erased>>>>>;
+
+struct QueryEntry {
+    value: Option<Erased>,
+    error: Option<Erased>,
+    updated: Option<Instant>,
+    invalidated: bool,
+    fetching: bool,
+    /// Whether the query was invalidated while it was fetched, so the result is outdated.
+    refetch: bool,
+    polling: bool,
+    fetcher: Option<Fetcher>,
+    options: QueryOptions,
+    subscribers: HashSet<ScopeId>,
+}
+
+impl QueryEntry {
+    fn new(options: QueryOptions) -> Self {
+        Self {
+            value: None,
+            error: None,
+            updated: None,
+            invalidated: false,
+            fetching: false,
+            refetch: false,
+            polling: false,
+            fetcher: None,
+            options,
+            subscribers: HashSet::new(),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.invalidated
+            || match self.updated {
+                Some(updated) => updated.elapsed() >= self.options.stale_time,
+                None => true,
+            }
+    }
+}
+
+/// The cache of all queries of an app, see [`use_query`].
+///
+/// The client is created at the root of the app by the first hook that needs it, and can be
+/// accessed with [`use_query_client`] to read, write and invalidate queries from anywhere.
+pub struct QueryClient {
+    this: Weak<QueryClient>,
+    update_any: Arc<dyn Fn(ScopeId)>,
+    defaults: RefCell<QueryOptions>,
+    entries: RefCell<HashMap<QueryKey, QueryEntry>>,
+    /// Fetches and mutations that were started, but aren't run by the [`Driver`] yet.
+    tasks: RefCell<Vec<Task>>,
+    /// Wakes the [`Driver`] when tasks are queued.
+    waker: RefCell<Option<Waker>>,
+    /// Whether the [`Driver`] was spawned.
+    driven: Cell<bool>,
+}
+
+impl QueryClient {
+    /// Create a client that schedules updates of components with `update_any`.
+    pub fn new(update_any: Arc<dyn Fn(ScopeId)>) -> Rc<Self> {
+        Rc::new_cyclic(|this| Self {
+            this: this.clone(),
+            update_any,
+            defaults: RefCell::new(QueryOptions::default()),
+            entries: RefCell::new(HashMap::new()),
+            tasks: RefCell::new(Vec::new()),
+            waker: RefCell::new(None),
+            driven: Cell::new(false),
+        })
+    }
+
+    /// Set the options of queries that don't specify their own.
+    ///
+    /// Only applies to queries that aren't cached yet.
+    pub fn set_default_options(&self, options: QueryOptions) {
+        *self.defaults.borrow_mut() = options;
+    }
+
+    /// Get the cached value of the query `key`.
+    ///
+    /// Returns [`None`] if there is no value, or if it isn't a `T`.
+    pub fn get_query_data<T: 'static>(&self, key: impl Into<QueryKey>) -> Option<Rc<T>> {
+        let entries = self.entries.borrow();
+        let value = entries.get(&key.into())?.value.clone()?;
+        value.downcast().ok()
+    }
+
+    /// Replace the cached value of the query `key`, and update the components using it.
+    ///
+    /// The value counts as freshly fetched.
+    pub fn set_query_data<T: 'static>(&self, key: impl Into<QueryKey>, value: T) {
+        self.set_erased(key.into(), Some(Rc::new(value)));
+    }
+
+    fn set_erased(&self, key: QueryKey, value: Option<Erased>) {
+        let subscribers = {
+            let mut entries = self.entries.borrow_mut();
+            let entry = entries
+                .entry(key)
+                .or_insert_with(|| QueryEntry::new(self.defaults.borrow().clone()));
+            entry.value = value;
+            entry.error = None;
+            entry.updated = Some(Instant::now());
+            entry.invalidated = false;
+            entry.subscribers.clone()
+        };
+        self.notify(subscribers);
+    }
+
+    /// Mark every query whose key starts with `prefix` as stale.
+    ///
+    /// Queries that are used by mounted components are refetched right away, the others once a
+    /// component uses them again.
+    pub fn invalidate(&self, prefix: impl Into<QueryKey>) {
+        let prefix = prefix.into();
+        let keys: Vec<_> = {
+            let mut entries = self.entries.borrow_mut();
+            entries
+                .iter_mut()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, entry)| {
+                    entry.invalidated = true;
+                    entry.refetch = entry.fetching;
+                    key.clone()
+                })
+                .collect()
+        };
+
+        for key in keys {
+            if let Some(fetch) = self.start_fetch(&key, false) {
+                self.queue(fetch);
+            }
+        }
+    }
+
+    /// Remove every query whose key starts with `prefix` from the cache.
+    ///
+    /// Mounted components keep their subscriptions, and fetch the query again.
+    pub fn remove(&self, prefix: impl Into<QueryKey>) {
+        let prefix = prefix.into();
+        let keys: Vec<_> = {
+            let mut entries = self.entries.borrow_mut();
+            entries
+                .iter_mut()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, entry)| {
+                    entry.value = None;
+                    entry.error = None;
+                    entry.updated = None;
+                    key.clone()
+                })
+                .collect()
+        };
+        self.entries
+            .borrow_mut()
+            .retain(|_, entry| entry.value.is_some() || !entry.subscribers.is_empty());
+
+        for key in keys {
+            if let Some(fetch) = self.start_fetch(&key, false) {
+                self.queue(fetch);
+            }
+        }
+    }
+
+    /// Check whether the query `key` is being fetched.
+    pub fn is_fetching(&self, key: impl Into<QueryKey>) -> bool {
+        let entries = self.entries.borrow();
+        matches!(entries.get(&key.into()), Some(entry) if entry.fetching)
+    }
+
+    /// Run `task` with the other fetches and mutations of the client.
+    fn queue(&self, task: Task) {
+        self.tasks.borrow_mut().push(task);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn notify(&self, scopes: impl IntoIterator<Item = ScopeId>) {
+        for scope in scopes {
+            (self.update_any)(scope);
+        }
+    }
+
+    /// Subscribe `scope` to `key`, and register the fetcher of the query.
+    fn subscribe(
+        &self,
+        key: &QueryKey,
+        scope: ScopeId,
+        options: Option<&QueryOptions>,
+        fetcher: Fetcher,
+    ) {
+        let mut entries = self.entries.borrow_mut();
+        let entry = entries.entry(key.clone()).or_insert_with(|| {
+            QueryEntry::new(
+                options
+                    .cloned()
+                    .unwrap_or_else(|| self.defaults.borrow().clone()),
+            )
+        });
+        if let Some(options) = options {
+            entry.options = options.clone();
+        }
+        entry.subscribers.insert(scope);
+        entry.fetcher = Some(fetcher);
+    }
+
+    fn unsubscribe(&self, key: &QueryKey, scope: ScopeId) {
+        if let Some(entry) = self.entries.borrow_mut().get_mut(key) {
+            entry.subscribers.remove(&scope);
+        }
+    }
+
+    /// Start fetching `key`, unless it is being fetched already or - without `force` - is fresh.
+    fn start_fetch(&self, key: &QueryKey, force: bool) -> Option<Task> {
+        let (fetcher, options, subscribers) = {
+            let mut entries = self.entries.borrow_mut();
+            let entry = entries.get_mut(key)?;
+            let idle = entry.subscribers.is_empty() && !force;
+            if entry.fetching || idle || !(force || entry.is_stale()) {
+                return None;
+            }
+            let fetcher = entry.fetcher.clone()?;
+            entry.fetching = true;
+            (fetcher, entry.options.clone(), entry.subscribers.clone())
+        };
+
+        // show that the query is fetching
+        self.notify(subscribers);
+
+        let client = self.this.clone();
+        let key = key.clone();
+        Some(Box::pin(async move {
+            let mut attempt = 0;
+            let result = loop {
+                match fetcher().await {
+                    Err(_) if attempt < options.retries => {
+                        Delay::new(options.delay(attempt)).await;
+                        attempt += 1;
+                    }
+                    result => break result,
+                }
+            };
+
+            if let Some(client) = client.upgrade() {
+                client.finish_fetch(&key, result);
+            }
+        }))
+    }
+
+    /// Store the result of a fetch of `key`, and fetch it again if it was invalidated meanwhile.
+    fn finish_fetch(&self, key: &QueryKey, result: Result<Erased, Erased>) {
+        let (subscribers, refetch) = {
+            let mut entries = self.entries.borrow_mut();
+            let entry = match entries.get_mut(key) {
+                Some(entry) => entry,
+                None => return,
+            };
+            entry.fetching = false;
+            let refetch = std::mem::take(&mut entry.refetch);
+            match result {
+                Ok(value) => {
+                    entry.value = Some(value);
+                    entry.error = None;
+                    entry.updated = Some(Instant::now());
+                    entry.invalidated = refetch;
+                }
+                Err(error) => entry.error = Some(error),
+            }
+            (entry.subscribers.clone(), refetch)
+        };
+        self.notify(subscribers);
+
+        if refetch {
+            if let Some(fetch) = self.start_fetch(key, false) {
+                self.queue(fetch);
+            }
+        }
+    }
+
+    /// Refetch `key` every `interval` in the background, for as long as components use it.
+    fn start_polling(&self, key: &QueryKey) {
+        let interval = {
+            let mut entries = self.entries.borrow_mut();
+            let entry = match entries.get_mut(key) {
+                Some(entry) => entry,
+                None => return,
+            };
+            match entry.options.refetch_interval {
+                Some(interval) if !entry.polling => {
+                    entry.polling = true;
+                    interval
+                }
+                _ => return,
+            }
+        };
+
+        let client = self.this.clone();
+        let key = key.clone();
+        self.queue(Box::pin(async move {
+            loop {
+                Delay::new(interval).await;
+                let client = match client.upgrade() {
+                    Some(client) => client,
+                    None => return,
+                };
+
+                let unused = match client.entries.borrow().get(&key) {
+                    Some(entry) => {
+                        entry.subscribers.is_empty() || entry.options.refetch_interval.is_none()
+                    }
+                    None => true,
+                };
+                if unused {
+                    if let Some(entry) = client.entries.borrow_mut().get_mut(&key) {
+                        entry.polling = false;
+                    }
+                    return;
+                }
+
+                if let Some(fetch) = client.start_fetch(&key, true) {
+                    fetch.await;
+                }
+            }
+        }));
+    }
+
+    fn read<T: 'static, E: 'static>(&self, key: &QueryKey) -> (QueryState<T, E>, bool, bool) {
+        let entries = self.entries.borrow();
+        let entry = match entries.get(key) {
+            Some(entry) => entry,
+            None => return (QueryState::Loading, false, true),
+        };
+
+        let value = entry.value.clone().and_then(|value| value.downcast().ok());
+        let error = entry.error.clone().and_then(|error| error.downcast().ok());
+        let state = match (value, error) {
+            (_, Some(error)) if !entry.fetching => QueryState::Error(error),
+            (Some(value), _) => QueryState::Success(value),
+            _ => QueryState::Loading,
+        };
+        (state, entry.fetching, entry.is_stale())
+    }
+}
+
+/// Get the [`QueryClient`] of the app, creating it at the root if it doesn't exist yet.
+pub fn use_query_client(cx: &ScopeState) -> &Rc<QueryClient> {
+    let client = cx.use_hook(|| match cx.consume_context::<Rc<QueryClient>>() {
+        Some(client) => client,
+        None => cx.provide_root_context(QueryClient::new(cx.schedule_update_any())),
+    });
+
+    // spawned on the root scope, so the tasks keep running when this scope is dropped
+    if !client.driven.replace(true) {
+        cx.spawn_forever(Driver {
+            client: Rc::downgrade(client),
+            running: Vec::new(),
+        });
+    }
+    client
+}
+
+/// Runs the fetches and mutations of a [`QueryClient`], for as long as the client exists.
+///
+/// Tasks can be queued at any time, without waiting for a component to render.
+struct Driver {
+    client: Weak<QueryClient>,
+    running: Vec<Task>,
+}
+
+impl Future for Driver {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let client = match self.client.upgrade() {
+            Some(client) => client,
+            None => return Poll::Ready(()),
+        };
+        client.waker.replace(Some(cx.waker().clone()));
+        let queued = client.tasks.take();
+        drop(client);
+
+        let running = &mut self.running;
+        running.extend(queued);
+        let mut index = 0;
+        while index < running.len() {
+            match running[index].as_mut().poll(cx) {
+                Poll::Ready(()) => drop(running.swap_remove(index)),
+                Poll::Pending => index += 1,
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// The state of a query.
+#[derive(Debug)]
+pub enum QueryState<T, E> {
+    /// The query has no value yet.
+    Loading,
+    /// The query has a value, which might be refetched in the background.
+    Success(Rc<T>),
+    /// The latest fetch failed, after all its retries.
+    Error(Rc<E>),
+}
+
+impl<T, E> Clone for QueryState<T, E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Loading => Self::Loading,
+            Self::Success(value) => Self::Success(value.clone()),
+            Self::Error(error) => Self::Error(error.clone()),
+        }
+    }
+}
+
+/// Fetch the resource `key` with `fetcher`, sharing the result with every component using `key`.
+///
+/// - If another component is fetching `key` already, no second request is made.
+/// - A cached value is returned right away. If it is stale, it is refetched in the background,
+///   and the component updates once the new value arrives.
+/// - Failed fetches are retried with exponential backoff.
+///
+/// The query uses the default options of the [`QueryClient`]. Values are stored by key, so the
+/// same key must always be fetched as the same `T` and `E`.
+///
+/// ```rust, ignore
+/// fn User(cx: Scope<UserProps>) -> Element {
+///     let id = cx.props.id;
+///     let user = use_query(&cx, ["users", &id.to_string()], move || api::fetch_user(id));
+///
+///     cx.render(match user.state() {
+///         QueryState::Loading => rsx!("Loading..."),
+///         QueryState::Success(user) => rsx!("{user.name}"),
+///         QueryState::Error(err) => rsx!("Failed to load the user: {err}"),
+///     })
+/// }
+/// ```
+pub fn use_query<T, E, F>(
+    cx: &ScopeState,
+    key: impl Into<QueryKey>,
+    fetcher: impl Fn() -> F + 'static,
+) -> &UseQuery<T, E>
+where
+    T: 'static,
+    E: 'static,
+    F: Future<Output = Result<T, E>> + 'static,
+{
+    use_query_inner(cx, key.into(), None, fetcher)
+}
+
+/// Like [`use_query`], but with `options` for the query instead of the defaults of the
+/// [`QueryClient`].
+pub fn use_query_with_options<T, E, F>(
+    cx: &ScopeState,
+    key: impl Into<QueryKey>,
+    options: QueryOptions,
+    fetcher: impl Fn() -> F + 'static,
+) -> &UseQuery<T, E>
+where
+    T: 'static,
+    E: 'static,
+    F: Future<Output = Result<T, E>> + 'static,
+{
+    use_query_inner(cx, key.into(), Some(options), fetcher)
+}
+
+fn use_query_inner<T, E, F>(
+    cx: &ScopeState,
+    key: QueryKey,
+    options: Option<QueryOptions>,
+    fetcher: impl Fn() -> F + 'static,
+) -> &UseQuery<T, E>
+where
+    T: 'static,
+    E: 'static,
+    F: Future<Output = Result<T, E>> + 'static,
+{
+    let client = use_query_client(cx).clone();
+    let query = cx.use_hook(|| UseQuery {
+        client,
+        scope: cx.scope_id(),
+        key: RefCell::new(None),
+        state: RefCell::new(QueryState::Loading),
+        fetching: Cell::new(false),
+        stale: Cell::new(true),
+    });
+
+    let fetcher: Fetcher = Rc::new(move || {
+        let fut = fetcher();
+        Box::pin(async move {
+            fut.await
+                .map(|value| Rc::new(value) as Erased)
+                .map_err(|error| Rc::new(error) as Erased)
+        })
+    });
+    query
+        .client
+        .subscribe(&key, query.scope, options.as_ref(), fetcher);
+
+    // fetch when mounting, or when the key changes
+    let changed = query.key.borrow().as_ref() != Some(&key);
+    if changed {
+        if let Some(previous) = query.key.replace(Some(key.clone())) {
+            query.client.unsubscribe(&previous, query.scope);
+        }
+        if let Some(fetch) = query.client.start_fetch(&key, false) {
+            query.client.queue(fetch);
+        }
+        query.client.start_polling(&key);
+    }
+
+    let (state, fetching, stale) = query.client.read(&key);
+    query.state.replace(state);
+    query.fetching.set(fetching);
+    query.stale.set(stale);
+    query
+}
+
+/// A query of the [`QueryClient`], see [`use_query`].
+pub struct UseQuery<T, E> {
+    client: Rc<QueryClient>,
+    scope: ScopeId,
+    key: RefCell<Option<QueryKey>>,
+    state: RefCell<QueryState<T, E>>,
+    fetching: Cell<bool>,
+    stale: Cell<bool>,
+}
+
+impl<T: 'static, E: 'static> UseQuery<T, E> {
+    /// Get the state of the query as of the latest render.
+    pub fn state(&self) -> QueryState<T, E> {
+        self.state.borrow().clone()
+    }
+
+    /// Get the latest value of the query, even if it is stale or a refetch failed.
+    pub fn value(&self) -> Option<Rc<T>> {
+        self.client.get_query_data(self.key())
+    }
+
+    /// Get the error of the latest fetch, if it failed.
+    pub fn error(&self) -> Option<Rc<E>> {
+        match &*self.state.borrow() {
+            QueryState::Error(error) => Some(error.clone()),
+            _ => None,
+        }
+    }
+
+    /// Check whether the query was being fetched as of the latest render.
+    pub fn is_fetching(&self) -> bool {
+        self.fetching.get()
+    }
+
+    /// Check whether the value of the query was stale as of the latest render.
+    pub fn is_stale(&self) -> bool {
+        self.stale.get()
+    }
+
+    /// Get the key of the query.
+    pub fn key(&self) -> QueryKey {
+        self.key.borrow().clone().unwrap_or_default()
+    }
+
+    /// Fetch the query again, even if its value is fresh.
+    pub fn refetch(&self) {
+        if let Some(fetch) = self.client.start_fetch(&self.key(), true) {
+            self.client.queue(fetch);
+        }
+    }
+}
+
+impl<T, E> Drop for UseQuery<T, E> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.get_mut() {
+            self.client.unsubscribe(key, self.scope);
+        }
+    }
+}
+
+/// The state of a mutation.
+#[derive(Debug)]
+pub enum MutationState<T, E> {
+    /// The mutation was never run.
+    Idle,
+    /// The mutation is running.
+    Pending,
+    /// The latest mutation succeeded.
+    Success(Rc<T>),
+    /// The latest mutation failed.
+    Error(Rc<E>),
+}
+
+impl<T, E> Clone for MutationState<T, E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Idle => Self::Idle,
+            Self::Pending => Self::Pending,
+            Self::Success(value) => Self::Success(value.clone()),
+            Self::Error(error) => Self::Error(error.clone()),
+        }
+    }
+}
+
+type Mutate<A, T, E> = dyn Fn(A) -> Pin<Box<dyn Future<Output = Result<T, E>>>>;
+
+/// Change a resource with the async function `mutate`, and keep the cached queries in sync.
+///
+/// Mutations don't touch the cache by themselves: invalidate the affected queries with the
+/// [`QueryClient`] once they are done, or use [`UseMutation::mutate_optimistic`] to update a query
+/// right away.
+///
+/// ```rust, ignore
+/// fn AddTodo(cx: Scope) -> Element {
+///     let add = use_mutation(&cx, |todo: Todo| api::add_todo(todo));
+///
+///     cx.render(rsx!{
+///         button {
+///             onclick: move |_| {
+///                 let todo = Todo::new("Write docs");
+///                 add.mutate_optimistic(todo, "todos", |todos: &Vec<Todo>, todo| {
+///                     let mut todos = todos.clone();
+///                     todos.push(todo.clone());
+///                     todos
+///                 });
+///             },
+///             "Add todo"
+///         }
+///     })
+/// }
+/// ```
+pub fn use_mutation<A, T, E, F>(
+    cx: &ScopeState,
+    mutate: impl Fn(A) -> F + 'static,
+) -> &UseMutation<A, T, E>
+where
+    A: 'static,
+    T: 'static,
+    E: 'static,
+    F: Future<Output = Result<T, E>> + 'static,
+{
+    let client = use_query_client(cx).clone();
+    cx.use_hook(|| UseMutation {
+        client,
+        mutate: Rc::new(move |arg| Box::pin(mutate(arg))),
+        state: Rc::new(RefCell::new(MutationState::Idle)),
+        update: cx.schedule_update(),
+    })
+}
+
+/// A mutation, see [`use_mutation`].
+pub struct UseMutation<A, T, E> {
+    client: Rc<QueryClient>,
+    mutate: Rc<Mutate<A, T, E>>,
+    state: Rc<RefCell<MutationState<T, E>>>,
+    update: Arc<dyn Fn()>,
+}
+
+impl<A: 'static, T: 'static, E: 'static> UseMutation<A, T, E> {
+    /// Get the state of the latest mutation.
+    pub fn state(&self) -> MutationState<T, E> {
+        self.state.borrow().clone()
+    }
+
+    /// Run the mutation with `arg`.
+    pub fn mutate(&self, arg: A) {
+        self.run(arg, |_, _| {});
+    }
+
+    /// Run the mutation with `arg`, and optimistically replace the cached value of the query `key`
+    /// with the value `update` creates from it.
+    ///
+    /// If the mutation fails, the previous value is restored. Either way, `key` is invalidated
+    /// once the mutation is done, to fetch the real value.
+    pub fn mutate_optimistic<Q: 'static>(
+        &self,
+        arg: A,
+        key: impl Into<QueryKey>,
+        update: impl FnOnce(&Q, &A) -> Q,
+    ) {
+        let key = key.into();
+        let previous = self.client.get_query_data::<Q>(&key);
+        if let Some(previous) = &previous {
+            self.client.set_query_data(&key, update(previous, &arg));
+        }
+
+        self.run(arg, move |client, succeeded| {
+            if let (Some(previous), false) = (previous, succeeded) {
+                client.set_erased(key.clone(), Some(previous));
+            }
+            client.invalidate(key);
+        });
+    }
+
+    fn run(&self, arg: A, settle: impl FnOnce(&QueryClient, bool) + 'static) {
+        let fut = (self.mutate)(arg);
+        let state = self.state.clone();
+        let update = self.update.clone();
+        let client = Rc::downgrade(&self.client);
+
+        *self.state.borrow_mut() = MutationState::Pending;
+        self.client.queue(Box::pin(async move {
+            let result = fut.await;
+            let succeeded = result.is_ok();
+            *state.borrow_mut() = match result {
+                Ok(value) => MutationState::Success(Rc::new(value)),
+                Err(error) => MutationState::Error(Rc::new(error)),
+            };
+            if let Some(client) = client.upgrade() {
+                settle(&client, succeeded);
+            }
+            update();
+        }));
+
+        // show that the mutation is pending
+        (self.update)();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_query() {
+        use dioxus_core::prelude::*;
+        use futures::executor::block_on;
+
+        // counts the fetches, the first two of which fail
+        type Fetches = Rc<Cell<u32>>;
+
+        fn app(cx: Scope<Fetches>) -> Element {
+            cx.render(LazyNodes::new(|f| {
+                let children = f.bump().alloc([
+                    f.component(user, UserProps(cx.props.clone()), None, "user"),
+                    f.component(user, UserProps(cx.props.clone()), None, "user"),
+                ]);
+                f.fragment_from_iter(children.iter())
+            }))
+        }
+
+        struct UserProps(Fetches);
+
+        impl Properties for UserProps {
+            type Builder = ();
+            const IS_STATIC: bool = false;
+            fn builder() -> Self::Builder {}
+            unsafe fn memoize(&self, _other: &Self) -> bool {
+                false
+            }
+        }
+
+        fn user(cx: Scope<UserProps>) -> Element {
+            let fetches = cx.props.0.clone();
+            let options = QueryOptions::default().with_retry_delay(Duration::ZERO);
+            let query = use_query_with_options(&cx, ["users", "1"], options, move || {
+                let fetches = fetches.clone();
+                async move {
+                    fetches.set(fetches.get() + 1);
+                    match fetches.get() {
+                        1 | 2 => Err("failed"),
+                        n => Ok(n),
+                    }
+                }
+            });
+
+            let text = match query.state() {
+                QueryState::Success(value) => value.to_string(),
+                _ => "loading".to_string(),
+            };
+            cx.render(LazyNodes::new(move |f| f.text(format_args!("{}", text))))
+        }
+
+        fn work(dom: &mut VirtualDom) {
+            block_on(dom.wait_for_work());
+            dom.work_with_deadline(|| false);
+        }
+
+        let fetches = Fetches::default();
+        let mut dom = VirtualDom::new_with_props(app, fetches.clone());
+        dom.rebuild();
+        let client = dom
+            .base_scope()
+            .consume_context::<Rc<QueryClient>>()
+            .unwrap();
+
+        // one request for both components, retried until it succeeds
+        while client.is_fetching(["users", "1"]) {
+            work(&mut dom);
+        }
+        assert_eq!(fetches.get(), 3);
+        assert_eq!(
+            client.get_query_data::<u32>(["users", "1"]),
+            Some(Rc::new(3))
+        );
+
+        // invalidation refetches without waiting for a component to render
+        dom.process_all_messages();
+        dom.work_with_deadline(|| false);
+        client.invalidate("users");
+        assert!(client.is_fetching(["users", "1"]));
+        block_on(dom.wait_for_work());
+        assert!(!client.is_fetching(["users", "1"]));
+        assert_eq!(fetches.get(), 4);
+        assert_eq!(
+            client.get_query_data::<u32>(["users", "1"]),
+            Some(Rc::new(4))
+        );
+    }
+
+    #[test]
+    fn test_invalidate_while_fetching() {
+        use dioxus_core::prelude::*;
+        use futures::{executor::block_on, future::select};
+
+        // the value on the server, and the number of fetches
+        type Server = Rc<(Cell<u32>, Cell<u32>)>;
+
+        fn app(cx: Scope<Server>) -> Element {
+            let server = cx.props.clone();
+            use_query(&cx, "count", move || {
+                let (value, fetches) = &*server;
+                let value = value.get();
+                fetches.set(fetches.get() + 1);
+                async move {
+                    Delay::new(Duration::from_millis(10)).await;
+                    Ok::<_, ()>(value)
+                }
+            });
+            None
+        }
+
+        fn work(dom: &mut VirtualDom) {
+            block_on(dom.wait_for_work());
+            dom.work_with_deadline(|| false);
+        }
+
+        let server = Server::default();
+        server.0.set(1);
+        let mut dom = VirtualDom::new_with_props(app, server.clone());
+        dom.rebuild();
+        let client = dom
+            .base_scope()
+            .consume_context::<Rc<QueryClient>>()
+            .unwrap();
+
+        // start the fetch, which reads the value before it is changed
+        block_on(select(
+            Box::pin(dom.wait_for_work()),
+            Delay::new(Duration::from_millis(1)),
+        ));
+        assert_eq!(server.1.get(), 1);
+        server.0.set(2);
+        client.invalidate("count");
+
+        // the outdated result isn't kept, the query is fetched again
+        while client.is_fetching("count") {
+            work(&mut dom);
+        }
+        assert_eq!(server.1.get(), 2);
+        assert_eq!(client.get_query_data::<u32>("count"), Some(Rc::new(2)));
+    }
+
+    #[test]
+    fn test_use_mutation() {
+        use dioxus_core::prelude::*;
+        use futures::executor::block_on;
+
+        fn app(cx: Scope) -> Element {
+            let add = use_mutation(&cx, |n: u32| async move {
+                match n {
+                    0 => Err("can't add zero"),
+                    n => Ok(n),
+                }
+            });
+
+            let client = use_query_client(&cx);
+            cx.use_hook(|| {
+                client.set_query_data("count", 10u32);
+                add.mutate_optimistic(0, "count", |count: &u32, n| count + n + 1);
+            });
+            None
+        }
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild();
+        let client = dom
+            .base_scope()
+            .consume_context::<Rc<QueryClient>>()
+            .unwrap();
+        assert_eq!(client.get_query_data::<u32>("count"), Some(Rc::new(11)));
+
+        // the failed mutation restores the previous value
+        block_on(dom.wait_for_work());
+        assert_eq!(client.get_query_data::<u32>("count"), Some(Rc::new(10)));
+
+        // a query that was only written to has nothing to fetch with
+        assert!(client.start_fetch(&"count".into(), true).is_none());
+        assert!(!client.is_fetching("count"));
+    }
+}
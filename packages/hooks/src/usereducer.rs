@@ -0,0 +1,431 @@
+use dioxus_core::ScopeState;
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+};
+
+/// Store state between component renders, and update it by dispatching actions to a reducer.
+///
+/// ## Dioxus equivalent of useReducer, designed for Rust
+///
+/// The reducer receives the state and an action - typically an enum - and updates the state in
+/// place. Every dispatched action re-renders the component. The reducer works on a copy of the
+/// state, so the state can still be held while an action is dispatched. This pattern is very useful when a
+/// single component handles many kinds of input.
+///
+/// The reducer may return an [`Effect`] to dispatch further actions, or to spawn futures that
+/// dispatch actions once they finish. Reducers that don't need effects simply return `()`.
+///
+/// ```rust, ignore
+/// enum Action {
+///     Increment,
+///     Decrement,
+///     Fetch,
+///     Fetched(i32),
+/// }
+///
+/// fn reduce(count: &mut i32, action: Action) -> Effect<Action> {
+///     match action {
+///         Action::Increment => *count += 1,
+///         Action::Decrement => *count -= 1,
+///         Action::Fetch => return Effect::future(|dispatcher| async move {
+///             dispatcher.dispatch(Action::Fetched(fetch_count().await));
+///         }),
+///         Action::Fetched(new) => *count = new,
+///     }
+///     Effect::none()
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     let count = use_reducer(&cx, || 0, reduce);
+///
+///     cx.render(rsx! {
+///         h1 { "Count: {count.state()}" }
+///         button { onclick: move |_| count.dispatch(Action::Increment), "Increment" }
+///         button { onclick: move |_| count.dispatch(Action::Decrement), "Decrement" }
+///         button { onclick: move |_| count.dispatch(Action::Fetch), "Fetch" }
+///     })
+/// }
+/// ```
+pub fn use_reducer<S, A, E>(
+    cx: &ScopeState,
+    initial_state_fn: impl FnOnce() -> S,
+    reducer: impl Fn(&mut S, A) -> E + 'static,
+) -> &UseReducer<S, A>
+where
+    S: Clone + 'static,
+    A: 'static,
+    E: Into<Effect<A>>,
+{
+    use_reducer_with_middleware(cx, initial_state_fn, reducer, Vec::new)
+}
+
+/// Like [`use_reducer`], but every action passes through `middleware` before it reaches the
+/// reducer.
+///
+/// Middleware is applied in order: the first middleware receives the dispatched action, and hands
+/// it on to the next one, until the last one hands it to the reducer. Middleware can inspect or
+/// replace the state and the action, and skip the reducer entirely.
+///
+/// ```rust, ignore
+/// let count = use_reducer_with_middleware(&cx, || 0, reduce, || {
+///     vec![log_actions(), undo(|action| matches!(action, Action::Undo), 100)]
+/// });
+/// ```
+pub fn use_reducer_with_middleware<S, A, E>(
+    cx: &ScopeState,
+    initial_state_fn: impl FnOnce() -> S,
+    reducer: impl Fn(&mut S, A) -> E + 'static,
+    middleware: impl FnOnce() -> Vec<Middleware<S, A>>,
+) -> &UseReducer<S, A>
+where
+    S: Clone + 'static,
+    A: 'static,
+    E: Into<Effect<A>>,
+{
+    let hook = cx.use_hook(move || {
+        let inner = Rc::new(ReducerInner {
+            state: RefCell::new(Rc::new(initial_state_fn())),
+            reducer: Box::new(move |state, action| reducer(state, action).into()),
+            middleware: middleware(),
+            queue: RefCell::new(VecDeque::new()),
+            dispatching: Cell::new(false),
+            effects: RefCell::new(Vec::new()),
+            update_callback: cx.schedule_update(),
+        });
+
+        let dispatcher = Dispatcher {
+            dispatch: Rc::new({
+                let inner = inner.clone();
+                move |action| inner.dispatch(action)
+            }),
+        };
+
+        UseReducer { inner, dispatcher }
+    });
+
+    // spawn the futures of the effects since the last render
+    for effect in hook.inner.effects.take() {
+        cx.push_future(effect(hook.dispatcher.clone()));
+    }
+
+    hook
+}
+
+pub struct UseReducer<S: 'static, A: 'static> {
+    inner: Rc<ReducerInner<S, A>>,
+    dispatcher: Dispatcher<A>,
+}
+
+impl<S: 'static, A: 'static> UseReducer<S, A> {
+    /// Get the current state.
+    ///
+    /// Dispatched actions don't change the returned state, but replace it for later calls.
+    pub fn state(&self) -> Rc<S> {
+        self.inner.state.borrow().clone()
+    }
+
+    /// Dispatch `action` to the reducer, and re-render the component.
+    pub fn dispatch(&self, action: A) {
+        self.dispatcher.dispatch(action);
+    }
+
+    /// Get a [`Dispatcher`] that can be moved into spawned tasks and child components.
+    #[must_use]
+    pub fn dispatcher(&self) -> Dispatcher<A> {
+        self.dispatcher.clone()
+    }
+}
+
+/// Dispatches actions to the reducer of a [`UseReducer`].
+///
+/// Dispatchers are cheap to clone, and keep working in spawned tasks after the component
+/// re-renders.
+pub struct Dispatcher<A> {
+    dispatch: Rc<dyn Fn(A)>,
+}
+
+impl<A> Clone for Dispatcher<A> {
+    fn clone(&self) -> Self {
+        Self {
+            dispatch: self.dispatch.clone(),
+        }
+    }
+}
+
+impl<A> Dispatcher<A> {
+    /// Dispatch `action` to the reducer, and re-render the component.
+    ///
+    /// Actions dispatched while another action is reduced are reduced right after it.
+    pub fn dispatch(&self, action: A) {
+        (self.dispatch)(action);
+    }
+}
+
+/// Creates the future of an effect, given a dispatcher for further actions.
+type EffectFuture<A> = Box<dyn FnOnce(Dispatcher<A>) -> Pin<Box<dyn Future<Output = ()>>>>;
+
+/// Work to do after an action was reduced.
+///
+/// Created by reducers and middleware. `()` converts into an effect that does nothing.
+#[must_use]
+pub struct Effect<A> {
+    actions: Vec<A>,
+    futures: Vec<EffectFuture<A>>,
+}
+
+impl<A> Effect<A> {
+    /// An effect that does nothing.
+    pub fn none() -> Self {
+        Self {
+            actions: Vec::new(),
+            futures: Vec::new(),
+        }
+    }
+
+    /// Dispatch `action` right after the current action was reduced.
+    pub fn action(action: A) -> Self {
+        Self {
+            actions: vec![action],
+            futures: Vec::new(),
+        }
+    }
+
+    /// Spawn the future returned by `f` on the component, which can dispatch further actions.
+    ///
+    /// The future is spawned when the component re-renders, and is canceled when the component is
+    /// dropped.
+    pub fn future<F>(f: impl FnOnce(Dispatcher<A>) -> F + 'static) -> Self
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        Self {
+            actions: Vec::new(),
+            futures: vec![Box::new(move |dispatcher| Box::pin(f(dispatcher)))],
+        }
+    }
+
+    /// Combine this effect with `other`, running both of them.
+    pub fn and(mut self, other: Self) -> Self {
+        self.actions.extend(other.actions);
+        self.futures.extend(other.futures);
+        self
+    }
+}
+
+impl<A> Default for Effect<A> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<A> From<()> for Effect<A> {
+    fn from(_: ()) -> Self {
+        Self::none()
+    }
+}
+
+/// Hands an action on to the next middleware, or to the reducer.
+pub type Next<'a, S, A> = &'a dyn Fn(&mut S, A) -> Effect<A>;
+
+/// Wraps the reducer of a [`UseReducer`], see [`use_reducer_with_middleware`].
+pub type Middleware<S, A> = Box<dyn Fn(&mut S, A, Next<'_, S, A>) -> Effect<A>>;
+
+/// Middleware that logs every action and the resulting state at the debug level.
+pub fn log_actions<S: Debug, A: Debug>() -> Middleware<S, A> {
+    Box::new(|state, action, next| {
+        log::debug!("dispatching {:?}", action);
+        let effect = next(state, action);
+        log::debug!("new state {:?}", state);
+        effect
+    })
+}
+
+/// Middleware that restores the previous state when an action matching `is_undo` is dispatched.
+///
+/// At most `limit` previous states are kept. Undo actions don't reach the reducer.
+pub fn undo<S: Clone + 'static, A: 'static>(
+    is_undo: fn(&A) -> bool,
+    limit: usize,
+) -> Middleware<S, A> {
+    let history = RefCell::new(VecDeque::new());
+
+    Box::new(move |state, action, next| {
+        let mut history = history.borrow_mut();
+
+        if is_undo(&action) {
+            if let Some(previous) = history.pop_back() {
+                *state = previous;
+            }
+            return Effect::none();
+        }
+
+        history.push_back(state.clone());
+        if history.len() > limit {
+            history.pop_front();
+        }
+        drop(history);
+
+        next(state, action)
+    })
+}
+
+/// A reducer with its effect converted into an [`Effect`].
+type Reducer<S, A> = dyn Fn(&mut S, A) -> Effect<A>;
+
+struct ReducerInner<S, A> {
+    state: RefCell<Rc<S>>,
+    reducer: Box<Reducer<S, A>>,
+    middleware: Vec<Middleware<S, A>>,
+    /// Actions waiting to be reduced.
+    queue: RefCell<VecDeque<A>>,
+    dispatching: Cell<bool>,
+    /// Effects waiting to be spawned on the next render.
+    effects: RefCell<Vec<EffectFuture<A>>>,
+    update_callback: Arc<dyn Fn()>,
+}
+
+impl<S: Clone, A> ReducerInner<S, A> {
+    fn dispatch(&self, action: A) {
+        self.queue.borrow_mut().push_back(action);
+        // the action is reduced by the dispatch that is already running
+        if self.dispatching.replace(true) {
+            return;
+        }
+
+        loop {
+            let action = match self.queue.borrow_mut().pop_front() {
+                Some(action) => action,
+                None => break,
+            };
+
+            // reduce a copy, so the state isn't borrowed while the reducer runs
+            let mut state = S::clone(&self.state.borrow());
+            let effect = apply(&self.middleware, &*self.reducer, &mut state, action);
+            *self.state.borrow_mut() = Rc::new(state);
+
+            self.queue.borrow_mut().extend(effect.actions);
+            self.effects.borrow_mut().extend(effect.futures);
+        }
+
+        self.dispatching.set(false);
+        (self.update_callback)();
+    }
+}
+
+/// Hand `action` to the first middleware of `middleware`, or to `reducer` if there is none.
+fn apply<S, A>(
+    middleware: &[Middleware<S, A>],
+    reducer: &Reducer<S, A>,
+    state: &mut S,
+    action: A,
+) -> Effect<A> {
+    match middleware.split_first() {
+        Some((first, rest)) => first(state, action, &|state, action| {
+            apply(rest, reducer, state, action)
+        }),
+        None => reducer(state, action),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_reducer() {
+        use dioxus_core::{prelude::*, DomEdit};
+        use futures::executor::block_on;
+
+        #[derive(Debug)]
+        enum Action {
+            Increment,
+            Add(i32),
+            AddLater(i32),
+            Twice(Box<Action>),
+            Undo,
+        }
+
+        fn reduce(count: &mut i32, action: Action) -> Effect<Action> {
+            match action {
+                Action::Increment => *count += 1,
+                Action::Add(n) => *count += n,
+                Action::AddLater(n) => {
+                    return Effect::future(move |dispatcher| async move {
+                        dispatcher.dispatch(Action::Add(n));
+                    })
+                }
+                Action::Twice(action) => {
+                    return Effect::action(*action).and(Effect::action(Action::Increment))
+                }
+                Action::Undo => {}
+            }
+            Effect::none()
+        }
+
+        fn app(cx: Scope) -> Element {
+            let count = use_reducer_with_middleware(
+                &cx,
+                || 0,
+                reduce,
+                || {
+                    vec![
+                        log_actions(),
+                        undo(|action| matches!(action, Action::Undo), 10),
+                    ]
+                },
+            );
+            let renders = cx.use_hook(|| Cell::new(0));
+            renders.set(renders.get() + 1);
+
+            // the state can be held while actions are dispatched
+            let state = count.state();
+            match renders.get() {
+                1 => {
+                    count.dispatch(Action::Increment);
+                    count.dispatch(Action::Twice(Box::new(Action::Add(3))));
+                }
+                2 => {
+                    count.dispatch(Action::Undo);
+                    count.dispatch(Action::Undo);
+                }
+                3 => count.dispatch(Action::AddLater(2)),
+                _ => {}
+            }
+
+            cx.render(LazyNodes::new(move |f| {
+                f.text(format_args!("count: {}", state))
+            }))
+        }
+
+        // render the dirty components, and collect the texts they changed
+        fn work(dom: &mut VirtualDom) -> Vec<String> {
+            dom.process_all_messages();
+            dom.work_with_deadline(|| false)
+                .into_iter()
+                .flat_map(|mutations| mutations.edits)
+                .filter_map(|edit| match edit {
+                    DomEdit::SetText { text, .. } => Some(text.to_string()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild();
+
+        // actions are reduced right away, and re-render the component once
+        assert_eq!(work(&mut dom), ["count: 5"]);
+        assert_eq!(work(&mut dom), ["count: 1"]);
+
+        // the effect is spawned on the next render, and dispatches from the spawned task
+        assert!(work(&mut dom).is_empty());
+        block_on(dom.wait_for_work());
+        assert_eq!(work(&mut dom), ["count: 3"]);
+    }
+}
@@ -0,0 +1,335 @@
+use dioxus_core::{bind_read, rendering_scope, BindingId, SchedulerMsg, ScopeId, ScopeState};
+use futures_channel::mpsc::UnboundedSender;
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::HashSet,
+    fmt::{Debug, Display},
+    rc::Rc,
+    sync::Arc,
+};
+
+/// Store a value that updates the UI with fine-grained precision.
+///
+/// Reading the signal in a text node or an attribute subscribes just that node: writing the
+/// signal sends a `SetText` or `SetAttribute` edit without running the component again. Reading it
+/// anywhere else - for example in control flow via [`Signal::read`] - subscribes the whole
+/// component, which is re-rendered on writes.
+///
+/// This makes signals a good fit for values that change often, like the live counters of a
+/// dashboard.
+///
+/// ```rust, ignore
+/// fn Counter(cx: Scope) -> Element {
+///     let count = use_signal(&cx, || 0);
+///
+///     // writing `count` only updates the text of the h1
+///     cx.render(rsx! {
+///         h1 { "Count: {count}" }
+///         button { onclick: move |_| count.with_mut(|c| *c += 1), "Increment" }
+///     })
+/// }
+/// ```
+pub fn use_signal<T: 'static>(cx: &ScopeState, initial_state_fn: impl FnOnce() -> T) -> &Signal<T> {
+    cx.use_hook(|| Signal {
+        inner: Rc::new(SignalInner {
+            value: RefCell::new(initial_state_fn()),
+            format: Cell::new(None),
+            scopes: RefCell::new(HashSet::new()),
+            bindings: RefCell::new(Vec::new()),
+            is_live: cx.binding_liveness(),
+            update_any: cx.schedule_update_any(),
+            scheduler: cx.scheduler_channel(),
+        }),
+    })
+}
+
+/// A value that updates the text nodes and attributes it is read in, see [`use_signal`].
+pub struct Signal<T: 'static> {
+    inner: Rc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    /// Formats the value for bound nodes, once the value was read in one.
+    format: Cell<Option<Format<T>>>,
+    /// The components that read the value outside of text nodes and attributes.
+    scopes: RefCell<HashSet<ScopeId>>,
+    /// The reads of the value in text nodes and attributes.
+    bindings: RefCell<Vec<BindingId>>,
+    /// Checks whether a binding is still valid, so outdated ones can be dropped.
+    is_live: Rc<dyn Fn(BindingId) -> bool>,
+    update_any: Arc<dyn Fn(ScopeId)>,
+    scheduler: UnboundedSender<SchedulerMsg>,
+}
+
+type Format<T> = fn(&T) -> String;
+
+impl<T: 'static> Signal<T> {
+    /// Read the value, and re-render the component that is rendering whenever it changes.
+    pub fn read(&self) -> Ref<'_, T> {
+        self.inner.subscribe_rendering_scope();
+        self.inner.value.borrow()
+    }
+
+    /// Read the value without subscribing to it.
+    pub fn peek(&self) -> Ref<'_, T> {
+        self.inner.value.borrow()
+    }
+
+    /// Set the value, and update everything that read it.
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = value;
+        self.inner.notify();
+    }
+
+    /// Modify the value in place, and update everything that read it.
+    pub fn with_mut(&self, f: impl FnOnce(&mut T)) {
+        f(&mut *self.inner.value.borrow_mut());
+        self.inner.notify();
+    }
+}
+
+impl<T> SignalInner<T> {
+    fn subscribe_rendering_scope(&self) {
+        if let Some(scope) = rendering_scope() {
+            self.scopes.borrow_mut().insert(scope);
+        }
+    }
+
+    /// Add the read `binding`, dropping the outdated bindings.
+    fn bind(&self, binding: BindingId) {
+        let mut bindings = self.bindings.borrow_mut();
+        bindings.retain(|b| match b.scope() == binding.scope() {
+            // the reads of earlier renders of the scope are replaced by the new ones
+            true => b.same_render(&binding),
+            false => (self.is_live)(*b),
+        });
+        bindings.push(binding);
+    }
+
+    fn notify(&self) {
+        // components subscribe again when they re-render
+        for scope in self.scopes.take() {
+            (self.update_any)(scope);
+        }
+
+        // the components of outdated bindings re-rendered or were unmounted
+        let mut bindings = self.bindings.borrow_mut();
+        bindings.retain(|binding| (self.is_live)(*binding));
+        if let (Some(format), false) = (self.format.get(), bindings.is_empty()) {
+            let text = format(&*self.value.borrow());
+            for binding in bindings.iter() {
+                let _ = self
+                    .scheduler
+                    .unbounded_send(SchedulerMsg::Binding(*binding, text.clone()));
+            }
+        }
+    }
+}
+
+impl<T: Display + 'static> Display for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // bound nodes are updated with the plain text, which ignores the formatting options
+        if has_options(f) {
+            self.inner.subscribe_rendering_scope();
+            return self.inner.value.borrow().fmt(f);
+        }
+
+        let text = self.inner.value.borrow().to_string();
+        let inner = self.inner.clone();
+        let bound = bind_read(text.len(), move |binding| match binding {
+            Some(binding) => {
+                inner.format.set(Some(|value: &T| value.to_string()));
+                inner.bind(binding);
+            }
+            None => inner.subscribe_rendering_scope(),
+        });
+        if !bound {
+            self.inner.subscribe_rendering_scope();
+        }
+
+        f.write_str(&text)
+    }
+}
+
+/// Check whether `f` formats with any other options than the defaults.
+fn has_options(f: &std::fmt::Formatter<'_>) -> bool {
+    f.sign_plus()
+        || f.sign_minus()
+        || f.alternate()
+        || f.sign_aware_zero_pad()
+        || f.fill() != ' '
+        || f.align().is_some()
+        || f.width().is_some()
+        || f.precision().is_some()
+}
+
+impl<T: Debug + 'static> Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.read().fmt(f)
+    }
+}
+
+impl<T: 'static> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: 'static> PartialEq for Signal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_signal() {
+        use dioxus_core::{prelude::*, AttributeValue, DomEdit};
+
+        type Signals = Rc<RefCell<Option<(Signal<i32>, Signal<bool>)>>>;
+
+        fn app(cx: Scope<Signals>) -> Element {
+            let count = use_signal(&cx, || 0);
+            let visible = use_signal(&cx, || true);
+            cx.props.replace(Some((count.clone(), visible.clone())));
+
+            // reading a signal in control flow subscribes the component
+            if !*visible.read() {
+                return None;
+            }
+
+            cx.render(LazyNodes::new(|f| {
+                let attributes =
+                    f.bump()
+                        .alloc([f.attr("data-count", format_args!("{}", count), None, false)]);
+                let children = f.bump().alloc([f.text(format_args!("count: {}!", count))]);
+                f.raw_element("div", None, &[], attributes, children, None)
+            }))
+        }
+
+        let signals = Signals::default();
+        let mut dom = VirtualDom::new_with_props(app, signals.clone());
+        dom.rebuild();
+        let (count, visible) = signals.borrow().clone().unwrap();
+
+        // bound nodes are updated without rendering the component
+        count.set(5);
+        dom.process_all_messages();
+        let mutations = dom.work_with_deadline(|| false);
+        let edits: Vec<_> = mutations.iter().flat_map(|m| &m.edits).collect();
+        assert!(edits.contains(&&DomEdit::SetText {
+            root: 2,
+            text: "count: 5!"
+        }));
+        assert!(edits.contains(&&DomEdit::SetAttribute {
+            root: 1,
+            field: "data-count",
+            value: AttributeValue::Text("5"),
+            ns: None,
+        }));
+        assert!(mutations.iter().all(|m| m.dirty_scopes.is_empty()));
+        assert_eq!(count.inner.bindings.borrow().len(), 2);
+
+        // the bindings of a render are dropped once the component rendered again
+        visible.set(false);
+        dom.process_all_messages();
+        dom.work_with_deadline(|| false);
+        count.set(2);
+        dom.process_all_messages();
+        assert!(dom.work_with_deadline(|| false).is_empty());
+        assert!(count.inner.bindings.borrow().is_empty());
+
+        visible.set(true);
+        dom.process_all_messages();
+        dom.work_with_deadline(|| false);
+        assert_eq!(count.inner.bindings.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_nested_attributes() {
+        use dioxus_core::{prelude::*, AttributeValue, DomEdit};
+
+        type Count = Rc<RefCell<Option<Signal<i32>>>>;
+
+        fn app(cx: Scope<Count>) -> Element {
+            let count = use_signal(&cx, || 0);
+            cx.props.replace(Some(count.clone()));
+
+            // the attributes of the outer element are formatted before the inner element is created
+            cx.render(LazyNodes::new(|f| {
+                let outer =
+                    f.bump()
+                        .alloc([f.attr("data-outer", format_args!("{}", count), None, false)]);
+                let inner =
+                    f.bump()
+                        .alloc([f.attr("data-inner", format_args!("{}", count), None, false)]);
+                let children = f
+                    .bump()
+                    .alloc([f.raw_element("span", None, &[], inner, &[], None)]);
+                f.raw_element("div", None, &[], outer, children, None)
+            }))
+        }
+
+        let count = Count::default();
+        let mut dom = VirtualDom::new_with_props(app, count.clone());
+        dom.rebuild();
+        let count = count.borrow().clone().unwrap();
+
+        count.set(3);
+        dom.process_all_messages();
+        let mutations = dom.work_with_deadline(|| false);
+        let edits: Vec<_> = mutations.iter().flat_map(|m| &m.edits).collect();
+        assert!(edits.contains(&&DomEdit::SetAttribute {
+            root: 1,
+            field: "data-outer",
+            value: AttributeValue::Text("3"),
+            ns: None,
+        }));
+        assert!(edits.contains(&&DomEdit::SetAttribute {
+            root: 2,
+            field: "data-inner",
+            value: AttributeValue::Text("3"),
+            ns: None,
+        }));
+        assert!(mutations.iter().all(|m| m.dirty_scopes.is_empty()));
+    }
+
+    #[test]
+    fn test_formatting_options() {
+        use dioxus_core::{prelude::*, DomEdit};
+
+        type Count = Rc<RefCell<Option<Signal<i32>>>>;
+
+        fn app(cx: Scope<Count>) -> Element {
+            let count = use_signal(&cx, || 0);
+            cx.props.replace(Some(count.clone()));
+
+            // the sign can't be kept by bound nodes, so the component is subscribed
+            cx.render(LazyNodes::new(|f| f.text(format_args!("{:+}", count))))
+        }
+
+        let count = Count::default();
+        let mut dom = VirtualDom::new_with_props(app, count.clone());
+        dom.rebuild();
+        let count = count.borrow().clone().unwrap();
+        assert!(count.inner.bindings.borrow().is_empty());
+
+        count.set(5);
+        dom.process_all_messages();
+        let mutations = dom.work_with_deadline(|| false);
+        let edits: Vec<_> = mutations.iter().flat_map(|m| &m.edits).collect();
+        assert!(edits.contains(&&DomEdit::SetText {
+            root: 1,
+            text: "+5"
+        }));
+        assert!(mutations
+            .iter()
+            .any(|m| m.dirty_scopes.contains(&ScopeId(0))));
+    }
+}
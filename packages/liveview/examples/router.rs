@@ -0,0 +1,65 @@
+#[cfg(not(all(feature = "axum", feature = "router")))]
+fn main() {}
+
+#[cfg(all(feature = "axum", feature = "router"))]
+#[tokio::main]
+async fn main() {
+    use axum::{extract::ws::WebSocketUpgrade, response::Html, routing::get, Router};
+    use dioxus::prelude::*;
+    use dioxus_liveview::use_liveview_history;
+    use dioxus_router::prelude::*;
+    pretty_env_logger::init();
+
+    fn app(cx: Scope) -> Element {
+        let routes = use_segment(&cx, || {
+            Segment::new()
+                .index(Home as Component)
+                .fixed("other", Other as Component)
+        });
+        let history = use_liveview_history(&cx);
+
+        cx.render(rsx! {
+            Router {
+                routes: routes.clone(),
+                history: history,
+
+                nav {
+                    Link { target: "/", "Home" }
+                    Link { target: "/other", "Other" }
+                }
+                Outlet { }
+            }
+        })
+    }
+
+    #[allow(non_snake_case)]
+    fn Home(cx: Scope) -> Element {
+        cx.render(rsx! { h1 { "Home" } })
+    }
+
+    #[allow(non_snake_case)]
+    fn Other(cx: Scope) -> Element {
+        cx.render(rsx! { h1 { "Other" } })
+    }
+
+    let addr: std::net::SocketAddr = ([127, 0, 0, 1], 3030).into();
+
+    let view = dioxus_liveview::new(addr);
+    let body = view.body("<title>Dioxus Liveview</title>");
+
+    // the body is served for all paths, so the app can be opened at any of them
+    let app = Router::new()
+        .route(
+            "/app",
+            get(move |ws: WebSocketUpgrade| async move {
+                ws.on_upgrade(move |socket| async move {
+                    view.upgrade_axum(socket, app).await;
+                })
+            }),
+        )
+        .fallback(get(move || async { Html(body) }));
+    axum::Server::bind(&addr.to_string().parse().unwrap())
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
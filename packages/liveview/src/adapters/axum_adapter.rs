@@ -1,4 +1,4 @@
-use crate::events;
+use crate::{events, history};
 use axum::extract::ws::{Message, WebSocket};
 use dioxus_core::prelude::*;
 use futures_util::{
@@ -35,12 +35,20 @@ pub async fn connect<T>(
     T: Send + Sync + 'static,
 {
     let (mut user_ws_tx, mut user_ws_rx) = socket.split();
+    // the client sends its location once the socket is open
+    let location = match user_ws_rx.next().await {
+        Some(Ok(msg)) => history::initial_location(msg.to_text().ok()),
+        _ => return,
+    };
     let (event_tx, event_rx) = mpsc::unbounded_channel();
     let (edits_tx, edits_rx) = mpsc::unbounded_channel();
+    let history = history::HistoryBridge::new(location, edits_tx.clone());
     let mut edits_rx = UnboundedReceiverStream::new(edits_rx);
     let mut event_rx = UnboundedReceiverStream::new(event_rx);
+    let vdom_history = history.clone();
     let vdom_fut = pool.clone().spawn_pinned(move || async move {
         let mut vdom = VirtualDom::new_with_props(app, props);
+        vdom.base_scope().provide_context(vdom_history);
         let edits = vdom.rebuild();
         let serialized = serde_json::to_string(&edits.edits).unwrap();
         edits_tx.send(serialized).unwrap();
@@ -69,8 +77,14 @@ pub async fn connect<T>(
             Either::Left((l, _)) => {
                 if let Some(Ok(msg)) = l {
                     if let Ok(Some(msg)) = msg.to_text().map(events::parse_ipc_message) {
-                        let user_event = events::trigger_from_serialized(msg.params);
-                        event_tx.send(user_event).unwrap();
+                        match msg.method.as_str() {
+                            "user_event" => {
+                                let user_event = events::trigger_from_serialized(msg.params);
+                                event_tx.send(user_event).unwrap();
+                            }
+                            "popstate" => history.handle_popstate(&msg.params),
+                            _ => {}
+                        }
                     } else {
                         break;
                     }
@@ -1,4 +1,4 @@
-use crate::events;
+use crate::{events, history};
 use dioxus_core::prelude::*;
 use futures_util::{pin_mut, SinkExt, StreamExt};
 use tokio::sync::mpsc;
@@ -35,14 +35,25 @@ pub async fn connect<T>(
     // Split the socket into a sender and receive of messages.
     let (mut user_ws_tx, mut user_ws_rx) = ws.split();
 
+    // The client sends its location once the socket is open.
+    let location = match user_ws_rx.next().await {
+        Some(Ok(msg)) => history::initial_location(msg.to_str().ok()),
+        _ => return,
+    };
+
     let (event_tx, event_rx) = mpsc::unbounded_channel();
     let (edits_tx, edits_rx) = mpsc::unbounded_channel();
 
+    // Keeps the browser URL in sync, commands are sent alongside the edits.
+    let history = history::HistoryBridge::new(location, edits_tx.clone());
+
     let mut edits_rx = UnboundedReceiverStream::new(edits_rx);
     let mut event_rx = UnboundedReceiverStream::new(event_rx);
 
+    let vdom_history = history.clone();
     let vdom_fut = pool.spawn_pinned(move || async move {
         let mut vdom = VirtualDom::new_with_props(app, props);
+        vdom.base_scope().provide_context(vdom_history);
 
         let edits = vdom.rebuild();
 
@@ -82,9 +93,13 @@ pub async fn connect<T>(
             Either::Left((l, _)) => {
                 if let Some(Ok(msg)) = l {
                     if let Ok(Some(msg)) = msg.to_str().map(events::parse_ipc_message) {
-                        if msg.method == "user_event" {
-                            let user_event = events::trigger_from_serialized(msg.params);
-                            event_tx.send(user_event).unwrap();
+                        match msg.method.as_str() {
+                            "user_event" => {
+                                let user_event = events::trigger_from_serialized(msg.params);
+                                event_tx.send(user_event).unwrap();
+                            }
+                            "popstate" => history.handle_popstate(&msg.params),
+                            _ => {}
                         }
                     } else {
                         break;
@@ -0,0 +1,385 @@
+//! Keep the URL of the browser in sync with the app running on the server.
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::events::{self, IpcMessage};
+
+struct HistoryCore {
+    callback: Option<Arc<dyn Fn() + Send + Sync>>,
+    location: String,
+}
+
+// [`Fn() + Send + Sync`] (in `callback`) doesn't implement [`Debug`]
+impl Debug for HistoryCore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistoryCore")
+            .field("callback", &self.callback.is_some())
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+/// Connects the history of a liveview session to the browser.
+///
+/// Every session provides one as a root context. It knows the current location of the browser and
+/// sends history commands to the client over the websocket.
+#[derive(Clone, Debug)]
+pub(crate) struct HistoryBridge {
+    core: Arc<Mutex<HistoryCore>>,
+    sender: UnboundedSender<String>,
+}
+
+impl HistoryBridge {
+    /// Create a new [`HistoryBridge`], starting at `location`.
+    ///
+    /// Commands are sent through `sender`, which is the same channel the edits are sent through.
+    /// This ensures commands and edits arrive at the client in the order they were created in.
+    pub(crate) fn new(location: String, sender: UnboundedSender<String>) -> Self {
+        Self {
+            core: Arc::new(Mutex::new(HistoryCore {
+                callback: None,
+                location,
+            })),
+            sender,
+        }
+    }
+
+    /// Get the current location of the browser, consisting of the path, query and fragment.
+    pub(crate) fn location(&self) -> String {
+        self.core.lock().unwrap().location.clone()
+    }
+
+    /// Set the function to call when the browser navigates on its own.
+    pub(crate) fn set_callback(&self, callback: Arc<dyn Fn() + Send + Sync>) {
+        self.core.lock().unwrap().callback = Some(callback);
+    }
+
+    /// Send a command to the client and update the current location to `location`.
+    pub(crate) fn navigate(&self, method: &str, location: String) {
+        self.send(method, json!({ "url": location }));
+        self.core.lock().unwrap().location = location;
+    }
+
+    /// Send a command to the client.
+    pub(crate) fn send(&self, method: &str, params: Value) {
+        let message = IpcMessage {
+            method: method.to_string(),
+            params,
+        };
+
+        // the session is over if the client is gone, no need to inform anybody
+        let _ = self.sender.send(serde_json::to_string(&message).unwrap());
+    }
+
+    /// Handle a `popstate` message sent by the client.
+    pub(crate) fn handle_popstate(&self, params: &Value) {
+        let location = match location_from_params(params) {
+            Some(location) => location,
+            None => return,
+        };
+
+        let callback = {
+            let mut core = self.core.lock().unwrap();
+            core.location = location;
+            core.callback.clone()
+        };
+
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+}
+
+/// Get the initial location from the `initialize` message the client sends once the websocket is
+/// open.
+///
+/// Falls back to `/` if the message isn't an `initialize` message.
+pub(crate) fn initial_location(message: Option<&str>) -> String {
+    message
+        .and_then(events::parse_ipc_message)
+        .filter(|msg| msg.method == "initialize")
+        .and_then(|msg| location_from_params(&msg.params))
+        .unwrap_or_else(|| String::from("/"))
+}
+
+fn location_from_params(params: &Value) -> Option<String> {
+    params["location"].as_str().map(|l| l.to_string())
+}
+
+#[cfg(feature = "router")]
+pub use router::*;
+
+#[cfg(feature = "router")]
+mod router {
+    use std::sync::Arc;
+
+    use dioxus_core::ScopeState;
+    use dioxus_router::history::{HistoryProvider, MemoryHistory};
+    use log::error;
+    use url::{Position, Url};
+
+    use super::HistoryBridge;
+
+    /// A [`HistoryProvider`] that keeps the URL of the browser in sync with a router running in a
+    /// liveview session.
+    ///
+    /// Navigations caused by the router are sent to the client, which applies them via the
+    /// [History API]. When the user navigates back or forward in the browser, the router is
+    /// informed and updates accordingly. The router starts at the URL the page was opened with.
+    ///
+    /// Because the page can be opened at any path, the HTTP server must serve the liveview body
+    /// for all paths the router handles.
+    ///
+    /// A [`LiveviewHistory`] is created via [`use_liveview_history`].
+    ///
+    /// [History API]: https://developer.mozilla.org/en-US/docs/Web/API/History_API
+    #[derive(Debug)]
+    pub struct LiveviewHistory {
+        bridge: HistoryBridge,
+    }
+
+    impl LiveviewHistory {
+        /// Get the current URL.
+        fn url(&self) -> Url {
+            // the location always comes from a valid url, either in the browser or in here
+            base().join(&self.bridge.location()).unwrap()
+        }
+
+        /// Resolve `path` relative to the current URL.
+        ///
+        /// Returns the path, query and fragment of the resulting URL.
+        fn resolve(&self, path: &str) -> Option<String> {
+            if path.starts_with("//") {
+                error!(r#"cannot navigate to paths starting with "//", path: {path}"#);
+                return None;
+            }
+
+            match self.url().join(path) {
+                Ok(url) => Some(url[Position::BeforePath..].to_string()),
+                Err(e) => {
+                    error!("cannot navigate to invalid path, path: {path}, error: {e}");
+                    None
+                }
+            }
+        }
+    }
+
+    impl HistoryProvider for LiveviewHistory {
+        fn foreign_navigation_handler(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
+            self.bridge.set_callback(callback);
+        }
+
+        fn current_path(&self) -> String {
+            self.url().path().to_string()
+        }
+
+        fn current_query(&self) -> Option<String> {
+            self.url().query().map(|q| q.to_string())
+        }
+
+        fn current_fragment(&self) -> Option<String> {
+            self.url()
+                .fragment()
+                .filter(|f| !f.is_empty())
+                .map(|f| f.to_string())
+        }
+
+        fn go_back(&mut self) {
+            self.bridge.send("go_back", Default::default());
+        }
+
+        fn go_forward(&mut self) {
+            self.bridge.send("go_forward", Default::default());
+        }
+
+        fn push(&mut self, path: String) {
+            if let Some(location) = self.resolve(&path) {
+                self.bridge.navigate("push_state", location);
+            }
+        }
+
+        fn replace(&mut self, path: String) {
+            if let Some(location) = self.resolve(&path) {
+                self.bridge.navigate("replace_state", location);
+            }
+        }
+
+        fn can_external(&self) -> bool {
+            true
+        }
+
+        fn external(&self, url: String) {
+            self.bridge
+                .send("external", serde_json::json!({ "url": url }));
+        }
+    }
+
+    /// Create a function that constructs a [`LiveviewHistory`], to be passed to the `history` prop
+    /// of a `Router`.
+    ///
+    /// # Panic
+    /// - When not used within a liveview session, but only in debug builds. In release builds, the
+    ///   returned function constructs a [`MemoryHistory`] instead.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus_router::prelude::*;
+    /// use dioxus_liveview::use_liveview_history;
+    /// fn App(cx: Scope) -> Element {
+    ///     let routes = use_segment(&cx, Segment::default);
+    ///     let history = use_liveview_history(&cx);
+    ///
+    ///     cx.render(rsx! {
+    ///         Router {
+    ///             routes: routes.clone(),
+    ///             history: history,
+    ///             Outlet { }
+    ///         }
+    ///     })
+    /// }
+    /// ```
+    pub fn use_liveview_history(cx: &ScopeState) -> &dyn Fn() -> Box<dyn HistoryProvider> {
+        cx.use_hook(|| {
+            let bridge = cx.consume_context::<HistoryBridge>();
+            if bridge.is_none() {
+                error!("`use_liveview_history` can only be used within a liveview session");
+                #[cfg(debug_assertions)]
+                panic!("`use_liveview_history` can only be used within a liveview session");
+            }
+
+            move || -> Box<dyn HistoryProvider> {
+                match &bridge {
+                    Some(bridge) => Box::new(LiveviewHistory {
+                        bridge: bridge.clone(),
+                    }),
+                    None => MemoryHistory::new(),
+                }
+            }
+        })
+    }
+
+    fn base() -> Url {
+        Url::parse("dioxus://index.html/").unwrap()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+        use super::*;
+        use crate::events::parse_ipc_message;
+
+        fn history(location: &str) -> (LiveviewHistory, UnboundedReceiver<String>) {
+            let (sender, receiver) = unbounded_channel();
+            let bridge = HistoryBridge::new(location.to_string(), sender);
+            (LiveviewHistory { bridge }, receiver)
+        }
+
+        fn command(receiver: &mut UnboundedReceiver<String>) -> (String, Option<String>) {
+            let message = parse_ipc_message(&receiver.try_recv().unwrap()).unwrap();
+            let url = message.params["url"].as_str().map(|u| u.to_string());
+            (message.method, url)
+        }
+
+        #[test]
+        fn initial_location() {
+            let (history, _) = history("/test?query=works#frag");
+
+            assert_eq!(history.current_path(), "/test");
+            assert_eq!(history.current_query(), Some(String::from("query=works")));
+            assert_eq!(history.current_fragment(), Some(String::from("frag")));
+        }
+
+        #[test]
+        fn push_and_replace() {
+            let (mut history, mut receiver) = history("/test/");
+
+            history.push(String::from("nested?query=works"));
+            assert_eq!(history.current_path(), "/test/nested");
+            assert_eq!(history.current_query(), Some(String::from("query=works")));
+            assert_eq!(
+                command(&mut receiver),
+                (
+                    String::from("push_state"),
+                    Some(String::from("/test/nested?query=works"))
+                )
+            );
+
+            history.replace(String::from("/other#frag"));
+            assert_eq!(history.current_path(), "/other");
+            assert_eq!(history.current_query(), None);
+            assert_eq!(
+                command(&mut receiver),
+                (
+                    String::from("replace_state"),
+                    Some(String::from("/other#frag"))
+                )
+            );
+
+            history.push(String::from("//invalid"));
+            assert_eq!(history.current_path(), "/other");
+            assert!(receiver.try_recv().is_err());
+        }
+
+        #[test]
+        fn back_and_forward() {
+            let (mut history, mut receiver) = history("/test");
+
+            history.go_back();
+            assert_eq!(command(&mut receiver), (String::from("go_back"), None));
+            history.go_forward();
+            assert_eq!(command(&mut receiver), (String::from("go_forward"), None));
+
+            // the location only changes once the client reports it
+            assert_eq!(history.current_path(), "/test");
+        }
+
+        #[test]
+        fn popstate() {
+            let (mut history, _) = history("/test");
+            let calls = Arc::new(AtomicUsize::new(0));
+            history.foreign_navigation_handler({
+                let calls = calls.clone();
+                Arc::new(move || {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                })
+            });
+
+            history
+                .bridge
+                .handle_popstate(&serde_json::json!({ "location": "/other?query=works" }));
+            assert_eq!(history.current_path(), "/other");
+            assert_eq!(history.current_query(), Some(String::from("query=works")));
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+            // invalid messages are ignored
+            history.bridge.handle_popstate(&serde_json::json!({}));
+            assert_eq!(history.current_path(), "/other");
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn external() {
+            let (history, mut receiver) = history("/test");
+
+            assert!(history.can_external());
+            history.external(String::from("https://dioxuslabs.com/"));
+            assert_eq!(
+                command(&mut receiver),
+                (
+                    String::from("external"),
+                    Some(String::from("https://dioxuslabs.com/"))
+                )
+            );
+        }
+    }
+}
@@ -4,8 +4,6 @@ function main() {
   if (root != null) {
     // create a new ipc
     window.ipc = new IPC(root);
-
-    window.ipc.send(serializeIpcMessage("initialize"));
   }
 }
 
@@ -18,6 +16,11 @@ class IPC {
 
     this.ws.onopen = () => {
       console.log("Connected to the websocket");
+
+      // the server needs to know where the app was opened before rendering it
+      this.send(
+        serializeIpcMessage("initialize", { location: currentLocation() })
+      );
     };
 
     this.ws.onerror = (err) => {
@@ -25,9 +28,19 @@ class IPC {
     };
 
     this.ws.onmessage = (event) => {
-      let edits = JSON.parse(event.data);
-      window.interpreter.handleEdits(edits);
+      let data = JSON.parse(event.data);
+      if (Array.isArray(data)) {
+        window.interpreter.handleEdits(data);
+      } else {
+        handleCommand(data);
+      }
     };
+
+    window.addEventListener("popstate", () => {
+      this.send(
+        serializeIpcMessage("popstate", { location: currentLocation() })
+      );
+    });
   }
 
   send(msg) {
@@ -35,6 +48,33 @@ class IPC {
   }
 }
 
+function currentLocation() {
+  const { pathname, search, hash } = window.location;
+  return pathname + search + hash;
+}
+
+function handleCommand({ method, params }) {
+  switch (method) {
+    case "push_state":
+      window.history.pushState(null, "", params.url);
+      break;
+    case "replace_state":
+      window.history.replaceState(null, "", params.url);
+      break;
+    case "go_back":
+      window.history.back();
+      break;
+    case "go_forward":
+      window.history.forward();
+      break;
+    case "external":
+      window.location.href = params.url;
+      break;
+    default:
+      console.error("Unknown command: ", method);
+  }
+}
+
 class Interpreter {
   constructor(root) {
     this.root = root;
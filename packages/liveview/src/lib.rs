@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub(crate) mod events;
+pub(crate) mod history;
 pub mod adapters {
     #[cfg(feature = "warp")]
     pub mod warp_adapter;
@@ -11,6 +12,9 @@ pub mod adapters {
 
 use std::net::SocketAddr;
 
+#[cfg(feature = "router")]
+pub use history::{use_liveview_history, LiveviewHistory};
+
 use tokio_util::task::LocalPoolHandle;
 
 #[derive(Clone)]
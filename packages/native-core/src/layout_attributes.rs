@@ -19,7 +19,7 @@
 - [x] pub padding: Rect<Dimension>,
 
 - [x] pub justify_content: JustifyContent,
-- [ ] pub position: Rect<Dimension>,
+- [x] pub position: Rect<Dimension>,
 - [x] pub border: Rect<Dimension>,
 
 - [ ] pub size: Size<Dimension>, ----> ??? seems to only be relevant for input?
@@ -87,7 +87,11 @@ pub fn apply_layout_attributes(name: &str, value: &str, style: &mut Style) {
         | "border-top-width"
         | "border-width" => apply_border(name, value, style),
 
-        "bottom" => {}
+        "bottom" => {
+            if let Some(v) = parse_value(value) {
+                style.position.bottom = v;
+            }
+        }
         "box-shadow" => {}
         "box-sizing" => {}
         "caption-side" => {}
@@ -152,7 +156,11 @@ pub fn apply_layout_attributes(name: &str, value: &str, style: &mut Style) {
                 _ => FlexStart,
             };
         }
-        "left" => {}
+        "left" => {
+            if let Some(v) = parse_value(value) {
+                style.position.start = v;
+            }
+        }
         "letter-spacing" => {}
 
         "list-style"
@@ -213,11 +221,19 @@ pub fn apply_layout_attributes(name: &str, value: &str, style: &mut Style) {
 
         "quotes" => {}
         "resize" => {}
-        "right" => {}
+        "right" => {
+            if let Some(v) = parse_value(value) {
+                style.position.end = v;
+            }
+        }
         "tab-size" => {}
         "table-layout" => {}
 
-        "top" => {}
+        "top" => {
+            if let Some(v) = parse_value(value) {
+                style.position.top = v;
+            }
+        }
 
         "transform"
         | "transform-origin"
@@ -525,11 +541,11 @@ fn apply_padding(name: &str, value: &str, style: &mut Style) {
 }
 
 fn apply_transform(_name: &str, _value: &str, _style: &mut Style) {
-    todo!()
+    // transforms don't change the layout
 }
 
 fn apply_transition(_name: &str, _value: &str, _style: &mut Style) {
-    todo!()
+    // transitions are not supported yet
 }
 
 fn apply_align(name: &str, value: &str, style: &mut Style) {
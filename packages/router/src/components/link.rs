@@ -56,6 +56,11 @@ pub struct LinkProps<'a> {
 /// However, in the background a [`Link`] still generates an anchor, which you can use for styling
 /// as normal.
 ///
+/// # Fragments
+/// When the `target` has a fragment (e.g. `/some/path#section`), the router will scroll to the
+/// element identified by it, once the [`Outlet`]s have been updated. This requires the [`Router`]
+/// to have a [`ScrollProvider`].
+///
 /// # External targets
 /// When the [`Link`]s target is [`ExternalTarget`], the target is used as the `href` directly. This
 /// means that a [`Link`] can always navigate to [`ExternalTarget`].
@@ -89,7 +94,9 @@ pub struct LinkProps<'a> {
 /// };
 /// ```
 ///
+/// [`Outlet`]: crate::components::Outlet
 /// [`Router`]: crate::components::Router
+/// [`ScrollProvider`]: crate::scroll::ScrollProvider
 #[allow(non_snake_case)]
 pub fn Link<'a>(cx: Scope<'a, LinkProps<'a>>) -> Element {
     let LinkProps {
@@ -253,6 +260,21 @@ mod tests {
         )
     }
 
+    #[test]
+    fn href_name_fragment() {
+        let target = NavigationTarget::from((Test, [])).with_fragment("frag");
+        let targets = {
+            let mut t = BTreeMap::new();
+            t.insert(
+                TypeId::of::<Test>(),
+                vec![NamedNavigationSegment::Fixed(String::from("test"))],
+            );
+            t
+        };
+
+        assert_eq!(format!("/test/#frag"), generate_href(&target, "", &targets));
+    }
+
     #[test]
     fn href_external() {
         let href = "test";
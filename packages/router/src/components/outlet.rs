@@ -1,7 +1,19 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
 use dioxus::prelude::*;
+use futures_timer::Delay;
 use log::error;
 
-use crate::{contexts::OutletContext, helpers::use_router_subscription};
+use crate::{
+    contexts::{OutletContext, TransitionContext, TransitionState},
+    helpers::use_router_subscription,
+    hooks::TransitionPhase,
+    service::RouterMessage,
+};
 
 /// Properties for an [`Outlet`].
 #[derive(Debug, Eq, PartialEq, Props)]
@@ -19,6 +31,29 @@ pub struct OutletProps {
     /// By default an [`Outlet`] will only render main content. This will make it render side
     /// content defined via [`RouteContent::Multi`](crate::route_definition::RouteContent::Multi).
     pub name: Option<&'static str>,
+    /// Animate routing changes.
+    ///
+    /// By default an [`Outlet`] replaces its content immediately. If a transition name is set, the
+    /// [`Outlet`] keeps the leaving content mounted while it exits, and lets the new content enter.
+    /// Each piece of content is wrapped in a `div` with classes describing its phase:
+    /// - `{name} {name}-enter` while entering,
+    /// - `{name}` when entered,
+    /// - `{name} {name}-exit` while exiting.
+    ///
+    /// A phase is finished when the wrapper itself fires an `animationend` or `transitionend`
+    /// event, or when [`Transition::finish`] is called. Events bubbling up from the content are
+    /// ignored. A phase that isn't finished within a second is finished anyway, so content without
+    /// an animation or transition doesn't get stuck. The content can access its phase via
+    /// [`use_transition`].
+    ///
+    /// If a navigation happens while content is still exiting, that content is unmounted
+    /// immediately.
+    ///
+    /// Don't change this prop after the [`Outlet`] has been rendered.
+    ///
+    /// [`Transition::finish`]: crate::hooks::Transition::finish
+    /// [`use_transition`]: crate::hooks::use_transition
+    pub transition: Option<&'static str>,
 }
 
 /// Renders the content of the current route.
@@ -50,7 +85,11 @@ pub struct OutletProps {
 /// [`Router`]: crate::components::Router
 #[allow(non_snake_case)]
 pub fn Outlet(cx: Scope<OutletProps>) -> Element {
-    let OutletProps { depth, name } = &cx.props;
+    let OutletProps {
+        depth,
+        name,
+        transition,
+    } = &cx.props;
 
     // hook up to router
     let router = match use_router_subscription(&cx) {
@@ -65,6 +104,14 @@ pub fn Outlet(cx: Scope<OutletProps>) -> Element {
     };
     let state = router.state.read().expect("router lock poison");
 
+    // tell the router once the content is mounted, so it can scroll to it
+    if state.scroll_pending {
+        let tx = router.tx.clone();
+        cx.spawn(async move {
+            let _ = tx.unbounded_send(RouterMessage::Rendered);
+        });
+    }
+
     // get own depth and communicate to nested outlets
     let depth = cx.use_hook(|| {
         let mut ctx = cx.consume_context::<OutletContext>().unwrap_or_default();
@@ -84,6 +131,38 @@ pub fn Outlet(cx: Scope<OutletProps>) -> Element {
         Some(name) => named.get(name).and_then(|comps| comps.get(*depth)),
     }
     .copied();
+    drop(state);
+
+    // keep track of entering and exiting content when animated
+    let transitions = cx.use_hook(|| {
+        transition.map(|_| {
+            let update = cx.schedule_update_any();
+            Rc::new(RefCell::new(TransitionState::new(X, cx.scope_id(), update)))
+        })
+    });
+
+    if let (Some(transition), Some(transitions)) = (transition, &*transitions) {
+        transitions.borrow_mut().navigate(X);
+        let entries: Vec<_> = transitions
+            .borrow()
+            .entries
+            .iter()
+            .map(|e| (e.id, e.component, e.phase))
+            .collect();
+
+        return cx.render(rsx! {
+            entries.into_iter().map(|(id, component, phase)| rsx! {
+                OutletTransition {
+                    key: "{id}",
+                    component: component,
+                    id: id,
+                    name: transition,
+                    phase: phase,
+                    state: transitions.clone(),
+                }
+            })
+        });
+    }
 
     // render component or nothing
     cx.render(match X {
@@ -91,3 +170,88 @@ pub fn Outlet(cx: Scope<OutletProps>) -> Element {
         None => rsx! { Fragment {} },
     })
 }
+
+#[derive(Props)]
+struct OutletTransitionProps {
+    component: Component,
+    id: usize,
+    name: &'static str,
+    phase: TransitionPhase,
+    state: Rc<RefCell<TransitionState>>,
+}
+
+// [`Component`] (in `component`) and [`TransitionState`] (in `state`) don't implement
+// [`PartialEq`]
+impl PartialEq for OutletTransitionProps {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.phase == other.phase
+            && Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+/// How long a transition phase may take before it is finished without an event.
+const TRANSITION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Wraps a single piece of content of an animated [`Outlet`].
+#[allow(non_snake_case)]
+fn OutletTransition(cx: Scope<OutletTransitionProps>) -> Element {
+    let OutletTransitionProps {
+        component,
+        id,
+        name,
+        phase,
+        state,
+    } = &cx.props;
+    let X = *component;
+
+    cx.use_hook(|| {
+        cx.provide_context(TransitionContext {
+            id: *id,
+            state: state.clone(),
+        })
+    });
+
+    // finish the phase anyway, in case the content isn't animated
+    let timed = cx.use_hook(|| Cell::new(None));
+    if *phase != TransitionPhase::Entered && timed.get() != Some(*phase) {
+        timed.set(Some(*phase));
+        let (id, phase, state) = (*id, *phase, state.clone());
+        cx.spawn(async move {
+            Delay::new(TRANSITION_TIMEOUT).await;
+            let mut state = state.borrow_mut();
+            if state.phase(id) == phase {
+                state.finish(id);
+            }
+        });
+    }
+
+    // set when an event from the content bubbles through, which the wrapper then ignores
+    let from_content = &*cx.use_hook(|| Cell::new(false));
+    let finish = move || {
+        if !from_content.replace(false) {
+            state.borrow_mut().finish(*id);
+        }
+    };
+
+    let class = match phase {
+        TransitionPhase::Entering => format!("{name} {name}-enter"),
+        TransitionPhase::Entered => name.to_string(),
+        TransitionPhase::Exiting => format!("{name} {name}-exit"),
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "{class}",
+            onanimationend: move |_| finish(),
+            ontransitionend: move |_| finish(),
+            div {
+                display: "contents",
+                onanimationend: move |_| from_content.set(true),
+                ontransitionend: move |_| from_content.set(true),
+                X {}
+            }
+        }
+    })
+}
@@ -12,6 +12,7 @@ use crate::{
     history::{HistoryProvider, MemoryHistory},
     navigation::NavigationTarget,
     route_definition::Segment,
+    scroll::ScrollProvider,
     service::RouterService,
     state::RouterState,
 };
@@ -64,6 +65,16 @@ pub struct RouterProps<'a> {
     pub initial_path: Option<String>,
     /// The routes of the application.
     pub routes: Arc<Segment>,
+    /// A function that constructs a scroll provider.
+    ///
+    /// The router uses it to restore scroll positions when navigating through the history, and to
+    /// scroll to the fragment of a [`NavigationTarget`].
+    ///
+    /// When [`None`], a default is used:
+    /// - [`WebScroll`](crate::scroll::WebScroll) when the `web` feature is enabled and the target
+    ///   family is `wasm`.
+    /// - Otherwise the router doesn't scroll.
+    pub scroll: Option<&'a dyn Fn() -> Box<dyn ScrollProvider>>,
     /// A function that will be called anytime the current route updates.
     ///
     /// The function is called after the routing state is updated, but before components and hooks
@@ -79,7 +90,8 @@ pub struct RouterProps<'a> {
     pub update_callback: Option<RouterUpdateCallback>,
 }
 
-// - [`Fn() -> Box<dyn HistoryProvider>`] (in `history`) doesn't implement [`Debug`]
+// - [`Fn() -> Box<dyn HistoryProvider>`] (in `history`) and [`Fn() -> Box<dyn ScrollProvider>`]
+//   (in `scroll`) don't implement [`Debug`]
 // - [`Option<Component>`] (in `fallback_external_navigation` and `fallback_named_navigation`)
 //   doesn't implement [`Debug`]
 // - [`Option<Arc<dyn Fn(RwLockReadGuard<RouterState>) -> Option<NavigationTarget>>>] (in
@@ -99,6 +111,7 @@ impl Debug for RouterProps<'_> {
             .field("history", &self.history.is_some())
             .field("init_only", &self.init_only)
             .field("routes", &self.routes)
+            .field("scroll", &self.scroll.is_some())
             .field("update_callback", &self.update_callback.is_some())
             .finish()
     }
@@ -144,6 +157,7 @@ pub fn Router<'a>(cx: Scope<'a, RouterProps<'a>>) -> Element {
         initial_path,
         update_callback: on_update,
         routes,
+        scroll,
     } = cx.props;
 
     let service = cx.use_hook(|| {
@@ -170,6 +184,7 @@ pub fn Router<'a>(cx: Scope<'a, RouterProps<'a>>) -> Element {
             routes.clone(),
             cx.schedule_update_any(),
             history,
+            scroll.map(|x| x()),
             fallback_external_navigation.unwrap_or(FallbackExternalNavigation),
             fallback_named_navigation.unwrap_or(FallbackNamedNavigation),
             on_update.clone(),
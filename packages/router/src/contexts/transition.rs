@@ -0,0 +1,272 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc, sync::Arc};
+
+use dioxus::prelude::*;
+
+use crate::hooks::TransitionPhase;
+
+/// A context provided to the content of an animated [`Outlet`].
+///
+/// [`Outlet`]: crate::components::Outlet
+#[derive(Clone, Debug)]
+pub(crate) struct TransitionContext {
+    /// The id of the entry the context is provided to.
+    pub(crate) id: usize,
+    /// The transitions of the [`Outlet`](crate::components::Outlet).
+    pub(crate) state: Rc<RefCell<TransitionState>>,
+}
+
+/// A single piece of content rendered by an animated [`Outlet`].
+///
+/// [`Outlet`]: crate::components::Outlet
+#[derive(Debug)]
+pub(crate) struct TransitionEntry {
+    pub(crate) id: usize,
+    pub(crate) component: Component,
+    pub(crate) phase: TransitionPhase,
+    /// The components that want to be updated when the phase changes.
+    subscribers: Vec<ScopeId>,
+}
+
+/// The transitions of an animated [`Outlet`].
+///
+/// [`Outlet`]: crate::components::Outlet
+pub(crate) struct TransitionState {
+    /// All entries that are currently rendered, oldest first.
+    ///
+    /// Only the last entry may be entering or entered, all others are exiting.
+    pub(crate) entries: Vec<TransitionEntry>,
+    next_id: usize,
+    /// The [`Outlet`](crate::components::Outlet) itself.
+    outlet: ScopeId,
+    update: Arc<dyn Fn(ScopeId)>,
+}
+
+impl TransitionState {
+    /// Create a new [`TransitionState`] for the outlet with the id `outlet`.
+    ///
+    /// The initial `component` starts out entered.
+    pub(crate) fn new(
+        component: Option<Component>,
+        outlet: ScopeId,
+        update: Arc<dyn Fn(ScopeId)>,
+    ) -> Self {
+        let mut state = Self {
+            entries: Vec::new(),
+            next_id: 0,
+            outlet,
+            update,
+        };
+
+        if let Some(component) = component {
+            state.push(component, TransitionPhase::Entered);
+        }
+
+        state
+    }
+
+    /// Update the state for a routing change, after which `component` should be rendered.
+    ///
+    /// Does nothing if `component` is already entering or entered. Otherwise the current entry
+    /// starts exiting and `component` starts entering. Entries that are already exiting are removed
+    /// immediately, so that rapid navigations don't pile up content.
+    pub(crate) fn navigate(&mut self, component: Option<Component>) {
+        let current = self
+            .entries
+            .last()
+            .filter(|e| e.phase != TransitionPhase::Exiting);
+        if current.map(|e| e.component as usize) == component.map(|c| c as usize) {
+            return;
+        }
+
+        self.entries.retain(|e| e.phase != TransitionPhase::Exiting);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.phase = TransitionPhase::Exiting;
+            for id in &entry.subscribers {
+                (self.update)(*id);
+            }
+        }
+
+        if let Some(component) = component {
+            self.push(component, TransitionPhase::Entering);
+        }
+    }
+
+    /// Finish the current phase of the entry with the id `id`.
+    ///
+    /// An entering entry is entered afterwards, an exiting entry is removed. Does nothing for
+    /// entered entries and unknown ids.
+    pub(crate) fn finish(&mut self, id: usize) {
+        let index = match self.entries.iter().position(|e| e.id == id) {
+            Some(index) => index,
+            None => return,
+        };
+
+        match self.entries[index].phase {
+            TransitionPhase::Entering => {
+                let entry = &mut self.entries[index];
+                entry.phase = TransitionPhase::Entered;
+                for id in &entry.subscribers {
+                    (self.update)(*id);
+                }
+            }
+            TransitionPhase::Entered => return,
+            TransitionPhase::Exiting => {
+                self.entries.remove(index);
+            }
+        }
+
+        (self.update)(self.outlet);
+    }
+
+    /// Get the phase of the entry with the id `id`.
+    ///
+    /// Entries that are no longer known are considered exiting.
+    pub(crate) fn phase(&self, id: usize) -> TransitionPhase {
+        self.entries
+            .iter()
+            .find(|e| e.id == id)
+            .map(|e| e.phase)
+            .unwrap_or(TransitionPhase::Exiting)
+    }
+
+    /// Update the component with the id `scope` whenever the phase of the entry with the id `id`
+    /// changes.
+    pub(crate) fn subscribe(&mut self, id: usize, scope: ScopeId) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) {
+            if !entry.subscribers.contains(&scope) {
+                entry.subscribers.push(scope);
+            }
+        }
+    }
+
+    fn push(&mut self, component: Component, phase: TransitionPhase) {
+        self.entries.push(TransitionEntry {
+            id: self.next_id,
+            component,
+            phase,
+            subscribers: Vec::new(),
+        });
+        self.next_id += 1;
+    }
+}
+
+// [`Fn(ScopeId)`] (in `update`) doesn't implement [`Debug`]
+impl Debug for TransitionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransitionState")
+            .field("entries", &self.entries)
+            .field("next_id", &self.next_id)
+            .field("outlet", &self.outlet)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn initial() {
+        let (state, _) = test_state(Some(First));
+
+        assert_eq!(phases(&state), vec![(0, TransitionPhase::Entered)]);
+    }
+
+    #[test]
+    fn navigate() {
+        let (mut state, updates) = test_state(Some(First));
+        state.subscribe(0, ScopeId(1));
+
+        state.navigate(Some(Second));
+        assert_eq!(
+            phases(&state),
+            vec![
+                (0, TransitionPhase::Exiting),
+                (1, TransitionPhase::Entering)
+            ]
+        );
+        assert_eq!(*updates.lock().unwrap(), vec![ScopeId(1)]);
+
+        // same component, nothing changes
+        state.navigate(Some(Second));
+        assert_eq!(state.entries.len(), 2);
+    }
+
+    #[test]
+    fn navigate_to_nothing() {
+        let (mut state, _) = test_state(Some(First));
+
+        state.navigate(None);
+        assert_eq!(phases(&state), vec![(0, TransitionPhase::Exiting)]);
+
+        state.navigate(Some(First));
+        assert_eq!(phases(&state), vec![(1, TransitionPhase::Entering)]);
+    }
+
+    #[test]
+    fn navigate_rapidly() {
+        let (mut state, _) = test_state(Some(First));
+
+        state.navigate(Some(Second));
+        state.navigate(Some(First));
+        assert_eq!(
+            phases(&state),
+            vec![
+                (1, TransitionPhase::Exiting),
+                (2, TransitionPhase::Entering)
+            ]
+        );
+
+        // stale ids are ignored
+        state.finish(0);
+        assert_eq!(state.entries.len(), 2);
+        assert_eq!(state.phase(0), TransitionPhase::Exiting);
+    }
+
+    #[test]
+    fn finish() {
+        let (mut state, updates) = test_state(Some(First));
+        state.navigate(Some(Second));
+        state.subscribe(1, ScopeId(1));
+
+        state.finish(1);
+        assert_eq!(
+            phases(&state),
+            vec![(0, TransitionPhase::Exiting), (1, TransitionPhase::Entered)]
+        );
+        assert_eq!(*updates.lock().unwrap(), vec![ScopeId(1), ScopeId(0)]);
+
+        state.finish(0);
+        assert_eq!(phases(&state), vec![(1, TransitionPhase::Entered)]);
+
+        // entered entries stay
+        state.finish(1);
+        assert_eq!(phases(&state), vec![(1, TransitionPhase::Entered)]);
+    }
+
+    fn phases(state: &TransitionState) -> Vec<(usize, TransitionPhase)> {
+        state.entries.iter().map(|e| (e.id, e.phase)).collect()
+    }
+
+    fn test_state(component: Option<Component>) -> (TransitionState, Arc<Mutex<Vec<ScopeId>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let state = TransitionState::new(component, ScopeId(0), {
+            let updates = updates.clone();
+            Arc::new(move |id| updates.lock().unwrap().push(id))
+        });
+
+        (state, updates)
+    }
+
+    #[allow(non_snake_case)]
+    fn First(_: Scope) -> Element {
+        None
+    }
+
+    #[allow(non_snake_case)]
+    fn Second(_: Scope) -> Element {
+        unimplemented!()
+    }
+}
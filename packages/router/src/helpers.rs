@@ -93,17 +93,18 @@ pub(crate) fn construct_named_path(
         }
     }
 
-    // add query
+    // add query and fragment
     match query {
         None => {}
         Some(Query::QueryString(qs)) => {
-            if qs.is_empty() {
-                // do nothing
-            } else if qs.starts_with('?') {
-                path = format!("{path}{qs}")
-            } else {
+            let (qs, fragment) = qs.split_once('#').unwrap_or((qs, ""));
+            let qs = qs.strip_prefix('?').unwrap_or(qs);
+            if !qs.is_empty() {
                 path = format!("{path}?{qs}")
             }
+            if !fragment.is_empty() {
+                path = format!("{path}#{fragment}")
+            }
         }
         Some(Query::QueryVec(vals)) => {
             if let Ok(q) = serde_urlencoded::to_string(vals) {
@@ -204,6 +205,32 @@ mod tests {
         )
     }
 
+    #[test]
+    fn named_path_fragment() {
+        assert_eq!(
+            Some(String::from("/test/nest/?query=works#frag")),
+            construct_named_path(
+                &named_tuple(Fixed),
+                &[],
+                &Some(Query::QueryString(String::from("query=works#frag"))),
+                &test_targets()
+            )
+        )
+    }
+
+    #[test]
+    fn named_path_fragment_without_query() {
+        assert_eq!(
+            Some(String::from("/test/nest/#frag")),
+            construct_named_path(
+                &named_tuple(Fixed),
+                &[],
+                &Some(Query::QueryString(String::from("#frag"))),
+                &test_targets()
+            )
+        )
+    }
+
     #[cfg(debug_assertions)]
     #[test]
     // TODO: find a better way to test this panic message; see docs for std::any::type_name
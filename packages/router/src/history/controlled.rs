@@ -4,6 +4,7 @@ use std::{
 };
 
 use super::HistoryProvider;
+use crate::scroll::ScrollPosition;
 
 struct ControlledHistoryCore {
     callback: Option<Arc<dyn Fn() + Send + Sync>>,
@@ -54,6 +55,26 @@ impl HistoryProvider for ControlledHistory {
         self.core.lock().unwrap().history.current_query()
     }
 
+    fn current_fragment(&self) -> Option<String> {
+        self.core.lock().unwrap().history.current_fragment()
+    }
+
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        self.core.lock().unwrap().history.current_scroll()
+    }
+
+    fn update_scroll(&mut self, position: ScrollPosition) {
+        self.core.lock().unwrap().history.update_scroll(position)
+    }
+
+    fn current_state(&self) -> Option<String> {
+        self.core.lock().unwrap().history.current_state()
+    }
+
+    fn update_state(&mut self, state: Option<String>) {
+        self.core.lock().unwrap().history.update_state(state)
+    }
+
     fn can_go_back(&self) -> bool {
         self.core.lock().unwrap().history.can_go_back()
     }
@@ -177,6 +198,26 @@ impl HistoryProvider for HistoryController {
         self.core.lock().unwrap().history.current_query()
     }
 
+    fn current_fragment(&self) -> Option<String> {
+        self.core.lock().unwrap().history.current_fragment()
+    }
+
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        self.core.lock().unwrap().history.current_scroll()
+    }
+
+    fn update_scroll(&mut self, position: ScrollPosition) {
+        self.core.lock().unwrap().history.update_scroll(position)
+    }
+
+    fn current_state(&self) -> Option<String> {
+        self.core.lock().unwrap().history.current_state()
+    }
+
+    fn update_state(&mut self, state: Option<String>) {
+        self.core.lock().unwrap().history.update_state(state)
+    }
+
     fn can_go_back(&self) -> bool {
         self.core.lock().unwrap().history.can_go_back()
     }
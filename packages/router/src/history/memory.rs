@@ -2,13 +2,27 @@ use log::error;
 use url::Url;
 
 use super::HistoryProvider;
+use crate::scroll::ScrollPosition;
 
 /// A [`HistoryProvider`] that stores all information in memory.
 #[derive(Debug)]
 pub struct MemoryHistory {
     current: Url,
-    past: Vec<String>,
-    future: Vec<String>,
+    scroll: Option<ScrollPosition>,
+    state: Option<String>,
+    past: Vec<Entry>,
+    future: Vec<Entry>,
+}
+
+/// A single entry of a [`MemoryHistory`].
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Entry {
+    /// The full URL.
+    pub(super) url: String,
+    /// The stored scroll position.
+    pub(super) scroll: Option<ScrollPosition>,
+    /// The stored state.
+    pub(super) state: Option<String>,
 }
 
 impl MemoryHistory {
@@ -23,12 +37,76 @@ impl MemoryHistory {
         h.push(path);
         Box::new(h)
     }
+
+    /// Get all entries, oldest first, and the index of the current entry.
+    pub(super) fn entries(&self) -> (Vec<Entry>, usize) {
+        let mut entries = self.past.clone();
+        entries.push(self.current_entry());
+        entries.extend(self.future.iter().rev().cloned());
+
+        (entries, self.past.len())
+    }
+
+    /// Create a [`MemoryHistory`] from entries returned by [`MemoryHistory::entries`].
+    ///
+    /// Returns [`None`] if `index` is out of bounds or an entry isn't a valid URL.
+    pub(super) fn from_entries(mut entries: Vec<Entry>, index: usize) -> Option<Self> {
+        if index >= entries.len() || entries.iter().any(|e| Url::parse(&e.url).is_err()) {
+            return None;
+        }
+
+        let mut future = entries.split_off(index + 1);
+        future.reverse();
+        let current = entries.pop().unwrap();
+
+        let mut history = Self {
+            past: entries,
+            future,
+            ..Default::default()
+        };
+        history.set_current_entry(current);
+        Some(history)
+    }
+
+    /// Get the current entry.
+    fn current_entry(&self) -> Entry {
+        Entry {
+            url: self.current.to_string(),
+            scroll: self.scroll,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Make `entry` the current entry.
+    fn set_current_entry(&mut self, entry: Entry) {
+        // urls of entries are always valid, they came from the url struct itself
+        self.current = Url::parse(&entry.url).unwrap();
+        self.scroll = entry.scroll;
+        self.state = entry.state;
+    }
+
+    /// Drop the oldest entries until at most `max` entries are left.
+    ///
+    /// Entries in the past are dropped before entries in the future. The current entry is always
+    /// kept.
+    pub(super) fn truncate(&mut self, max: usize) {
+        let max = max.max(1);
+        while 1 + self.past.len() + self.future.len() > max {
+            if !self.past.is_empty() {
+                self.past.remove(0);
+            } else {
+                self.future.remove(0);
+            }
+        }
+    }
 }
 
 impl Default for MemoryHistory {
     fn default() -> Self {
         Self {
             current: Url::parse("dioxus://index.html/").unwrap(),
+            scroll: None,
+            state: None,
             past: Default::default(),
             future: Default::default(),
         }
@@ -44,6 +122,29 @@ impl HistoryProvider for MemoryHistory {
         self.current.query().map(|q| q.to_string())
     }
 
+    fn current_fragment(&self) -> Option<String> {
+        self.current
+            .fragment()
+            .filter(|f| !f.is_empty())
+            .map(|f| f.to_string())
+    }
+
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        self.scroll
+    }
+
+    fn update_scroll(&mut self, position: ScrollPosition) {
+        self.scroll = Some(position);
+    }
+
+    fn current_state(&self) -> Option<String> {
+        self.state.clone()
+    }
+
+    fn update_state(&mut self, state: Option<String>) {
+        self.state = state;
+    }
+
     fn can_go_back(&self) -> bool {
         !self.past.is_empty()
     }
@@ -53,20 +154,16 @@ impl HistoryProvider for MemoryHistory {
     }
 
     fn go_back(&mut self) {
-        if self.can_go_back() {
-            self.future.push(self.current.to_string());
-            self.current = Url::parse(&self.past.pop().unwrap()).unwrap();
-
-            // past urls are always valid, they came from the url struct itself
+        if let Some(entry) = self.past.pop() {
+            self.future.push(self.current_entry());
+            self.set_current_entry(entry);
         }
     }
 
     fn go_forward(&mut self) {
-        if self.can_go_forward() {
-            self.past.push(self.current.to_string());
-            self.current = Url::parse(&self.future.pop().unwrap()).unwrap();
-
-            // future urls are always valid, they came from the url struct itself
+        if let Some(entry) = self.future.pop() {
+            self.past.push(self.current_entry());
+            self.set_current_entry(entry);
         }
     }
 
@@ -76,11 +173,13 @@ impl HistoryProvider for MemoryHistory {
             return;
         }
 
-        let previous_path = self.current.to_string();
-
         if let Ok(url) = self.current.join(&path) {
-            self.past.push(previous_path);
-            self.current = url;
+            self.past.push(self.current_entry());
+            self.set_current_entry(Entry {
+                url: url.to_string(),
+                scroll: None,
+                state: None,
+            });
             self.future.clear();
         }
     }
@@ -93,6 +192,76 @@ impl HistoryProvider for MemoryHistory {
 
         if let Ok(url) = self.current.join(&path) {
             self.current = url;
+            self.scroll = None;
+            self.state = None;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragment() {
+        let mut history = MemoryHistory::default();
+        assert_eq!(history.current_fragment(), None);
+
+        history.push(String::from("/test?query=works#frag"));
+        assert_eq!(history.current_path(), "/test");
+        assert_eq!(history.current_query(), Some(String::from("query=works")));
+        assert_eq!(history.current_fragment(), Some(String::from("frag")));
+
+        history.push(String::from("#other"));
+        assert_eq!(history.current_path(), "/test");
+        assert_eq!(history.current_fragment(), Some(String::from("other")));
+    }
+
+    #[test]
+    fn scroll_restoration() {
+        let position = ScrollPosition { x: 1.0, y: 2.0 };
+        let mut history = MemoryHistory::default();
+
+        history.update_scroll(position);
+        history.push(String::from("/test"));
+        assert_eq!(history.current_scroll(), None);
+
+        history.update_scroll(ScrollPosition { x: 3.0, y: 4.0 });
+        history.go_back();
+        assert_eq!(history.current_scroll(), Some(position));
+
+        history.go_forward();
+        assert_eq!(
+            history.current_scroll(),
+            Some(ScrollPosition { x: 3.0, y: 4.0 })
+        );
+    }
+
+    #[test]
+    fn scroll_reset_on_replace() {
+        let mut history = MemoryHistory::default();
+
+        history.update_scroll(ScrollPosition { x: 1.0, y: 2.0 });
+        history.replace(String::from("/test"));
+        assert_eq!(history.current_scroll(), None);
+    }
+
+    #[test]
+    fn state() {
+        let mut history = MemoryHistory::default();
+
+        history.update_state(Some(String::from("first")));
+        history.push(String::from("/test"));
+        assert_eq!(history.current_state(), None);
+
+        history.update_state(Some(String::from("second")));
+        history.go_back();
+        assert_eq!(history.current_state(), Some(String::from("first")));
+
+        history.go_forward();
+        assert_eq!(history.current_state(), Some(String::from("second")));
+
+        history.replace(String::from("/other"));
+        assert_eq!(history.current_state(), None);
+    }
+}
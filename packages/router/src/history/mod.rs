@@ -1,5 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
+use crate::scroll::ScrollPosition;
+
 #[cfg(feature = "web")]
 mod web_hash;
 #[cfg(feature = "web")]
@@ -16,13 +18,20 @@ pub use controlled::*;
 mod memory;
 pub use memory::*;
 
+#[cfg(not(target_family = "wasm"))]
+mod persistent;
+#[cfg(not(target_family = "wasm"))]
+pub use persistent::*;
+
 /// A trait that lets the router access the navigation history.
 ///
 /// Provided implementations:
 /// - [`MemoryHistory`] implements a history entirely in memory.
+/// - [`PersistentHistory`] implements a history in memory, but also persists it to a file. Not
+///   available on `wasm` targets.
 /// - [`WebHistory`] hooks up to the browsers history and URL.
-/// - [`WebHashHistory`] hooks up to the browsers history, but stores the actual path
-///   and query in the fragment of the browsers URL.
+/// - [`WebHashHistory`] hooks up to the browsers history, but stores the actual path,
+///   query and fragment in the fragment of the browsers URL.
 /// - [`HistoryController`] and [`ControlledHistory`] share an other [`HistoryProvider`] internally.
 ///   The [`HistoryController`] can be used to control the router from outside the VDOM.
 pub trait HistoryProvider
@@ -47,6 +56,39 @@ where
     /// Get the current query string.
     #[must_use]
     fn current_query(&self) -> Option<String>;
+    /// Get the current fragment, without the leading `#`.
+    #[must_use]
+    fn current_fragment(&self) -> Option<String> {
+        None
+    }
+
+    /// Get the scroll position stored for the current history entry.
+    ///
+    /// The router restores it after navigating back or forward. Return [`None`] if no position is
+    /// stored, in which case the router will scroll to the current fragment (if present).
+    #[must_use]
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        None
+    }
+    /// Store the scroll position for the current history entry.
+    ///
+    /// Called by the router right before it navigates away from the current entry.
+    #[allow(unused)]
+    fn update_scroll(&mut self, position: ScrollPosition) {}
+
+    /// Get the state the app stored for the current history entry.
+    ///
+    /// Return [`None`] if no state is stored, or if the provider can't store state.
+    #[must_use]
+    fn current_state(&self) -> Option<String> {
+        None
+    }
+    /// Store `state` for the current history entry.
+    ///
+    /// The state is restored when navigating back or forward to the entry. New entries, and
+    /// entries replacing the current one, start without state.
+    #[allow(unused)]
+    fn update_state(&mut self, state: Option<String>) {}
 
     /// Check if there is a prior path that can be navigated back to.
     ///
@@ -93,30 +135,25 @@ where
     fn external(&self, url: String) {}
 }
 
-/// The position the browser is scrolled to.
+/// Get the scroll position stored in the state of the current browser history entry.
 ///
-/// Used to restore it when navigating through history, by both [`BrowserPathHistoryProvider`] and
-/// [`BrowserHashHistoryProvider`].
+/// Used by both [`WebHistory`] and [`WebHashHistory`].
 #[cfg(feature = "web")]
-#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
-struct ScrollPosition {
-    x: f64,
-    y: f64,
+fn scroll_from_history(history: &web_sys::History) -> Option<ScrollPosition> {
+    history
+        .state()
+        .ok()
+        .and_then(|state| serde_wasm_bindgen::from_value(state).ok())
 }
 
 /// Replace the current history entry with an equivalent one, but with an updated scroll position.
 ///
-/// Used by both [`BrowserPathHistoryProvider`] and [`BrowserHashHistoryProvider`].
+/// Used by both [`WebHistory`] and [`WebHashHistory`].
 #[cfg(feature = "web")]
-fn update_history_with_scroll(window: &web_sys::Window, history: &web_sys::History) {
+fn update_history_with_scroll(history: &web_sys::History, position: ScrollPosition) {
     use log::error;
 
-    // get position
-    let position = serde_wasm_bindgen::to_value(&ScrollPosition {
-        x: window.scroll_x().unwrap_or_default(),
-        y: window.scroll_y().unwrap_or_default(),
-    })
-    .unwrap();
+    let position = serde_wasm_bindgen::to_value(&position).unwrap();
 
     // replace in history
     if let Err(e) = history.replace_state(&position, "") {
@@ -0,0 +1,352 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+use url::{Position, Url};
+
+use super::{memory::Entry, HistoryProvider, MemoryHistory};
+use crate::scroll::ScrollPosition;
+
+/// The default maximum amount of entries a [`PersistentHistory`] keeps.
+pub const DEFAULT_MAX_ENTRIES: usize = 100;
+
+/// The name of the file a [`PersistentHistory`] created via [`PersistentHistory::in_data_dir`]
+/// writes to.
+const FILE_NAME: &str = "history.json";
+
+/// A single entry of a [`PersistentHistory`], as it is written to the file.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct PersistedEntry {
+    /// The path, including the query and fragment.
+    path: String,
+    /// The stored scroll position.
+    scroll: Option<ScrollPosition>,
+    /// The stored state.
+    #[serde(default)]
+    state: Option<String>,
+}
+
+/// The content of the file a [`PersistentHistory`] writes to.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct PersistedHistory {
+    /// All entries, oldest first.
+    entries: Vec<PersistedEntry>,
+    /// The index of the current entry.
+    index: usize,
+}
+
+/// A [`HistoryProvider`] that keeps the history in memory, and persists it to a file.
+///
+/// This allows desktop apps to restore the navigation history, the current location, the scroll
+/// positions and the [state](HistoryProvider::update_state) of the entries after a restart. Every
+/// change is written to the file immediately. When created, the [`PersistentHistory`] restores the
+/// history stored in the file. If the file doesn't exist or cannot be read, it starts with an empty
+/// history.
+///
+/// The amount of stored entries is capped. When the cap is exceeded, the oldest entries are
+/// dropped.
+///
+/// # Example
+/// ```rust,no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_router::prelude::*;
+/// use dioxus_router::history::PersistentHistory;
+/// fn App(cx: Scope) -> Element {
+///     let routes = use_segment(&cx, Segment::default);
+///
+///     cx.render(rsx! {
+///         Router {
+///             routes: routes.clone(),
+///             history: &|| PersistentHistory::in_data_dir("my-app").unwrap(),
+///             Outlet { }
+///         }
+///     })
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PersistentHistory {
+    file: PathBuf,
+    history: MemoryHistory,
+    max_entries: usize,
+}
+
+impl PersistentHistory {
+    /// Create a new [`PersistentHistory`] that persists to `file`.
+    ///
+    /// Keeps at most [`DEFAULT_MAX_ENTRIES`] entries.
+    pub fn new(file: impl Into<PathBuf>) -> Box<Self> {
+        Self::with_max_entries(file, DEFAULT_MAX_ENTRIES)
+    }
+
+    /// Create a new [`PersistentHistory`] that persists to `file` and keeps at most `max_entries`
+    /// entries.
+    ///
+    /// The current entry is always kept, even if `max_entries` is `0`.
+    pub fn with_max_entries(file: impl Into<PathBuf>, max_entries: usize) -> Box<Self> {
+        let file = file.into();
+        let mut history = match load(&file) {
+            Ok(Some(history)) => history,
+            Ok(None) => MemoryHistory::default(),
+            Err(e) => {
+                error!("failed to restore history from {file:?}, starting empty: {e}");
+                MemoryHistory::default()
+            }
+        };
+        history.truncate(max_entries);
+
+        Box::new(Self {
+            file,
+            history,
+            max_entries,
+        })
+    }
+
+    /// Create a new [`PersistentHistory`] that persists to a file in the data directory of the
+    /// current user. The file is placed in a subdirectory named `app_name`.
+    ///
+    /// Returns [`None`] if the data directory is unknown on the current platform.
+    #[must_use]
+    pub fn in_data_dir(app_name: &str) -> Option<Box<Self>> {
+        let file = dirs::data_dir()?.join(app_name).join(FILE_NAME);
+        Some(Self::new(file))
+    }
+
+    /// Get the path of the file the [`PersistentHistory`] persists to.
+    #[must_use]
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Cap the history and write it to the file.
+    fn persist(&mut self) {
+        self.history.truncate(self.max_entries);
+
+        if let Err(e) = save(&self.file, &self.history) {
+            error!("failed to persist history to {:?}: {e}", self.file);
+        }
+    }
+}
+
+impl HistoryProvider for PersistentHistory {
+    fn current_path(&self) -> String {
+        self.history.current_path()
+    }
+
+    fn current_query(&self) -> Option<String> {
+        self.history.current_query()
+    }
+
+    fn current_fragment(&self) -> Option<String> {
+        self.history.current_fragment()
+    }
+
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        self.history.current_scroll()
+    }
+
+    fn update_scroll(&mut self, position: ScrollPosition) {
+        self.history.update_scroll(position);
+        self.persist();
+    }
+
+    fn current_state(&self) -> Option<String> {
+        self.history.current_state()
+    }
+
+    fn update_state(&mut self, state: Option<String>) {
+        self.history.update_state(state);
+        self.persist();
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.history.can_go_back()
+    }
+
+    fn can_go_forward(&self) -> bool {
+        self.history.can_go_forward()
+    }
+
+    fn go_back(&mut self) {
+        self.history.go_back();
+        self.persist();
+    }
+
+    fn go_forward(&mut self) {
+        self.history.go_forward();
+        self.persist();
+    }
+
+    fn push(&mut self, path: String) {
+        self.history.push(path);
+        self.persist();
+    }
+
+    fn replace(&mut self, path: String) {
+        self.history.replace(path);
+        self.persist();
+    }
+}
+
+/// Read a [`MemoryHistory`] from `file`.
+///
+/// Returns [`None`] if the file doesn't exist.
+fn load(file: &Path) -> io::Result<Option<MemoryHistory>> {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let PersistedHistory { entries, index } = serde_json::from_str(&content)?;
+
+    let base = Url::parse("dioxus://index.html/").unwrap();
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            if !entry.path.starts_with('/') {
+                return Err(url::ParseError::RelativeUrlWithoutBase);
+            }
+            base.join(&entry.path).map(|url| Entry {
+                url: url.to_string(),
+                scroll: entry.scroll,
+                state: entry.state,
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+
+    MemoryHistory::from_entries(entries, index)
+        .map(Some)
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "invalid history entries"))
+}
+
+/// Write `history` to `file`.
+///
+/// The content is written to a temporary file first, which then replaces `file`. This ensures the
+/// file is never left in a partially written state.
+fn save(file: &Path, history: &MemoryHistory) -> io::Result<()> {
+    let (entries, index) = history.entries();
+    let entries = entries
+        .into_iter()
+        .map(|Entry { url, scroll, state }| PersistedEntry {
+            // urls in the history are always valid, they came from the url struct itself
+            path: Url::parse(&url).unwrap()[Position::BeforePath..].to_string(),
+            scroll,
+            state,
+        })
+        .collect();
+    let content = serde_json::to_string(&PersistedHistory { entries, index })?;
+
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temporary = file.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nested").join(FILE_NAME);
+
+        let mut history = PersistentHistory::new(&file);
+        history.push(String::from("/first"));
+        history.update_scroll(ScrollPosition { x: 1.0, y: 2.0 });
+        history.update_state(Some(String::from("state")));
+        history.push(String::from("/second?query=works#frag"));
+        history.push(String::from("/third"));
+        history.go_back();
+        drop(history);
+
+        let mut history = PersistentHistory::new(&file);
+        assert_eq!(history.current_path(), "/second");
+        assert_eq!(history.current_query(), Some(String::from("query=works")));
+        assert_eq!(history.current_fragment(), Some(String::from("frag")));
+        assert!(history.can_go_forward());
+
+        history.go_forward();
+        assert_eq!(history.current_path(), "/third");
+
+        history.go_back();
+        history.go_back();
+        assert_eq!(history.current_path(), "/first");
+        assert_eq!(
+            history.current_scroll(),
+            Some(ScrollPosition { x: 1.0, y: 2.0 })
+        );
+        assert_eq!(history.current_state(), Some(String::from("state")));
+
+        history.go_back();
+        assert_eq!(history.current_path(), "/");
+        assert!(!history.can_go_back());
+    }
+
+    #[test]
+    fn missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = PersistentHistory::new(dir.path().join(FILE_NAME));
+
+        assert_eq!(history.current_path(), "/");
+        assert!(!history.can_go_back());
+        assert!(!history.can_go_forward());
+    }
+
+    #[test]
+    fn invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(FILE_NAME);
+
+        for content in [
+            "not json",
+            r#"{"entries":[],"index":0}"#,
+            r#"{"entries":[{"path":"/","scroll":null}],"index":1}"#,
+            r#"{"entries":[{"path":"relative","scroll":null}],"index":0}"#,
+        ] {
+            fs::write(&file, content).unwrap();
+            let history = PersistentHistory::new(&file);
+
+            assert_eq!(history.current_path(), "/");
+            assert!(!history.can_go_back());
+        }
+    }
+
+    #[test]
+    fn max_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(FILE_NAME);
+
+        let mut history = PersistentHistory::with_max_entries(&file, 3);
+        for i in 0..5 {
+            history.push(format!("/{i}"));
+        }
+        drop(history);
+
+        let content: PersistedHistory =
+            serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(content.index, 2);
+        assert_eq!(
+            content
+                .entries
+                .iter()
+                .map(|e| e.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/2", "/3", "/4"]
+        );
+
+        // a lower cap applies to restored histories as well
+        let mut history = PersistentHistory::with_max_entries(&file, 2);
+        history.go_back();
+        assert_eq!(history.current_path(), "/3");
+        assert!(!history.can_go_back());
+    }
+}
@@ -4,7 +4,8 @@ use gloo_events::EventListener;
 use log::error;
 use web_sys::{History, ScrollRestoration, Window};
 
-use super::{update_history_with_scroll, HistoryProvider, ScrollPosition};
+use super::{scroll_from_history, update_history_with_scroll, HistoryProvider};
+use crate::scroll::ScrollPosition;
 
 /// A [`HistoryProvider`] that uses the [History API] and [Location] to integrate with the
 /// browser.
@@ -80,7 +81,13 @@ impl Default for WebHistory {
             EventListener::new(
                 &window.document().expect("access to document"),
                 "scroll",
-                move |_| update_history_with_scroll(&inner_window, &history),
+                move |_| {
+                    let position = ScrollPosition {
+                        x: inner_window.scroll_x().unwrap_or_default(),
+                        y: inner_window.scroll_y().unwrap_or_default(),
+                    };
+                    update_history_with_scroll(&history, position)
+                },
             )
         };
 
@@ -96,21 +103,9 @@ impl Default for WebHistory {
 
 impl HistoryProvider for WebHistory {
     fn foreign_navigation_handler(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
-        let history = self.history.clone();
-        let window = self.window.clone();
-
-        // replace listener
+        // replace listener, the router restores the scroll position once it has updated
         self.listener_navigation = Some(EventListener::new(&self.window, "popstate", move |_| {
-            // tell router to update
             callback();
-
-            // update scroll position
-            let ScrollPosition { x, y } = history
-                .state()
-                .map(|state| serde_wasm_bindgen::from_value(state).unwrap_or_default())
-                .unwrap_or_default();
-            // TODO: find way to scroll when new outlets are updated
-            window.scroll_to_with_x_and_y(x, y);
         }));
     }
 
@@ -150,6 +145,28 @@ impl HistoryProvider for WebHistory {
         }
     }
 
+    fn current_fragment(&self) -> Option<String> {
+        let mut fragment = self.window.location().hash().ok()?;
+
+        // remove # from start of fragment
+        if fragment.starts_with('#') {
+            fragment.remove(0);
+        }
+
+        match fragment.is_empty() {
+            false => Some(fragment),
+            true => None,
+        }
+    }
+
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        scroll_from_history(&self.history)
+    }
+
+    fn update_scroll(&mut self, position: ScrollPosition) {
+        update_history_with_scroll(&self.history, position);
+    }
+
     fn go_back(&mut self) {
         if let Err(e) = self.history.back() {
             error!("failed to navigate back: {e:?}");
@@ -172,13 +189,12 @@ impl HistoryProvider for WebHistory {
             path = format!("{prefix}{path}");
         }
 
-        match self.history.push_state_with_url(
+        if let Err(e) = self.history.push_state_with_url(
             &serde_wasm_bindgen::to_value(&ScrollPosition::default()).unwrap(),
             "",
             Some(&path),
         ) {
-            Ok(_) => self.window.scroll_to_with_x_and_y(0.0, 0.0),
-            Err(e) => error!("failed to push state: {e:?}"),
+            error!("failed to push state: {e:?}");
         }
     }
 
@@ -192,13 +208,12 @@ impl HistoryProvider for WebHistory {
             path = format!("{prefix}{path}");
         }
 
-        match self.history.replace_state_with_url(
+        if let Err(e) = self.history.replace_state_with_url(
             &serde_wasm_bindgen::to_value(&ScrollPosition::default()).unwrap(),
             "",
             Some(&path),
         ) {
-            Ok(_) => self.window.scroll_to_with_x_and_y(0.0, 0.0),
-            Err(e) => error!("failed to replace state: {e:?}"),
+            error!("failed to replace state: {e:?}");
         }
     }
 
@@ -5,7 +5,8 @@ use log::error;
 use url::Url;
 use web_sys::{History, ScrollRestoration, Window};
 
-use super::{update_history_with_scroll, HistoryProvider, ScrollPosition};
+use super::{scroll_from_history, update_history_with_scroll, HistoryProvider};
+use crate::scroll::ScrollPosition;
 
 /// A [`HistoryProvider`] that uses the [History API] and [Location.hash] to integrate with the
 /// browser.
@@ -89,7 +90,13 @@ impl Default for WebHashHistory {
             EventListener::new(
                 &window.document().expect("access to document"),
                 "scroll",
-                move |_| update_history_with_scroll(&inner_window, &history),
+                move |_| {
+                    let position = ScrollPosition {
+                        x: inner_window.scroll_x().unwrap_or_default(),
+                        y: inner_window.scroll_y().unwrap_or_default(),
+                    };
+                    update_history_with_scroll(&history, position)
+                },
             )
         };
 
@@ -104,21 +111,9 @@ impl Default for WebHashHistory {
 
 impl HistoryProvider for WebHashHistory {
     fn foreign_navigation_handler(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
-        let history = self.history.clone();
-        let window = self.window.clone();
-
-        // replace listener
+        // replace listener, the router restores the scroll position once it has updated
         self.listener_navigation = Some(EventListener::new(&self.window, "popstate", move |_| {
-            // tell router to update
             callback();
-
-            // update scroll position
-            let ScrollPosition { x, y } = history
-                .state()
-                .map(|state| serde_wasm_bindgen::from_value(state).unwrap_or_default())
-                .unwrap_or_default();
-            // TODO: find way to scroll when new outlets are updated
-            window.scroll_to_with_x_and_y(x, y);
         }));
     }
 
@@ -138,6 +133,20 @@ impl HistoryProvider for WebHashHistory {
             .and_then(|url| url.query().map(|query| query.to_string()))
     }
 
+    fn current_fragment(&self) -> Option<String> {
+        self.url()
+            .and_then(|url| url.fragment().map(|fragment| fragment.to_string()))
+            .filter(|fragment| !fragment.is_empty())
+    }
+
+    fn current_scroll(&self) -> Option<ScrollPosition> {
+        scroll_from_history(&self.history)
+    }
+
+    fn update_scroll(&mut self, position: ScrollPosition) {
+        update_history_with_scroll(&self.history, position);
+    }
+
     fn go_back(&mut self) {
         if let Err(e) = self.history.back() {
             error!("failed to navigate back: {e:?}");
@@ -159,9 +168,10 @@ impl HistoryProvider for WebHashHistory {
         // join path & get hash
         let hash = match self.url().map(|url| url.join(&path)) {
             Some(Ok(url)) => format!(
-                "#{path}{query}",
+                "#{path}{query}{fragment}",
                 path = url.path(),
-                query = url.query().map(|q| format!("?{q}")).unwrap_or_default()
+                query = url.query().map(|q| format!("?{q}")).unwrap_or_default(),
+                fragment = url.fragment().map(|f| format!("#{f}")).unwrap_or_default()
             ),
             Some(Err(e)) => {
                 error!("failed to join locations: {e}");
@@ -170,13 +180,12 @@ impl HistoryProvider for WebHashHistory {
             None => return,
         };
 
-        match self.history.push_state_with_url(
+        if let Err(e) = self.history.push_state_with_url(
             &serde_wasm_bindgen::to_value(&ScrollPosition::default()).unwrap(),
             "",
             Some(&hash),
         ) {
-            Ok(_) => self.window.scroll_with_x_and_y(0.0, 0.0),
-            Err(e) => error!("failed to push state: {e:?}"),
+            error!("failed to push state: {e:?}");
         }
     }
 
@@ -189,9 +198,10 @@ impl HistoryProvider for WebHashHistory {
         // join path & get hash
         let hash = match self.url().map(|url| url.join(&path)) {
             Some(Ok(url)) => format!(
-                "#{path}{query}",
+                "#{path}{query}{fragment}",
                 path = url.path(),
-                query = url.query().map(|q| format!("?{q}")).unwrap_or_default()
+                query = url.query().map(|q| format!("?{q}")).unwrap_or_default(),
+                fragment = url.fragment().map(|f| format!("#{f}")).unwrap_or_default()
             ),
             Some(Err(e)) => {
                 error!("failed to join locations: {e}");
@@ -200,14 +210,13 @@ impl HistoryProvider for WebHashHistory {
             None => return,
         };
 
-        match self.history.replace_state_with_url(
+        if let Err(e) = self.history.replace_state_with_url(
             &serde_wasm_bindgen::to_value(&ScrollPosition::default()).unwrap(),
             "",
             Some(&hash),
         ) {
-            Ok(_) => self.window.scroll_with_x_and_y(0.0, 0.0),
-            Err(e) => error!("failed to push state: {e:?}"),
-        };
+            error!("failed to push state: {e:?}");
+        }
     }
 
     fn can_external(&self) -> bool {
@@ -91,6 +91,19 @@ impl Navigator {
             .tx
             .unbounded_send(RouterMessage::Replace(target.into()));
     }
+
+    /// Store `state` for the current history entry, replacing the state stored before.
+    ///
+    /// The state is available as [`RouterState::history_state`] and is restored when the user
+    /// navigates back or forward to the entry. Whether it outlives the app depends on the
+    /// [`HistoryProvider`]; a [`PersistentHistory`] writes it to its file.
+    ///
+    /// [`RouterState::history_state`]: crate::state::RouterState::history_state
+    /// [`HistoryProvider`]: crate::history::HistoryProvider
+    /// [`PersistentHistory`]: crate::history::PersistentHistory
+    pub fn set_state(&self, state: Option<String>) {
+        let _ = self.tx.unbounded_send(RouterMessage::SetState(state));
+    }
 }
 
 #[cfg(test)]
@@ -127,6 +140,17 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn set_state() {
+        let (n, mut rx) = prepare();
+        n.set_state(Some(String::from("state")));
+
+        assert!(matches!(
+            rx.try_next(),
+            Ok(Some(RouterMessage::SetState(Some(_))))
+        ));
+    }
+
     #[test]
     fn replace() {
         let (n, mut rx) = prepare();
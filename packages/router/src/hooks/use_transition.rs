@@ -0,0 +1,98 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+use dioxus::prelude::*;
+
+use crate::contexts::{TransitionContext, TransitionState};
+
+/// The phases of content rendered by an animated [`Outlet`].
+///
+/// [`Outlet`]: crate::components::Outlet
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransitionPhase {
+    /// The content was just navigated to.
+    Entering,
+    /// The content is fully visible.
+    Entered,
+    /// The content was just navigated away from. It will be unmounted once the phase is finished.
+    Exiting,
+}
+
+/// A hook that provides access to the [`TransitionPhase`] of the content of an animated
+/// [`Outlet`].
+///
+/// The calling component is updated whenever the phase changes. The phase can be finished via
+/// [`Transition::finish`], which allows effects that aren't driven by CSS (e.g. in a terminal).
+///
+/// # Return values
+/// - [`None`], when the calling component is not nested within an animated [`Outlet`]. Routes can
+///   therefore be used with both, animated and regular [`Outlet`]s.
+/// - Otherwise [`Some`].
+///
+/// # Example
+/// ```rust,no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_router::prelude::*;
+/// fn SomeComponent(cx: Scope) -> Element {
+///     let phase = use_transition(&cx).map(|t| t.phase());
+///
+///     cx.render(rsx! {
+///         p { "phase: {phase:?}" }
+///     })
+/// }
+/// ```
+///
+/// [`Outlet`]: crate::components::Outlet
+#[must_use]
+pub fn use_transition(cx: &ScopeState) -> Option<Transition> {
+    let context = cx.use_hook(|| {
+        let context = cx.consume_context::<TransitionContext>();
+        if let Some(TransitionContext { id, state }) = &context {
+            state.borrow_mut().subscribe(*id, cx.scope_id());
+        }
+        context
+    });
+
+    context
+        .as_ref()
+        .map(|TransitionContext { id, state }| Transition {
+            id: *id,
+            phase: state.borrow().phase(*id),
+            state: state.clone(),
+        })
+}
+
+/// Information about the transition of the content of an animated [`Outlet`].
+///
+/// [`Outlet`]: crate::components::Outlet
+#[derive(Clone)]
+pub struct Transition {
+    id: usize,
+    phase: TransitionPhase,
+    state: Rc<RefCell<TransitionState>>,
+}
+
+impl Transition {
+    /// Get the current phase.
+    #[must_use]
+    pub fn phase(&self) -> TransitionPhase {
+        self.phase
+    }
+
+    /// Finish the current phase.
+    ///
+    /// Entering content becomes entered, exiting content is unmounted. Does nothing if the content
+    /// is already entered, or if the phase has already been finished.
+    pub fn finish(&self) {
+        self.state.borrow_mut().finish(self.id);
+    }
+}
+
+// [`TransitionState`] (in `state`) is internal
+impl Debug for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transition")
+            .field("id", &self.id)
+            .field("phase", &self.phase)
+            .finish_non_exhaustive()
+    }
+}
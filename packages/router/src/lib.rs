@@ -32,6 +32,9 @@ mod contexts {
 
     mod router;
     pub(crate) use router::*;
+
+    mod transition;
+    pub(crate) use transition::*;
 }
 
 /// Helper functions used within the router.
@@ -50,6 +53,9 @@ pub mod hooks {
 
     mod use_segment;
     pub use use_segment::*;
+
+    mod use_transition;
+    pub use use_transition::*;
 }
 
 /// Navigation information.
@@ -73,6 +79,9 @@ pub mod route_definition {
     mod matcher;
     pub use matcher::*;
 
+    mod metadata;
+    pub use metadata::*;
+
     mod parameter;
     pub use parameter::*;
 
@@ -118,6 +127,9 @@ pub mod names {
     // ];
 }
 
+/// Scroll position handling.
+pub mod scroll;
+
 /// The core of the router.
 mod service;
 
@@ -12,6 +12,9 @@ pub enum NavigationTarget {
     ///
     /// If the path starts with a `/` it is treated as an absolute path. Otherwise it is treated as
     /// relative.
+    ///
+    /// The path may end with a fragment (`#section`). After navigating, the router will scroll to
+    /// the element identified by it.
     InternalTarget(String),
     /// Navigate to the route with the corresponding name.
     ///
@@ -28,6 +31,9 @@ pub enum NavigationTarget {
         /// The contained values will be used to construct the actual path as needed.
         Vec<(&'static str, String)>,
         /// The query.
+        ///
+        /// A [`Query::QueryString`] may end with a fragment (`#section`). Use
+        /// [`NavigationTarget::with_fragment`] to add one.
         Option<Query>,
     ),
     /// Navigate to an external page.
@@ -51,6 +57,66 @@ impl NavigationTarget {
     pub fn is_external_target(&self) -> bool {
         matches!(self, Self::ExternalTarget(..))
     }
+
+    /// Get the fragment of the navigation target, without the leading `#`.
+    ///
+    /// Returns [`None`] if the target has no fragment or if it is empty.
+    #[must_use]
+    pub fn fragment(&self) -> Option<&str> {
+        let fragment = match self {
+            Self::InternalTarget(path) | Self::ExternalTarget(path) => path.split_once('#')?.1,
+            Self::NamedTarget(_, _, Some(Query::QueryString(query))) => query.split_once('#')?.1,
+            Self::NamedTarget(..) => return None,
+        };
+
+        match fragment.is_empty() {
+            false => Some(fragment),
+            true => None,
+        }
+    }
+
+    /// Set the fragment of the navigation target. An existing fragment is replaced.
+    ///
+    /// The `fragment` may be provided with or without the leading `#`. For a [`NamedTarget`], the
+    /// fragment is appended to the query, which is turned into a [`Query::QueryString`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus_router::prelude::*;
+    /// let target = NavigationTarget::from("/some/path#old").with_fragment("new");
+    /// assert_eq!(target.fragment(), Some("new"));
+    /// ```
+    ///
+    /// [`NamedTarget`]: NavigationTarget::NamedTarget
+    #[must_use]
+    pub fn with_fragment(self, fragment: impl Into<String>) -> Self {
+        let mut fragment = fragment.into();
+        if fragment.starts_with('#') {
+            fragment.remove(0);
+        }
+
+        let append = |mut path: String| {
+            if let Some(index) = path.find('#') {
+                path.truncate(index);
+            }
+            format!("{path}#{fragment}")
+        };
+
+        match self {
+            Self::InternalTarget(path) => Self::InternalTarget(append(path)),
+            Self::NamedTarget(name, parameters, query) => {
+                let query = match query {
+                    None => String::new(),
+                    Some(Query::QueryString(query)) => query,
+                    Some(Query::QueryVec(vals)) => {
+                        serde_urlencoded::to_string(vals).unwrap_or_default()
+                    }
+                };
+                Self::NamedTarget(name, parameters, Some(Query::QueryString(append(query))))
+            }
+            Self::ExternalTarget(url) => Self::ExternalTarget(append(url)),
+        }
+    }
 }
 
 impl From<String> for NavigationTarget {
@@ -194,4 +260,51 @@ mod tests {
             assert_eq!(path, target);
         }
     }
+
+    #[test]
+    fn nt_fragment() {
+        assert_eq!(
+            NavigationTarget::from("/some/route#frag").fragment(),
+            Some("frag")
+        );
+        assert_eq!(NavigationTarget::from("/some/route#").fragment(), None);
+        assert_eq!(NavigationTarget::from("/some/route").fragment(), None);
+        assert_eq!(
+            NavigationTarget::from("https://dioxuslabs.com/#frag").fragment(),
+            Some("frag")
+        );
+    }
+
+    #[test]
+    fn nt_with_fragment_internal() {
+        let nt = NavigationTarget::from("/some/route#old").with_fragment("#new");
+
+        assert!(matches!(nt, NavigationTarget::InternalTarget(_)));
+        if let NavigationTarget::InternalTarget(path) = nt {
+            assert_eq!(path, "/some/route#new");
+        }
+    }
+
+    #[test]
+    fn nt_with_fragment_named() {
+        struct Test;
+        let nt = NavigationTarget::from((Test, [])).with_fragment("new");
+
+        assert_eq!(nt.fragment(), Some("new"));
+    }
+
+    #[test]
+    fn nt_with_fragment_named_query() {
+        struct Test;
+        let nt = NavigationTarget::from((Test, [], vec![("some", "query")])).with_fragment("new");
+
+        assert_eq!(nt.fragment(), Some("new"));
+        assert!(matches!(
+            nt,
+            NavigationTarget::NamedTarget(_, _, Some(Query::QueryString(_)))
+        ));
+        if let NavigationTarget::NamedTarget(_, _, Some(Query::QueryString(query))) = nt {
+            assert_eq!(query, "some=query#new");
+        }
+    }
 }
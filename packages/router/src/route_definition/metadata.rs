@@ -0,0 +1,106 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::Arc,
+};
+
+/// Typed metadata of a [`Route`] or [`ParameterRoute`].
+///
+/// Contains at most one value of every type. Values are identified by their type, so applications
+/// usually define their own types for their metadata (e.g. `struct Title(&'static str)`).
+///
+/// [`Route`]: super::Route
+/// [`ParameterRoute`]: super::ParameterRoute
+#[derive(Clone, Default)]
+pub struct RouteMetadata {
+    values: BTreeMap<TypeId, (&'static str, Arc<dyn Any + Send + Sync>)>,
+}
+
+impl RouteMetadata {
+    /// Create a new, empty [`RouteMetadata`].
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the value of type `T`, if present.
+    #[must_use]
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|(_, value)| value.downcast_ref())
+    }
+
+    /// Check whether a value of type `T` is present.
+    #[must_use]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Check whether no values are present.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Insert `value`, replacing a previous value of the same type.
+    ///
+    /// Returns [`true`] if a value was replaced.
+    pub(crate) fn insert<T: Any + Send + Sync>(&mut self, value: T) -> bool {
+        self.values
+            .insert(TypeId::of::<T>(), (type_name::<T>(), Arc::new(value)))
+            .is_some()
+    }
+}
+
+// [`Any`] (in `values`) doesn't implement [`Debug`]
+impl Debug for RouteMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.values.values().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Title(&'static str);
+    #[derive(Debug, PartialEq)]
+    struct Label(&'static str);
+
+    #[test]
+    fn get() {
+        let mut metadata = RouteMetadata::new();
+        assert!(metadata.is_empty());
+
+        assert!(!metadata.insert(Title("title")));
+        assert!(metadata.contains::<Title>());
+        assert!(!metadata.contains::<Label>());
+        assert_eq!(metadata.get::<Title>(), Some(&Title("title")));
+        assert_eq!(metadata.get::<Label>(), None);
+    }
+
+    #[test]
+    fn replace() {
+        let mut metadata = RouteMetadata::new();
+
+        metadata.insert(Title("first"));
+        assert!(metadata.insert(Title("second")));
+        assert_eq!(metadata.get::<Title>(), Some(&Title("second")));
+    }
+
+    #[test]
+    fn debug() {
+        let mut metadata = RouteMetadata::new();
+        metadata.insert(Title("title"));
+
+        assert_eq!(
+            format!("{metadata:?}"),
+            r#"{"dioxus_router::route_definition::metadata::tests::Title"}"#
+        );
+    }
+}
@@ -1,8 +1,8 @@
-use std::any::{type_name, TypeId};
+use std::any::{type_name, Any, TypeId};
 
 use log::error;
 
-use super::{RouteContent, Segment};
+use super::{RouteContent, RouteMetadata, Segment};
 
 /// A route that treats its actual value as a parameter.
 #[derive(Debug)]
@@ -10,6 +10,7 @@ pub struct ParameterRoute {
     pub(crate) name: Option<(TypeId, &'static str)>,
     pub(crate) key: &'static str,
     pub(crate) content: RouteContent,
+    pub(crate) metadata: RouteMetadata,
     pub(crate) nested: Option<Box<Segment>>,
 }
 
@@ -24,12 +25,43 @@ impl ParameterRoute {
     pub fn new(key: &'static str, content: impl Into<RouteContent>) -> Self {
         Self {
             content: content.into(),
+            metadata: Default::default(),
             name: Default::default(),
             key,
             nested: Default::default(),
         }
     }
 
+    /// Add a metadata value.
+    ///
+    /// The metadata of the active routes is available through the [`RouterState`]. Values of inner
+    /// routes take precedence over values of the same type of outer routes.
+    ///
+    /// Values are identified by their type. Call this function multiple times to add values of
+    /// different types.
+    ///
+    /// # Panic
+    /// - If a value of the same type was already set, but only in debug builds.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus_router::prelude::*;
+    /// struct Title(&'static str);
+    /// ParameterRoute::new("key", ()).metadata(Title("title"));
+    /// ```
+    ///
+    /// [`RouterState`]: crate::state::RouterState
+    pub fn metadata<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        if self.metadata.insert(value) {
+            let name = type_name::<T>();
+            error!(r#"metadata already set: "{name}", later prevails"#);
+            #[cfg(debug_assertions)]
+            panic!(r#"metadata already set: "{name}""#);
+        }
+
+        self
+    }
+
     /// Add a name.
     ///
     /// The name can be used for name based navigation. See [`NamedTarget`] for more details. Make
@@ -100,6 +132,35 @@ mod tests {
     struct Test;
     struct Test2;
 
+    #[test]
+    fn metadata() {
+        let r = ParameterRoute::new("", RouteContent::Empty)
+            .metadata(Test)
+            .metadata(Test2);
+
+        assert!(r.metadata.contains::<Test>());
+        assert!(r.metadata.contains::<Test2>());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic = r#"metadata already set: "dioxus_router::route_definition::parameter::tests::Test""#]
+    fn metadata_panic_in_debug() {
+        ParameterRoute::new("", RouteContent::Empty)
+            .metadata(Test)
+            .metadata(Test);
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn metadata_override_in_release() {
+        let r = ParameterRoute::new("", RouteContent::Empty)
+            .metadata(Test)
+            .metadata(Test);
+
+        assert!(r.metadata.contains::<Test>());
+    }
+
     #[test]
     fn name() {
         let r = ParameterRoute::new("", RouteContent::Empty).name(Test);
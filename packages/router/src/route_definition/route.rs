@@ -1,13 +1,14 @@
-use std::any::{type_name, TypeId};
+use std::any::{type_name, Any, TypeId};
 
 use log::error;
 
-use super::{RouteContent, Segment};
+use super::{RouteContent, RouteMetadata, Segment};
 
 /// A static route.
 #[derive(Debug)]
 pub struct Route {
     pub(crate) content: RouteContent,
+    pub(crate) metadata: RouteMetadata,
     pub(crate) name: Option<(TypeId, &'static str)>,
     pub(crate) nested: Option<Segment>,
 }
@@ -23,11 +24,42 @@ impl Route {
     pub fn new(content: impl Into<RouteContent>) -> Self {
         Self {
             content: content.into(),
+            metadata: Default::default(),
             name: Default::default(),
             nested: Default::default(),
         }
     }
 
+    /// Add a metadata value.
+    ///
+    /// The metadata of the active routes is available through the [`RouterState`]. Values of inner
+    /// routes take precedence over values of the same type of outer routes.
+    ///
+    /// Values are identified by their type. Call this function multiple times to add values of
+    /// different types.
+    ///
+    /// # Panic
+    /// - If a value of the same type was already set, but only in debug builds.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus_router::prelude::*;
+    /// struct Title(&'static str);
+    /// Route::new(()).metadata(Title("title"));
+    /// ```
+    ///
+    /// [`RouterState`]: crate::state::RouterState
+    pub fn metadata<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        if self.metadata.insert(value) {
+            let name = type_name::<T>();
+            error!(r#"metadata already set: "{name}", later prevails"#);
+            #[cfg(debug_assertions)]
+            panic!(r#"metadata already set: "{name}""#);
+        }
+
+        self
+    }
+
     /// Add a name.
     ///
     /// The name can be used for name based navigation. See [`NamedTarget`] for more details. Make
@@ -98,6 +130,35 @@ mod tests {
     struct Test;
     struct Test2;
 
+    #[test]
+    fn metadata() {
+        let r = Route::new(RouteContent::Empty)
+            .metadata(Test)
+            .metadata(Test2);
+
+        assert!(r.metadata.contains::<Test>());
+        assert!(r.metadata.contains::<Test2>());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic = r#"metadata already set: "dioxus_router::route_definition::route::tests::Test""#]
+    fn metadata_panic_in_debug() {
+        Route::new(RouteContent::Empty)
+            .metadata(Test)
+            .metadata(Test);
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn metadata_override_in_release() {
+        let r = Route::new(RouteContent::Empty)
+            .metadata(Test)
+            .metadata(Test);
+
+        assert!(r.metadata.contains::<Test>());
+    }
+
     #[test]
     fn name() {
         let r = Route::new(RouteContent::Empty).name(Test);
@@ -1,9 +1,12 @@
-use std::collections::{BTreeMap, HashSet};
+use std::{
+    any::{type_name, Any},
+    collections::{BTreeMap, HashSet},
+};
 
 use log::error;
 use urlencoding::encode;
 
-use super::{ParameterRoute, Route, RouteContent, SegmentMatch};
+use super::{ParameterRoute, Route, RouteContent, RouteMetadata, SegmentMatch};
 
 /// A collection of routes for a single path segment.
 ///
@@ -14,6 +17,7 @@ pub struct Segment {
     pub(crate) fallback: RouteContent,
     pub(crate) fixed: BTreeMap<String, Route>,
     pub(crate) index: RouteContent,
+    pub(crate) index_metadata: RouteMetadata,
     pub(crate) matching: Vec<(Box<dyn SegmentMatch>, ParameterRoute)>,
     pub(crate) catch_all: Option<ParameterRoute>,
 }
@@ -53,6 +57,34 @@ impl Segment {
         self
     }
 
+    /// Add a metadata value to the _index_ route.
+    ///
+    /// This works like [`Route::metadata`]. While the _index_ route is active, its metadata is the
+    /// innermost entry of [`RouterState::route_metadata`], with the path of the route the
+    /// [`Segment`] is nested in (or `/` for the root segment).
+    ///
+    /// # Panic
+    /// - If a value of the same type was already set, but only in debug builds.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus_router::prelude::*;
+    /// struct Title(&'static str);
+    /// Segment::new().index(()).index_metadata(Title("Home"));
+    /// ```
+    ///
+    /// [`RouterState::route_metadata`]: crate::state::RouterState::route_metadata
+    pub fn index_metadata<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        if self.index_metadata.insert(value) {
+            let name = type_name::<T>();
+            error!(r#"index metadata already set: "{name}", later prevails"#);
+            #[cfg(debug_assertions)]
+            panic!(r#"index metadata already set: "{name}""#);
+        }
+
+        self
+    }
+
     /// Add a _fixed_ route.
     ///
     /// A _fixed_ route acts like a static file or directory (with most web servers). It is active,
@@ -235,6 +267,39 @@ impl Segment {
     /// explained in the previous paragraph is unambiguous, but parameter URLs must be filtered out
     /// if valid URLs are required.
     pub fn sitemap(&self) -> Vec<String> {
+        self.sitemap_internal(None, None)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Generate a sitemap, including the metadata of the routes.
+    ///
+    /// This function works like [`Segment::sitemap`], but also provides the [`RouteMetadata`] of
+    /// the route every path leads to. This allows applications to generate navigation menus from
+    /// their route definitions.
+    ///
+    /// The path of the root _index_ route (`/`) comes with the metadata of the _index_ route. The
+    /// metadata of nested _index_ routes is not included, as they share their path with the route
+    /// they are nested in.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus_router::prelude::*;
+    /// struct Label(&'static str);
+    ///
+    /// let segment = Segment::new()
+    ///     .fixed("blog", Route::new(()).metadata(Label("Blog")))
+    ///     .fixed("hidden", Route::new(()));
+    ///
+    /// let menu: Vec<_> = segment
+    ///     .sitemap_with_metadata()
+    ///     .into_iter()
+    ///     .filter_map(|(path, metadata)| metadata.get::<Label>().map(|label| (path, label.0)))
+    ///     .collect();
+    /// assert_eq!(menu, vec![(String::from("/blog/"), "Blog")]);
+    /// ```
+    pub fn sitemap_with_metadata(&self) -> Vec<(String, &RouteMetadata)> {
         self.sitemap_internal(None, None)
     }
 
@@ -252,27 +317,30 @@ impl Segment {
     ) -> HashSet<String> {
         self.sitemap_internal(Some(params), None)
             .into_iter()
+            .map(|(path, _)| path)
             .collect()
     }
 
     /// Generate a full sitemap with the provided parameters / parent path.
+    ///
+    /// Every path is accompanied by the metadata of the route it leads to.
     fn sitemap_internal(
         &self,
         params: Option<&BTreeMap<&'static str, HashSet<String>>>,
         parents: Option<&str>,
-    ) -> Vec<String> {
+    ) -> Vec<(String, &RouteMetadata)> {
         let parents = parents.unwrap_or("/");
         let mut res = Vec::new();
 
         // insert index
         if parents == "/" {
-            res.push(String::from("/"));
+            res.push((String::from("/"), &self.index_metadata));
         }
 
         // insert fixed routes
         for (name, route) in &self.fixed {
             let parents = format!("{parents}{}/", encode(name));
-            res.push(parents.clone());
+            res.push((parents.clone(), &route.metadata));
 
             if let Some(n) = &route.nested {
                 res.append(&mut n.sitemap_internal(params, Some(&parents)));
@@ -284,7 +352,7 @@ impl Segment {
             match params {
                 None => {
                     let parents = format!("{parents}\\{}/", encode(route.key));
-                    res.push(parents.clone());
+                    res.push((parents.clone(), &route.metadata));
 
                     if let Some(n) = &route.nested {
                         res.append(&mut n.sitemap_internal(params, Some(&parents)));
@@ -295,7 +363,7 @@ impl Segment {
                         for p in p {
                             if regex.matches(p) {
                                 let parents = format!("{parents}{}/", encode(p));
-                                res.push(parents.clone());
+                                res.push((parents.clone(), &route.metadata));
 
                                 if let Some(n) = &route.nested {
                                     res.append(&mut n.sitemap_internal(params, Some(&parents)));
@@ -312,7 +380,7 @@ impl Segment {
             match params {
                 None => {
                     let parents = format!("{parents}\\{}/", encode(route.key));
-                    res.push(parents.clone());
+                    res.push((parents.clone(), &route.metadata));
 
                     if let Some(n) = &route.nested {
                         res.append(&mut n.sitemap_internal(params, Some(&parents)));
@@ -322,7 +390,7 @@ impl Segment {
                     if let Some(p) = p.get(route.key) {
                         for p in p {
                             let parents = format!("{parents}{}/", encode(p));
-                            res.push(parents.clone());
+                            res.push((parents.clone(), &route.metadata));
 
                             if let Some(n) = &route.nested {
                                 res.append(&mut n.sitemap_internal(params, Some(&parents)));
@@ -345,6 +413,9 @@ mod tests {
     use dioxus::prelude::*;
     use regex::Regex;
 
+    struct Test;
+    struct Test2;
+
     #[test]
     fn fallback() {
         let s = Segment::new().fallback("test");
@@ -412,6 +483,21 @@ mod tests {
         assert!(!s.index.is_empty());
     }
 
+    #[test]
+    fn index_metadata() {
+        let s = Segment::new().index_metadata(Test).index_metadata(Test2);
+
+        assert!(s.index_metadata.contains::<Test>());
+        assert!(s.index_metadata.contains::<Test2>());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic = r#"index metadata already set: "dioxus_router::route_definition::segment::tests::Test""#]
+    fn index_metadata_panic_in_debug() {
+        Segment::new().index_metadata(Test).index_metadata(Test);
+    }
+
     #[cfg(debug_assertions)]
     #[test]
     #[should_panic = "index route already set"]
@@ -448,6 +534,7 @@ mod tests {
                 name: _,
                 key: _,
                 content: RouteContent::Redirect(NavigationTarget::InternalTarget(target)),
+                metadata: _,
                 nested: _,
             }) => target == "test",
             _ => false,
@@ -0,0 +1,54 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "web")]
+mod web;
+#[cfg(feature = "web")]
+pub use web::*;
+
+/// The position a page is scrolled to.
+///
+/// [`HistoryProvider`]s store one for each history entry, so it can be restored when navigating
+/// through the history.
+///
+/// [`HistoryProvider`]: crate::history::HistoryProvider
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ScrollPosition {
+    /// The horizontal scroll position.
+    pub x: f64,
+    /// The vertical scroll position.
+    pub y: f64,
+}
+
+/// A trait that lets the router access and change the scroll position of the page.
+///
+/// The router uses it to restore the scroll position when navigating through the history, and to
+/// scroll to the element identified by the fragment of a [`NavigationTarget`]. Scrolling happens
+/// after the [`Outlet`]s have been updated.
+///
+/// Provided implementations:
+/// - [`WebScroll`] scrolls the browser window.
+///
+/// Other renderers can participate by implementing this trait and passing it to the [`Router`]
+/// via its `scroll` property.
+///
+/// [`NavigationTarget`]: crate::navigation::NavigationTarget
+/// [`Outlet`]: crate::components::Outlet
+/// [`Router`]: crate::components::Router
+pub trait ScrollProvider
+where
+    Self: Debug,
+{
+    /// Get the current scroll position.
+    #[must_use]
+    fn current_position(&self) -> ScrollPosition;
+
+    /// Scroll to the specified `position`.
+    fn scroll_to(&mut self, position: ScrollPosition);
+
+    /// Scroll to the element identified by `fragment` (without the leading `#`).
+    ///
+    /// Return [`false`] if no such element exists. The router will then scroll to the top.
+    fn scroll_to_fragment(&mut self, fragment: &str) -> bool;
+}
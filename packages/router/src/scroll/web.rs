@@ -0,0 +1,54 @@
+use web_sys::Window;
+
+use super::{ScrollPosition, ScrollProvider};
+
+/// A [`ScrollProvider`] that scrolls the browser window.
+///
+/// Fragments are resolved by looking up the element with the matching `id`.
+#[derive(Debug)]
+pub struct WebScroll {
+    window: Window,
+}
+
+impl WebScroll {
+    /// Create a new [`WebScroll`].
+    pub fn new() -> Box<Self> {
+        Box::new(Self::default())
+    }
+}
+
+impl Default for WebScroll {
+    fn default() -> Self {
+        Self {
+            window: web_sys::window().expect("access to window"),
+        }
+    }
+}
+
+impl ScrollProvider for WebScroll {
+    fn current_position(&self) -> ScrollPosition {
+        ScrollPosition {
+            x: self.window.scroll_x().unwrap_or_default(),
+            y: self.window.scroll_y().unwrap_or_default(),
+        }
+    }
+
+    fn scroll_to(&mut self, ScrollPosition { x, y }: ScrollPosition) {
+        self.window.scroll_to_with_x_and_y(x, y);
+    }
+
+    fn scroll_to_fragment(&mut self, fragment: &str) -> bool {
+        let element = self
+            .window
+            .document()
+            .and_then(|document| document.get_element_by_id(fragment));
+
+        match element {
+            Some(element) => {
+                element.scroll_into_view();
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -16,8 +16,6 @@ use urlencoding::decode;
 
 #[cfg(not(all(feature = "web", target_family = "wasm")))]
 use crate::history::MemoryHistory;
-#[cfg(all(feature = "web", target_family = "wasm"))]
-use crate::history::WebHistory;
 use crate::{
     components::RouterUpdateCallback,
     contexts::RouterContext,
@@ -25,9 +23,12 @@ use crate::{
     history::HistoryProvider,
     names::{FallbackExternalNavigation, FallbackNamedNavigation, RootIndex},
     navigation::{NamedNavigationSegment, NavigationTarget},
-    route_definition::{RouteContent, Segment},
+    route_definition::{RouteContent, RouteMetadata, Segment},
+    scroll::{ScrollPosition, ScrollProvider},
     state::RouterState,
 };
+#[cfg(all(feature = "web", target_family = "wasm"))]
+use crate::{history::WebHistory, scroll::WebScroll};
 
 /// A set of messages that the [`RouterService`] can handle.
 #[derive(Debug)]
@@ -44,6 +45,14 @@ pub(crate) enum RouterMessage {
     /// Replace the current history item with a new one.
     Replace(NavigationTarget),
 
+    /// Tell the router that an [`Outlet`] has been rendered and its content is mounted.
+    ///
+    /// [`Outlet`]: crate::components::Outlet
+    Rendered,
+
+    /// Store state for the current history item.
+    SetState(Option<String>),
+
     /// Subscribe the specified scope to router updates.
     Subscribe(Arc<ScopeId>),
 
@@ -73,9 +82,16 @@ pub(crate) struct RouterService {
     named_routes: Arc<BTreeMap<TypeId, Vec<NamedNavigationSegment>>>,
     /// All routes.
     routes: Arc<Segment>,
+    /// Whether the scroll position needs to be updated, once the [`Outlet`]s have been rendered.
+    /// Contains the `restore` argument for [`RouterService::update_scroll`].
+    ///
+    /// [`Outlet`]: crate::components::Outlet
+    pending_scroll: Option<bool>,
     /// The receiving end of the channel components, hooks and [`HistoryProvider`]s will send
     /// updates to.
     rx: UnboundedReceiver<RouterMessage>,
+    /// The [`ScrollProvider`], if scrolling is supported.
+    scroll: Option<Box<dyn ScrollProvider>>,
     /// The current state of the router. Shared with components and hooks.
     state: Arc<RwLock<RouterState>>,
     /// The components to update when the routing `state` changes.
@@ -106,6 +122,7 @@ impl RouterService {
         routes: Arc<Segment>,
         update: Arc<dyn Fn(ScopeId)>,
         history: Option<Box<dyn HistoryProvider>>,
+        scroll: Option<Box<dyn ScrollProvider>>,
         fallback_external_navigation: Component,
         fallback_named_navigation: Component,
         update_callback: Option<RouterUpdateCallback>,
@@ -136,6 +153,10 @@ impl RouterService {
             let _ = tx.unbounded_send(RouterMessage::Update);
         }));
 
+        // initiate the scroll provider
+        #[cfg(all(feature = "web", target_family = "wasm"))]
+        let scroll = scroll.or_else(|| Some(WebScroll::new() as Box<dyn ScrollProvider>));
+
         (
             Self {
                 fallback_external_navigation,
@@ -143,8 +164,10 @@ impl RouterService {
                 history,
                 named_routes,
                 update_callback,
+                pending_scroll: None,
                 routes,
                 rx,
+                scroll,
                 state,
                 subscribers: vec![],
                 update,
@@ -166,26 +189,51 @@ impl RouterService {
         self.update_routing();
 
         while let Some(x) = self.rx.next().await {
-            match x {
-                RouterMessage::GoBack => self.history.go_back(),
-                RouterMessage::GoForward => self.history.go_forward(),
+            // whether to restore the stored scroll position of the new location
+            let restore_scroll = match x {
+                RouterMessage::GoBack => {
+                    self.store_scroll();
+                    self.history.go_back();
+                    true
+                }
+                RouterMessage::GoForward => {
+                    self.store_scroll();
+                    self.history.go_forward();
+                    true
+                }
                 RouterMessage::Push(target) => {
+                    self.store_scroll();
                     if self.push_with_navigation_target(target) {
                         continue; // navigation failure
                     }
+                    false
                 }
                 RouterMessage::Replace(target) => {
                     if self.replace_with_navigation_target(target) {
                         continue; // navigation failure
                     }
+                    false
+                }
+                RouterMessage::Rendered => {
+                    if let Some(restore) = self.pending_scroll.take() {
+                        self.state.write().unwrap().scroll_pending = false;
+                        self.update_scroll(restore);
+                    }
+                    continue; // no navigation happened
+                }
+                RouterMessage::SetState(state) => {
+                    self.history.update_state(state);
+                    self.state.write().unwrap().history_state = self.history.current_state();
+                    self.update_subscribers();
+                    continue; // no navigation happened
                 }
                 RouterMessage::Subscribe(id) => {
                     self.subscribers.push(Arc::downgrade(&id));
                     (self.update)(*id);
                     continue; // no navigation happened
                 }
-                RouterMessage::Update => { /* update triggered at end of loop */ }
-            }
+                RouterMessage::Update => true,
+            };
 
             self.update_routing();
 
@@ -193,10 +241,57 @@ impl RouterService {
                 continue; // navigation failure
             }
 
+            if self.scroll.is_some() {
+                self.pending_scroll = Some(restore_scroll);
+                self.state.write().unwrap().scroll_pending = true;
+            }
             self.update_subscribers();
         }
     }
 
+    /// Store the current scroll position for the current history entry.
+    fn store_scroll(&mut self) {
+        if let Some(scroll) = &self.scroll {
+            self.history.update_scroll(scroll.current_position());
+        }
+    }
+
+    /// Scroll to the appropriate position. Called once the [`Outlet`]s have been rendered.
+    ///
+    /// If `restore` is [`true`], the scroll position the [`HistoryProvider`] has stored for the
+    /// current location is restored. Otherwise, or if no position is stored, the router scrolls to
+    /// the element identified by the current fragment. If there is no such element and `restore`
+    /// is [`false`], the router scrolls to the top.
+    ///
+    /// [`Outlet`]: crate::components::Outlet
+    fn update_scroll(&mut self, restore: bool) {
+        let scroll = match self.scroll.as_mut() {
+            Some(scroll) => scroll,
+            None => return,
+        };
+
+        if restore {
+            if let Some(position) = self.history.current_scroll() {
+                scroll.scroll_to(position);
+                return;
+            }
+        }
+
+        let found = match self.history.current_fragment() {
+            Some(fragment) => {
+                let fragment = decode(&fragment)
+                    .map(|fragment| fragment.to_string())
+                    .unwrap_or(fragment);
+                scroll.scroll_to_fragment(&fragment)
+            }
+            None => false,
+        };
+
+        if !found && !restore {
+            scroll.scroll_to(ScrollPosition::default());
+        }
+    }
+
     /// Update the current state of the router.
     fn update_routing(&mut self) {
         // prepare variables
@@ -212,11 +307,15 @@ impl RouterService {
                 can_go_back: _,
                 can_go_forward: _,
                 components,
+                scroll_pending: _,
                 names,
                 path,
                 prefix: _,
                 query: _,
+                fragment: _,
+                history_state: _,
                 parameters,
+                route_metadata,
             } = &mut *state;
 
             // normalize and split path
@@ -231,6 +330,7 @@ impl RouterService {
             // index on root
             let next = if path.is_empty() && !empty_root {
                 names.insert(TypeId::of::<RootIndex>());
+                add_index_metadata(&self.routes, String::from("/"), route_metadata);
                 self.routes.index.add_to_list(components)
             }
             // all other cases
@@ -241,6 +341,7 @@ impl RouterService {
                     components,
                     names,
                     parameters,
+                    route_metadata,
                     &RouteContent::Empty,
                 )
             };
@@ -438,6 +539,7 @@ impl Debug for RouterService {
             .field("named_routes", &self.named_routes)
             .field("routes", &self.routes)
             .field("rx", &self.rx)
+            .field("scroll", &self.scroll)
             .field("state", &self.state)
             .field("subscribers", &self.subscribers)
             .finish_non_exhaustive()
@@ -455,7 +557,23 @@ fn clear_state(state: &mut RouterState, history: &dyn HistoryProvider) {
     state.path = history.current_path();
     state.prefix = history.current_prefix();
     state.query = history.current_query();
+    state.fragment = history.current_fragment();
+    state.history_state = history.current_state();
     state.parameters.clear();
+    state.route_metadata.clear();
+}
+
+/// Add the metadata of the _index_ route of `segment` to `metadata`, with the path `path`.
+///
+/// Nothing is added if the _index_ route has no metadata.
+fn add_index_metadata(
+    segment: &Segment,
+    path: String,
+    metadata: &mut Vec<(String, RouteMetadata)>,
+) {
+    if !segment.index_metadata.is_empty() {
+        metadata.push((path, segment.index_metadata.clone()));
+    }
 }
 
 /// Traverse the provided `segment` and populate `named` with the named routes.
@@ -531,14 +649,17 @@ fn construct_named_targets(
 
 /// Takes in a `segment` and finds the active routes based on the first `path` value.
 ///
-/// Populates `components`, `names` and `vars` with values found while finding all active routes.
+/// Populates `components`, `names`, `vars` and `metadata` with values found while finding all
+/// active routes.
 #[must_use]
+#[allow(clippy::too_many_arguments)]
 fn match_segment<'a>(
     path: &[&str],
     segment: &'a Segment,
     components: &mut (Vec<Component>, BTreeMap<&'static str, Vec<Component>>),
     names: &mut BTreeSet<TypeId>,
     parameters: &mut BTreeMap<&'static str, String>,
+    metadata: &mut Vec<(String, RouteMetadata)>,
     mut fallback: &'a RouteContent,
 ) -> Option<NavigationTarget> {
     let decoded_path = decode(path[0])
@@ -557,6 +678,7 @@ fn match_segment<'a>(
     let mut name = None;
     let mut nested = None;
     let mut key = None;
+    let mut route_metadata = None;
 
     // extract data
     if let Some(route) = segment.fixed.get(&decoded_path) {
@@ -564,6 +686,7 @@ fn match_segment<'a>(
         content = &route.content;
         name = route.name;
         nested = route.nested.as_ref();
+        route_metadata = Some(&route.metadata);
     } else if let Some((_, route)) = segment
         .matching
         .iter()
@@ -574,12 +697,14 @@ fn match_segment<'a>(
         key = Some(route.key);
         name = route.name;
         nested = route.nested.as_ref().map(|b| b.as_ref());
+        route_metadata = Some(&route.metadata);
     } else if let Some(route) = &segment.catch_all {
         found_route = true;
         content = &route.content;
         key = Some(route.key);
         name = route.name;
         nested = route.nested.as_ref().map(|b| b.as_ref());
+        route_metadata = Some(&route.metadata);
     }
 
     // check if fallback is overwritten
@@ -602,16 +727,32 @@ fn match_segment<'a>(
         }
     }
 
+    // metadata, with the path of the route
+    if let Some(route_metadata) = route_metadata {
+        let parent = metadata.last().map(|(p, _)| p.as_str()).unwrap_or_default();
+        metadata.push((format!("{parent}/{}", path[0]), route_metadata.clone()));
+    }
+
     if let Some(nested) = nested {
         // index route
         if path.len() == 1 {
+            let route_path = metadata.last().map(|(p, _)| p.clone()).unwrap_or_default();
+            add_index_metadata(nested, route_path, metadata);
             if let Some(target) = nested.index.add_to_list(components) {
                 return Some(target);
             }
         }
         // nested routes
         else {
-            return match_segment(&path[1..], nested, components, names, parameters, fallback);
+            return match_segment(
+                &path[1..],
+                nested,
+                components,
+                names,
+                parameters,
+                metadata,
+                fallback,
+            );
         }
     }
 
@@ -623,6 +764,7 @@ fn match_segment<'a>(
         components.1.clear();
         names.clear();
         parameters.clear();
+        metadata.clear();
         return fallback.add_to_list(components);
     }
 
@@ -642,6 +784,7 @@ mod tests {
     struct Nested2;
     struct Match;
     struct Parameter;
+    struct Title(&'static str);
 
     #[test]
     fn named_targets() {
@@ -750,6 +893,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["fixed"],
@@ -757,6 +901,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -773,6 +918,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["fixed-%C3%84%C3%96%C3%9C"],
@@ -780,6 +926,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -796,6 +943,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["nested"],
@@ -803,6 +951,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -819,6 +968,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["nested", "second-layer"],
@@ -826,6 +976,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -843,6 +994,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["m1test"],
@@ -850,6 +1002,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -867,6 +1020,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["m1%C3%84%C3%96%C3%9C"],
@@ -874,6 +1028,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -891,6 +1046,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["test"],
@@ -898,6 +1054,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -915,6 +1072,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["nested", "redirect"],
@@ -922,6 +1080,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -945,6 +1104,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["nested", "invalid", "another"],
@@ -952,6 +1112,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -966,6 +1127,7 @@ mod tests {
         assert!(components.1.is_empty());
         assert!(names.is_empty());
         assert!(parameters.is_empty());
+        assert!(metadata.is_empty());
     }
 
     #[test]
@@ -973,6 +1135,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["nested", "empty", "another"],
@@ -980,6 +1143,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Empty,
         );
 
@@ -994,6 +1158,7 @@ mod tests {
         assert!(components.1.is_empty());
         assert!(names.is_empty());
         assert!(parameters.is_empty());
+        assert!(metadata.is_empty());
     }
 
     #[test]
@@ -1001,6 +1166,7 @@ mod tests {
         let mut components = (Vec::new(), BTreeMap::new());
         let mut names = BTreeSet::new();
         let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
 
         let ret = match_segment(
             &["fixed", "too-specific"],
@@ -1008,6 +1174,7 @@ mod tests {
             &mut components,
             &mut names,
             &mut parameters,
+            &mut metadata,
             &RouteContent::Redirect(NavigationTarget::InternalTarget(String::from("global"))),
         );
 
@@ -1021,6 +1188,57 @@ mod tests {
         assert!(components.1.is_empty());
         assert!(names.is_empty());
         assert!(parameters.is_empty());
+        assert!(metadata.is_empty());
+    }
+
+    #[test]
+    fn match_segment_metadata() {
+        let mut components = (Vec::new(), BTreeMap::new());
+        let mut names = BTreeSet::new();
+        let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
+
+        let ret = match_segment(
+            &["nested", "second-layer"],
+            &prepare_segment(),
+            &mut components,
+            &mut names,
+            &mut parameters,
+            &mut metadata,
+            &RouteContent::Empty,
+        );
+
+        assert!(ret.is_none());
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0].0, "/nested");
+        assert_eq!(metadata[0].1.get::<Title>().unwrap().0, "nested");
+        assert_eq!(metadata[1].0, "/nested/second-layer");
+        assert_eq!(metadata[1].1.get::<Title>().unwrap().0, "second-layer");
+    }
+
+    #[test]
+    fn match_segment_index_metadata() {
+        let mut components = (Vec::new(), BTreeMap::new());
+        let mut names = BTreeSet::new();
+        let mut parameters = BTreeMap::new();
+        let mut metadata = Vec::new();
+
+        let ret = match_segment(
+            &["nested"],
+            &prepare_segment(),
+            &mut components,
+            &mut names,
+            &mut parameters,
+            &mut metadata,
+            &RouteContent::Empty,
+        );
+
+        assert!(ret.is_none());
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0].0, "/nested");
+        assert_eq!(metadata[0].1.get::<Title>().unwrap().0, "nested");
+        assert_eq!(metadata[1].0, "/nested");
+        assert_eq!(metadata[1].1.get::<Title>().unwrap().0, "nested index");
     }
 
     fn prepare_segment() -> Segment {
@@ -1032,19 +1250,24 @@ mod tests {
             )
             .fixed(
                 "nested",
-                Route::new(TestComponent as Component).name(Nested).nested(
-                    Segment::new()
-                        .index(TestComponent as Component)
-                        .fixed(
-                            "second-layer",
-                            Route::new(TestComponent as Component)
-                                .name(Nested2)
-                                .nested(Segment::new().index(TestComponent as Component)),
-                        )
-                        .fixed("redirect", "redirect-path")
-                        .fixed("empty", Route::new(RouteContent::Empty))
-                        .fallback("fallback"),
-                ),
+                Route::new(TestComponent as Component)
+                    .name(Nested)
+                    .metadata(Title("nested"))
+                    .nested(
+                        Segment::new()
+                            .index(TestComponent as Component)
+                            .index_metadata(Title("nested index"))
+                            .fixed(
+                                "second-layer",
+                                Route::new(TestComponent as Component)
+                                    .name(Nested2)
+                                    .metadata(Title("second-layer"))
+                                    .nested(Segment::new().index(TestComponent as Component)),
+                            )
+                            .fixed("redirect", "redirect-path")
+                            .fixed("empty", Route::new(RouteContent::Empty))
+                            .fallback("fallback"),
+                    ),
             )
             .matching(
                 Regex::new("^m1.*$").unwrap(),
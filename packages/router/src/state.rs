@@ -7,7 +7,7 @@ use std::{
 use dioxus::prelude::*;
 use serde::Deserialize;
 
-use crate::navigation::NavigationTarget;
+use crate::{navigation::NavigationTarget, route_definition::RouteMetadata};
 
 /// The current routing information.
 pub struct RouterState {
@@ -27,6 +27,11 @@ pub struct RouterState {
     /// The components specified by the active routes.
     pub(crate) components: (Vec<Component>, BTreeMap<&'static str, Vec<Component>>),
 
+    /// Whether the router waits for the [`Outlet`]s to be rendered, to update the scroll position.
+    ///
+    /// [`Outlet`]: crate::components::Outlet
+    pub(crate) scroll_pending: bool,
+
     /// The names of the currently active routes.
     pub names: BTreeSet<TypeId>,
 
@@ -39,8 +44,24 @@ pub struct RouterState {
     /// The current query string, if present.
     pub query: Option<String>,
 
+    /// The current fragment (without the leading `#`), if present.
+    pub fragment: Option<String>,
+
+    /// The state stored for the current history entry, if present.
+    ///
+    /// It is set with [`Navigator::set_state`](crate::hooks::Navigator::set_state).
+    pub history_state: Option<String>,
+
     /// The parameters read from the path as specified by the current routes.
     pub parameters: BTreeMap<&'static str, String>,
+
+    /// The metadata of the currently active routes, from the outermost to the innermost route.
+    ///
+    /// Every entry consists of the path of the route (without a trailing `/`) and its metadata.
+    /// Routes without metadata are included as well. An active _index_ route is only included if
+    /// it has [metadata](crate::route_definition::Segment::index_metadata), with the path of the
+    /// route it is nested in, or `/` for the root _index_ route.
+    pub route_metadata: Vec<(String, RouteMetadata)>,
 }
 
 impl RouterState {
@@ -50,11 +71,15 @@ impl RouterState {
             can_go_back: Default::default(),
             can_go_forward: Default::default(),
             components: Default::default(),
+            scroll_pending: Default::default(),
             names: Default::default(),
             path: Default::default(),
             prefix: Default::default(),
             query: Default::default(),
+            fragment: Default::default(),
+            history_state: Default::default(),
             parameters: Default::default(),
+            route_metadata: Default::default(),
         }
     }
 
@@ -69,6 +94,8 @@ impl RouterState {
     ///
     /// Otherwise, the last segment of the current path must match the `target` path.
     ///
+    /// A fragment of the `target` path is ignored.
+    ///
     /// # [`NamedTarget`](crate::navigation::NavigationTarget::NamedTarget)
     /// The `target` name must be in the list of active names.
     ///
@@ -76,7 +103,7 @@ impl RouterState {
     /// `target` is still active, even if the current parameters are more than the `target`
     /// parameters.
     ///
-    /// The query is ignored.
+    /// The query and fragment are ignored.
     ///
     /// # [`ExternalTarget`](crate::navigation::NavigationTarget::ExternalTarget)
     /// Always [`false`].
@@ -84,8 +111,14 @@ impl RouterState {
     pub fn is_active(&self, target: &NavigationTarget, exact: bool) -> bool {
         match target {
             NavigationTarget::InternalTarget(path) => {
+                // ignore fragment
+                let path = match path.split_once('#') {
+                    Some((path, _)) => path,
+                    None => path.as_str(),
+                };
+
                 if exact {
-                    return &self.path == path;
+                    return self.path == path;
                 }
 
                 // absolute path
@@ -125,6 +158,34 @@ impl RouterState {
         }
     }
 
+    /// Get the metadata value of type `T` of the currently active routes.
+    ///
+    /// If multiple active routes have a value of type `T`, the value of the innermost route is
+    /// returned.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus_router::prelude::*;
+    /// struct Title(&'static str);
+    ///
+    /// fn SomeComponent(cx: Scope) -> Element {
+    ///     let route = use_route(&cx).expect("router as ancestor");
+    ///     let title = route.metadata::<Title>().map(|t| t.0).unwrap_or("Home");
+    ///
+    ///     cx.render(rsx! {
+    ///         h1 { "{title}" }
+    ///     })
+    /// }
+    /// ```
+    #[must_use]
+    pub fn metadata<T: 'static>(&self) -> Option<&T> {
+        self.route_metadata
+            .iter()
+            .rev()
+            .find_map(|(_, metadata)| metadata.get())
+    }
+
     /// Get the query parameters as a [`BTreeMap`].
     #[must_use]
     pub fn query_params(&self) -> Option<BTreeMap<String, String>> {
@@ -155,7 +216,10 @@ impl Debug for RouterState {
             .field("path", &self.path)
             .field("prefix", &self.prefix)
             .field("query", &self.query)
+            .field("fragment", &self.fragment)
+            .field("history_state", &self.history_state)
             .field("parameters", &self.parameters)
+            .field("route_metadata", &self.route_metadata)
             .finish_non_exhaustive()
     }
 }
@@ -169,6 +233,11 @@ mod tests {
     struct Nest;
     struct Test;
 
+    #[derive(Debug, PartialEq)]
+    struct Title(&'static str);
+    #[derive(Debug, PartialEq)]
+    struct Label(&'static str);
+
     #[test]
     fn is_active_external() {
         let state = test_state();
@@ -215,6 +284,20 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn is_active_path_fragment() {
+        let state = test_state();
+
+        assert!(state.is_active(
+            &NavigationTarget::InternalTarget(String::from("/test/nest#frag")),
+            true
+        ));
+        assert!(state.is_active(
+            &NavigationTarget::InternalTarget(String::from("nest#frag")),
+            false
+        ));
+    }
+
     #[test]
     fn is_active_path_relative() {
         let state = test_state();
@@ -252,29 +335,55 @@ mod tests {
         let state = test_state();
 
         assert!(state.is_active(
-            &NavigationTarget::NamedTarget(named_tuple(Test), vec![("test", String::from("test"))], None),
+            &NavigationTarget::NamedTarget(
+                named_tuple(Test),
+                vec![("test", String::from("test"))],
+                None
+            ),
             true
         ));
         assert!(!state.is_active(
-            &NavigationTarget::NamedTarget(named_tuple(Invalid), vec![("test", String::from("test"))], None),
+            &NavigationTarget::NamedTarget(
+                named_tuple(Invalid),
+                vec![("test", String::from("test"))],
+                None
+            ),
             true
         ));
         assert!(!state.is_active(
-            &NavigationTarget::NamedTarget(named_tuple(Test), vec![("invalid", String::from("test"))], None),
+            &NavigationTarget::NamedTarget(
+                named_tuple(Test),
+                vec![("invalid", String::from("test"))],
+                None
+            ),
             true
         ));
         assert!(!state.is_active(
-            &NavigationTarget::NamedTarget(named_tuple(Test), vec![("test", String::from("invalid"))], None),
+            &NavigationTarget::NamedTarget(
+                named_tuple(Test),
+                vec![("test", String::from("invalid"))],
+                None
+            ),
             true
         ));
     }
 
+    #[test]
+    fn metadata() {
+        let state = test_state();
+
+        assert_eq!(state.metadata::<Title>(), Some(&Title("inner")));
+        assert_eq!(state.metadata::<Label>(), Some(&Label("outer")));
+        assert_eq!(state.metadata::<Invalid>().map(|_| ()), None);
+    }
+
     fn test_state() -> RouterState {
         RouterState {
             can_external: false,
             can_go_back: false,
             can_go_forward: false,
             components: (vec![], BTreeMap::new()),
+            scroll_pending: false,
             names: {
                 let mut names = BTreeSet::new();
                 names.insert(TypeId::of::<Test>());
@@ -284,11 +393,26 @@ mod tests {
             path: String::from("/test/nest"),
             prefix: String::from(""),
             query: None,
+            fragment: None,
+            history_state: None,
             parameters: {
                 let mut parameters = BTreeMap::new();
                 parameters.insert("test", String::from("test"));
                 parameters
             },
+            route_metadata: vec![
+                (String::from("/test"), {
+                    let mut metadata = RouteMetadata::new();
+                    metadata.insert(Title("outer"));
+                    metadata.insert(Label("outer"));
+                    metadata
+                }),
+                (String::from("/test/nest"), {
+                    let mut metadata = RouteMetadata::new();
+                    metadata.insert(Title("inner"));
+                    metadata
+                }),
+            ],
         }
     }
 }
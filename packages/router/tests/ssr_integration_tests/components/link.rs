@@ -240,6 +240,38 @@ fn with_external_target_and_rel() {
     }
 }
 
+#[test]
+fn with_fragment() {
+    assert_eq!(
+        format!(
+            "{link1}{link2}",
+            link1 = r#"<a href="/test/#frag" dioxus-prevent-default="onclick" class=" active" id="" rel="" target="">Test Link 1</a>"#,
+            link2 = r#"<a href="/test/#frag" dioxus-prevent-default="onclick" class="" id="" rel="" target="">Test Link 2</a>"#,
+        ),
+        render(App)
+    );
+
+    #[allow(non_snake_case)]
+    fn App(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                routes: test_routes(&cx),
+                initial_path: "/test/",
+                Link {
+                    target: "/test/#frag",
+                    active_class: "active",
+                    exact: true,
+                    "Test Link 1"
+                }
+                Link {
+                    target: NavigationTarget::from((TestName, [])).with_fragment("frag"),
+                    "Test Link 2"
+                }
+            }
+        })
+    }
+}
+
 #[cfg(debug_assertions)]
 #[test]
 #[should_panic = "`Link` can only be used as a descendent of a `Router`"]
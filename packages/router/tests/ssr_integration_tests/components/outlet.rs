@@ -113,6 +113,28 @@ fn with_name() {
     }
 }
 
+#[test]
+fn with_transition() {
+    assert_eq!(
+        r#"<div class="fade"><div style="display:contents;"><p>0: test</p><p>1: index</p></div></div>"#,
+        render(App)
+    );
+
+    #[allow(non_snake_case)]
+    fn App(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                routes: test_routes(&cx),
+                initial_path: "/test",
+
+                Outlet {
+                    transition: "fade"
+                }
+            }
+        })
+    }
+}
+
 #[cfg(debug_assertions)]
 #[test]
 #[should_panic = "`Outlet` can only be used as a descendent of a `Router`"]
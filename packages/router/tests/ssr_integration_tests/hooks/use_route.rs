@@ -19,6 +19,32 @@ fn with_router() {
     }
 }
 
+#[test]
+fn with_fragment() {
+    assert_eq!("<p>fragment: section</p>", render(App));
+
+    #[allow(non_snake_case)]
+    fn App(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                routes: test_routes(&cx),
+                initial_path: "/test/#section",
+                ComponentWithFragment { }
+            }
+        })
+    }
+
+    #[allow(non_snake_case)]
+    fn ComponentWithFragment(cx: Scope) -> Element {
+        let route = use_route(&cx).expect("router as ancestor");
+        let fragment = route.fragment.clone().unwrap_or_default();
+
+        cx.render(rsx! {
+            p { "fragment: {fragment}" }
+        })
+    }
+}
+
 #[cfg(debug_assertions)]
 #[test]
 #[should_panic = "`use_route` can only be used in descendants of a `Router`"]
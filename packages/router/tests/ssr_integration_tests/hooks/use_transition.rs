@@ -0,0 +1,40 @@
+use dioxus::prelude::*;
+use dioxus_router::prelude::*;
+
+use crate::render;
+
+#[test]
+fn with_transition() {
+    assert_eq!(
+        r#"<div class="fade"><div style="display:contents;"><p>phase: Some(Entered)</p></div></div>"#,
+        render(App)
+    );
+
+    #[allow(non_snake_case)]
+    fn App(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                routes: use_segment(&cx, || Segment::new().index(ComponentWithHook as Component))
+                    .clone(),
+                init_only: true,
+                Outlet {
+                    transition: "fade"
+                }
+            }
+        })
+    }
+}
+
+#[test]
+fn without_transition() {
+    assert_eq!("<p>phase: None</p>", render(ComponentWithHook));
+}
+
+#[allow(non_snake_case)]
+fn ComponentWithHook(cx: Scope) -> Element {
+    let phase = use_transition(&cx).map(|t| t.phase());
+
+    cx.render(rsx! {
+        p { "phase: {phase:?}" }
+    })
+}
@@ -124,4 +124,5 @@ mod usage {
 mod hooks {
     mod use_navigate;
     mod use_route;
+    mod use_transition;
 }
@@ -1,8 +1,9 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use dioxus::prelude::*;
-use dioxus_tui::{Config, TuiContext};
+use dioxus_tui::{Config, Headless, TuiContext};
 
-criterion_group!(mbenches, tui_update);
+criterion_group!(mbenches, tui_update, tui_update_one_cell);
 criterion_main!(mbenches);
 
 /// This benchmarks the cache performance of the TUI for small edits by changing one box at a time.
@@ -218,3 +219,70 @@ fn app24(cx: Scope) -> Element {
         }
     })
 }
+
+/// This benchmarks redrawing a large tree after a single cell changed. Only the area of the
+/// changed cell is repainted, compared to repainting the whole tree.
+fn tui_update_one_cell(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Update one cell");
+
+    for repaint_all in [false, true] {
+        let name = if repaint_all { "full" } else { "incremental" };
+        group.bench_function(name, |b| {
+            let mut headless = Headless::new(one_cell_app, 128, 64);
+            // focus the counter
+            headless.inject_event(key(KeyCode::Tab));
+            headless.step();
+            b.iter(|| {
+                headless.inject_event(key(KeyCode::Char('a')));
+                if repaint_all {
+                    headless.invalidate();
+                }
+                headless.step();
+            })
+        });
+    }
+}
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn one_cell_app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div{
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            Counter{}
+            (0..63).map(|y| rsx! {
+                div{
+                    key: "{y}",
+                    flex_direction: "row",
+                    (0..32).map(|x| rsx! {
+                        div{
+                            key: "{x}",
+                            width: "4px",
+                            background_color: "hsl({x * 10}, 100%, 50%)",
+                            "{x:03}"
+                        }
+                    })
+                }
+            })
+        }
+    })
+}
+
+#[allow(non_snake_case)]
+fn Counter(cx: Scope) -> Element {
+    let count = use_state(&cx, || 0);
+    cx.render(rsx! {
+        div{
+            tabindex: "0",
+            onkeydown: move |_| count.modify(|c| (c + 1) % 10),
+            "{count}"
+        }
+    })
+}
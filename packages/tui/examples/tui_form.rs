@@ -0,0 +1,72 @@
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let name = use_state(&cx, String::new);
+    let submitted = use_state(&cx, String::new);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            justify_content: "center",
+            align_items: "center",
+
+            "Press tab to move between the controls"
+            form {
+                flex_direction: "column",
+                border_style: "solid",
+                border_width: "1px",
+                padding: "1px",
+
+                div {
+                    "Name: "
+                    input {
+                        name: "name",
+                        placeholder: "your name",
+                        value: "{name}",
+                        oninput: move |evt| name.set(evt.value.clone()),
+                    }
+                }
+                div {
+                    "Password: "
+                    input { r#type: "password", name: "password" }
+                }
+                div {
+                    "Age: "
+                    input { r#type: "number", name: "age", size: "5" }
+                }
+                div {
+                    input { r#type: "checkbox", name: "subscribe" }
+                    " Subscribe"
+                }
+                div {
+                    input { r#type: "radio", name: "size", value: "small", checked: "true" }
+                    " Small "
+                    input { r#type: "radio", name: "size", value: "large" }
+                    " Large"
+                }
+                div {
+                    "Color: "
+                    select {
+                        name: "color",
+                        option { value: "red", "Red" }
+                        option { value: "green", "Green" }
+                        option { value: "blue", "Blue" }
+                    }
+                }
+                textarea { name: "comment", cols: "30", rows: "3" }
+                input {
+                    r#type: "submit",
+                    value: "Submit",
+                    onclick: move |_| submitted.set(format!("Hello {}!", name)),
+                }
+            }
+            "{submitted}"
+        }
+    })
+}
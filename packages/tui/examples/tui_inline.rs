@@ -0,0 +1,43 @@
+use dioxus::prelude::*;
+use dioxus_tui::{Config, InlineHeight, TuiContext};
+
+fn main() {
+    println!("Downloading files...");
+    dioxus_tui::launch_cfg(app, Config::new().with_inline(InlineHeight::Content));
+    println!("Done!");
+}
+
+fn app(cx: Scope) -> Element {
+    let progress = use_state(&cx, || 0);
+    let tui_ctx: TuiContext = cx.consume_context().unwrap();
+
+    use_future(&cx, (), move |_| {
+        let progress = progress.to_owned();
+        let tui_ctx = tui_ctx.clone();
+        async move {
+            for file in 0..=20 {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                // finished files scroll up with the rest of the output
+                tui_ctx.print_above(format!("downloaded file_{file}.txt"));
+                progress.set(file * 5);
+            }
+            tui_ctx.quit();
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            flex_direction: "column",
+            border_style: "solid",
+            border_width: "1px",
+
+            "Progress: {progress}%"
+            div {
+                width: "{progress}%",
+                height: "1px",
+                background_color: "green",
+            }
+        }
+    })
+}
@@ -0,0 +1,96 @@
+use dioxus::prelude::*;
+use dioxus_tui::{use_keybinding, KeyBinding, TuiContext};
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let tui_ctx: TuiContext = cx.consume_context().unwrap();
+    let count = use_state(&cx, || 0);
+    let help = use_state(&cx, || false);
+
+    {
+        let help = help.clone();
+        use_keybinding(&cx, KeyBinding::new("?", "Toggle this help"), move || {
+            help.modify(|h| !h)
+        });
+    }
+    {
+        let tui_ctx = tui_ctx.clone();
+        use_keybinding(&cx, KeyBinding::new("q", "Quit"), move || tui_ctx.quit());
+    }
+    {
+        let count = count.clone();
+        use_keybinding(
+            &cx,
+            KeyBinding::new("g g", "Reset the counter"),
+            move || count.set(0),
+        );
+    }
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            justify_content: "center",
+            align_items: "center",
+
+            "Count: {count}"
+            div {
+                tabindex: "0",
+                border_style: "solid",
+                border_width: "1px",
+                padding_left: "1px",
+                padding_right: "1px",
+                Counter { count: count.clone() }
+                "Tab here and press + or -"
+            }
+            "Press ? for help"
+
+            help.then(|| rsx! {
+                div {
+                    position: "absolute",
+                    top: "1px",
+                    right: "1px",
+                    z_index: "1",
+                    flex_direction: "column",
+                    border_style: "solid",
+                    border_width: "1px",
+                    background_color: "black",
+                    // the list changes with the focus
+                    tui_ctx.keybindings().into_iter().map(|binding| rsx! {
+                        div {
+                            key: "{binding.keys()}",
+                            "{binding.keys()}: {binding.description()}"
+                        }
+                    })
+                }
+            })
+        }
+    })
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Counter(cx: Scope, count: UseState<i32>) -> Element {
+    let scope = cx.scope_id();
+    {
+        let count = count.clone();
+        use_keybinding(
+            &cx,
+            KeyBinding::new("+", "Add one").in_component(scope),
+            move || count.modify(|c| c + 1),
+        );
+    }
+    {
+        let count = count.clone();
+        use_keybinding(
+            &cx,
+            KeyBinding::new("-", "Subtract one").in_component(scope),
+            move || count.modify(|c| c - 1),
+        );
+    }
+    None
+}
@@ -0,0 +1,53 @@
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let open = use_state(&cx, || false);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            background_color: "hsl(200, 50%, 30%)",
+
+            div {
+                onclick: move |_| open.set(true),
+                "Click anywhere to open the modal"
+            }
+            (0..20).map(|i| rsx! {
+                div { key: "{i}", "Line {i} of the page beneath the modal" }
+            })
+
+            open.then(|| rsx! {
+                // covers the page, so clicks outside of the dialog close it
+                div {
+                    position: "absolute",
+                    width: "100%",
+                    height: "100%",
+                    z_index: "1",
+                    justify_content: "center",
+                    align_items: "center",
+                    background_color: "rgba(0, 0, 0, 0.5)",
+                    onclick: move |_| open.set(false),
+
+                    div {
+                        width: "30px",
+                        height: "5px",
+                        flex_direction: "column",
+                        justify_content: "center",
+                        align_items: "center",
+                        border_style: "solid",
+                        border_width: "1px",
+                        background_color: "black",
+                        "A modal dialog"
+                        "Click to close it"
+                    }
+                }
+            })
+        }
+    })
+}
@@ -0,0 +1,40 @@
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let scrolled = use_state(&cx, || 0);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            justify_content: "center",
+            align_items: "center",
+
+            "Scroll with the mouse wheel, or press tab and use the arrow keys ({scrolled} scroll events)"
+            div {
+                width: "50%",
+                height: "50%",
+                flex_direction: "column",
+                overflow: "auto",
+                border_style: "solid",
+                border_width: "1px",
+                onscroll: move |_| scrolled.modify(|s| s + 1),
+
+                (0..100).map(|i| rsx! {
+                    div {
+                        key: "{i}",
+                        width: "150%",
+                        height: "1px",
+                        background_color: "hsl({i * 10}, 70%, 50%)",
+                        "line {i}"
+                    }
+                })
+            }
+        }
+    })
+}
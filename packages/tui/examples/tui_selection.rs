@@ -0,0 +1,47 @@
+use dioxus::prelude::*;
+use dioxus_tui::{Config, TuiContext};
+
+fn main() {
+    dioxus_tui::launch_cfg(app, Config::new().with_selection());
+}
+
+fn app(cx: Scope) -> Element {
+    let tui_ctx: TuiContext = cx.consume_context().unwrap();
+    let copied = use_state(&cx, String::new);
+
+    // show what was copied instead of putting it into the terminal's clipboard
+    cx.use_hook(|| {
+        let copied = copied.clone();
+        tui_ctx.set_clipboard(move |text: &str| copied.set(text.to_string()));
+    });
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            padding: "1px",
+
+            div {
+                user_select: "none",
+                font_weight: "bold",
+                "Drag the mouse over the text to copy it"
+            }
+            div {
+                margin_top: "1px",
+                "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor"
+            }
+            div {
+                "incididunt ut labore et dolore magna aliqua."
+            }
+            div {
+                margin_top: "1px",
+                border_style: "solid",
+                border_width: "1px",
+                user_select: "none",
+                "Copied: "
+                span { user_select: "text", "{copied}" }
+            }
+        }
+    })
+}
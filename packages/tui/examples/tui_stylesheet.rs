@@ -0,0 +1,74 @@
+use dioxus::prelude::*;
+use dioxus_html::input_data::keyboard_types::Key;
+use dioxus_tui::TuiContext;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+const STYLE: &str = "
+.menu {
+    flex-direction: column;
+    border-style: solid;
+    border-width: 1px;
+    padding: 1px;
+}
+
+.menu > .item {
+    padding-left: 1px;
+    padding-right: 1px;
+}
+
+.item:hover {
+    background-color: darkblue;
+}
+
+.item:focus, .item.selected:focus {
+    background-color: blue;
+    font-weight: bold;
+}
+
+.selected {
+    color: green;
+}
+
+#title {
+    text-decoration: underline;
+}
+";
+
+fn app(cx: Scope) -> Element {
+    let tui_ctx: TuiContext = cx.consume_context().unwrap();
+    cx.use_hook(|| tui_ctx.add_stylesheet(STYLE));
+    let selected = use_state(&cx, || 0);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            justify_content: "center",
+            align_items: "center",
+
+            div {
+                class: "menu",
+                div { id: "title", "Press tab to move and enter to select" }
+                ["Apples", "Pears", "Plums"].iter().enumerate().map(|(i, fruit)| {
+                    let class = if *selected.get() == i { "item selected" } else { "item" };
+                    rsx! {
+                        div {
+                            key: "{fruit}",
+                            class: "{class}",
+                            tabindex: "0",
+                            onkeydown: move |evt| {
+                                if evt.key() == Key::Enter {
+                                    selected.set(i);
+                                }
+                            },
+                            "{fruit}"
+                        }
+                    }
+                })
+            }
+        }
+    })
+}
@@ -6,7 +6,12 @@ pub struct Config {
     /// To handle quiting on your own, use the [crate::TuiContext] root context.
     pub(crate) ctrl_c_quit: bool,
     /// Controls if the terminal should dislay anything, usefull for testing.
+    /// To test what is displayed, use [crate::Headless].
     pub(crate) headless: bool,
+    /// Controls if the app is drawn below the cursor instead of on the alternate screen.
+    pub(crate) inline: Option<InlineHeight>,
+    /// Controls if text can be selected with the mouse and copied.
+    pub(crate) selection: bool,
 }
 
 impl Config {
@@ -34,6 +39,29 @@ impl Config {
             ..self
         }
     }
+
+    /// Draw the app into lines below the cursor instead of taking over the whole terminal. Lines
+    /// can be printed above the app with [crate::TuiContext::print_above], and the last frame
+    /// stays in the terminal when the app quits.
+    ///
+    /// Mouse events are not captured in inline mode, so the terminal can still be scrolled.
+    pub fn with_inline(self, height: InlineHeight) -> Self {
+        Self {
+            inline: Some(height),
+            ..self
+        }
+    }
+
+    /// Let the user select text by dragging the mouse over it, because mouse capture keeps the
+    /// terminal from doing it. The selection is copied when the mouse button is released, with
+    /// the clipboard set by [crate::TuiContext::set_clipboard]. Text in elements with
+    /// `user-select: none` is left out.
+    pub fn with_selection(self) -> Self {
+        Self {
+            selection: true,
+            ..self
+        }
+    }
 }
 
 impl Default for Config {
@@ -42,6 +70,8 @@ impl Default for Config {
             rendering_mode: Default::default(),
             ctrl_c_quit: true,
             headless: false,
+            inline: None,
+            selection: false,
         }
     }
 }
@@ -61,3 +91,12 @@ impl Default for RenderingMode {
         RenderingMode::Rgb
     }
 }
+
+/// How many lines an app drawn inline takes up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InlineHeight {
+    /// A fixed number of lines.
+    Fixed(u16),
+    /// As many lines as the content needs, up to the height of the terminal.
+    Content,
+}
@@ -64,8 +64,9 @@ pub(crate) struct Focus {
 
 impl NodeDepState<()> for Focus {
     type Ctx = ();
-    const NODE_MASK: NodeMask =
-        NodeMask::new_with_attrs(AttributeMask::Static(FOCUS_ATTRIBUTES)).with_listeners();
+    const NODE_MASK: NodeMask = NodeMask::new_with_attrs(AttributeMask::Static(FOCUS_ATTRIBUTES))
+        .with_listeners()
+        .with_tag();
 
     fn reduce(&mut self, node: NodeView<'_>, _sibling: (), _: &Self::Ctx) -> bool {
         let new = Focus {
@@ -91,6 +92,19 @@ impl NodeDepState<()> for Focus {
                 .any(|l| FOCUS_EVENTS.binary_search(&l.event).is_ok())
             {
                 FocusLevel::Focusable
+            } else if is_control(&node)
+                || node.attributes().any(|a| {
+                    matches!(a.name, "overflow" | "overflow-x" | "overflow-y")
+                        && a.value
+                            .as_text()
+                            .into_iter()
+                            .flat_map(str::split_whitespace)
+                            .any(|v| matches!(v, "scroll" | "auto"))
+                })
+            {
+                // form controls can be focused like in a browser, and scroll containers can be
+                // focused to scroll them with the keyboard
+                FocusLevel::Focusable
             } else {
                 FocusLevel::Unfocusable
             },
@@ -104,8 +118,33 @@ impl NodeDepState<()> for Focus {
     }
 }
 
+/// Check whether the node is a form control that can be focused like in a browser.
+fn is_control(node: &NodeView) -> bool {
+    let disabled = node.attributes().any(|a| {
+        a.name == "disabled"
+            && a.value
+                .as_bool()
+                .unwrap_or_else(|| a.value.as_text() != Some("false"))
+    });
+    let hidden = node
+        .attributes()
+        .any(|a| a.name == "type" && a.value.as_text().map(str::trim) == Some("hidden"));
+    match node.tag() {
+        Some("input") => !disabled && !hidden,
+        Some("textarea" | "select" | "button") => !disabled,
+        _ => false,
+    }
+}
+
 const FOCUS_EVENTS: &[&str] = &sorted_str_slice!(["keydown", "keypress", "keyup"]);
-const FOCUS_ATTRIBUTES: &[&str] = &sorted_str_slice!(["tabindex"]);
+const FOCUS_ATTRIBUTES: &[&str] = &sorted_str_slice!([
+    "disabled",
+    "overflow",
+    "overflow-x",
+    "overflow-y",
+    "tabindex",
+    "type"
+]);
 
 #[derive(Default)]
 pub(crate) struct FocusState {
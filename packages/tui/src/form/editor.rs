@@ -0,0 +1,182 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The text of an `input` or `textarea` with a cursor and selection.
+///
+/// Positions are byte offsets into the text that always lie on grapheme boundaries.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct Editor {
+    pub text: String,
+    pub cursor: usize,
+    /// The other end of the selection, if any text is selected.
+    pub anchor: Option<usize>,
+}
+
+impl Editor {
+    /// Replace the text, keeping the cursor if the text didn't change.
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_string();
+            self.cursor = self.text.len();
+            self.anchor = None;
+        }
+    }
+
+    /// Get the selected range, if it isn't empty.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        match anchor.cmp(&self.cursor) {
+            std::cmp::Ordering::Less => Some((anchor, self.cursor)),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some((self.cursor, anchor)),
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.text.len();
+    }
+
+    /// Remove the selected text. Returns `true` if anything was selected.
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some((start, end)) => {
+                self.text.replace_range(start..end, "");
+                self.cursor = start;
+                self.anchor = None;
+                true
+            }
+            None => {
+                self.anchor = None;
+                false
+            }
+        }
+    }
+
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// Delete the selection or the grapheme before the cursor. Returns `true` if the text changed.
+    pub fn backspace(&mut self, word: bool) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let start = if word {
+            self.word_start(self.cursor)
+        } else {
+            self.prev_boundary(self.cursor)
+        };
+        self.text.replace_range(start..self.cursor, "");
+        let changed = start != self.cursor;
+        self.cursor = start;
+        changed
+    }
+
+    /// Delete the selection or the grapheme after the cursor. Returns `true` if the text changed.
+    pub fn delete(&mut self, word: bool) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let end = if word {
+            self.word_end(self.cursor)
+        } else {
+            self.next_boundary(self.cursor)
+        };
+        self.text.replace_range(self.cursor..end, "");
+        end != self.cursor
+    }
+
+    /// Move the cursor to `position`, extending the selection if `select` is set.
+    pub fn move_to(&mut self, position: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = position;
+    }
+
+    pub fn left(&mut self, word: bool, select: bool) {
+        let position = match self.selection() {
+            Some((start, _)) if !select => start,
+            _ if word => self.word_start(self.cursor),
+            _ => self.prev_boundary(self.cursor),
+        };
+        self.move_to(position, select);
+    }
+
+    pub fn right(&mut self, word: bool, select: bool) {
+        let position = match self.selection() {
+            Some((_, end)) if !select => end,
+            _ if word => self.word_end(self.cursor),
+            _ => self.next_boundary(self.cursor),
+        };
+        self.move_to(position, select);
+    }
+
+    /// Get the start of the line the cursor is on.
+    pub fn line_start(&self) -> usize {
+        self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    /// Get the end of the line the cursor is on.
+    pub fn line_end(&self) -> usize {
+        self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i)
+    }
+
+    /// Move the cursor to the line above (`-1`) or below (`1`), keeping the column if possible.
+    /// Returns `false` if there is no line in that direction.
+    pub fn vertical(&mut self, direction: isize, select: bool) -> bool {
+        let start = self.line_start();
+        let column = self.text[start..self.cursor].graphemes(true).count();
+        let target_start = if direction < 0 {
+            if start == 0 {
+                return false;
+            }
+            self.text[..start - 1].rfind('\n').map_or(0, |i| i + 1)
+        } else {
+            let end = self.line_end();
+            if end == self.text.len() {
+                return false;
+            }
+            end + 1
+        };
+        let line = &self.text[target_start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let offset: usize = line.graphemes(true).take(column).map(str::len).sum();
+        self.move_to(target_start + offset, select);
+        true
+    }
+
+    fn prev_boundary(&self, position: usize) -> usize {
+        self.text[..position]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self, position: usize) -> usize {
+        self.text[position..]
+            .graphemes(true)
+            .next()
+            .map_or(position, |g| position + g.len())
+    }
+
+    fn word_start(&self, position: usize) -> usize {
+        self.text[..position]
+            .unicode_word_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn word_end(&self, position: usize) -> usize {
+        self.text[position..]
+            .unicode_word_indices()
+            .next()
+            .map_or(self.text.len(), |(i, w)| position + i + w.len())
+    }
+}
@@ -0,0 +1,603 @@
+//! Native form controls: `input`, `textarea`, `select` and `button`.
+//!
+//! The attributes of a control are read into [`FormControl`], and what the user changed is kept
+//! in [`ControlState`]. The state is reset to the attributes whenever they change, so controlled
+//! inputs (where `oninput` sets the `value` attribute) and uncontrolled inputs both work like they
+//! do in a browser.
+
+mod editor;
+mod render;
+
+use std::{collections::HashMap, sync::Arc};
+
+use dioxus_core::{ElementId, EventPriority, UserEvent};
+use dioxus_html::{
+    event_bubbles,
+    geometry::{ClientPoint, Coordinates, ElementPoint, PagePoint, ScreenPoint},
+    input_data::{keyboard_types::Key, keyboard_types::Modifiers, MouseButtonSet},
+    on::{FormData, KeyboardData, MouseData},
+};
+use dioxus_native_core::{
+    node_ref::{AttributeMask, NodeMask, NodeView},
+    real_dom::NodeType,
+    state::NodeDepState,
+    traversable::Traversable,
+};
+use dioxus_native_core_macro::sorted_str_slice;
+use fxhash::FxHashSet;
+use taffy::prelude::{Dimension, Rect, Style};
+
+use crate::{text, Dom, Node};
+pub(crate) use editor::Editor;
+pub(crate) use render::ControlWidget;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum ControlKind {
+    Text,
+    Password,
+    Number,
+    TextArea,
+    Checkbox,
+    Radio,
+    Select,
+    SelectOption,
+    /// A `button` element. Its content is rendered like any other element.
+    Button,
+    /// An `input` with the type `button`, `submit` or `reset`, labeled with its value.
+    InputButton,
+}
+
+impl ControlKind {
+    fn from_tag(tag: &str, input_type: Option<&str>) -> Option<Self> {
+        Some(match tag {
+            "input" => match input_type.unwrap_or("text").trim() {
+                "password" => ControlKind::Password,
+                "number" => ControlKind::Number,
+                "checkbox" => ControlKind::Checkbox,
+                "radio" => ControlKind::Radio,
+                "button" | "submit" | "reset" => ControlKind::InputButton,
+                "hidden" => return None,
+                _ => ControlKind::Text,
+            },
+            "textarea" => ControlKind::TextArea,
+            "select" => ControlKind::Select,
+            "option" => ControlKind::SelectOption,
+            "button" => ControlKind::Button,
+            _ => return None,
+        })
+    }
+
+    /// Check whether the control edits text.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            ControlKind::Text | ControlKind::Password | ControlKind::Number | ControlKind::TextArea
+        )
+    }
+
+    /// Check whether the children of the control are hidden and replaced by the control.
+    pub fn hides_children(&self) -> bool {
+        matches!(self, ControlKind::TextArea | ControlKind::Select)
+    }
+}
+
+/// The attributes of a form control.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct FormControl {
+    pub kind: Option<ControlKind>,
+    pub value: Option<String>,
+    pub checked: bool,
+    pub selected: bool,
+    pub disabled: bool,
+    pub name: Option<String>,
+    pub placeholder: Option<String>,
+    pub step: Option<f64>,
+}
+
+impl NodeDepState<()> for FormControl {
+    type Ctx = ();
+    const NODE_MASK: NodeMask =
+        NodeMask::new_with_attrs(AttributeMask::Static(CONTROL_ATTRS)).with_tag();
+
+    fn reduce(&mut self, node: NodeView<'_>, _sibling: (), _: &Self::Ctx) -> bool {
+        let text = |name| {
+            node.attributes()
+                .find(|a| a.name == name)
+                .and_then(|a| a.value.as_text())
+        };
+        let flag = |name| {
+            node.attributes()
+                .find(|a| a.name == name)
+                .into_iter()
+                .any(|a| {
+                    a.value
+                        .as_bool()
+                        .unwrap_or_else(|| a.value.as_text() != Some("false"))
+                })
+        };
+
+        let new = match node
+            .tag()
+            .and_then(|tag| ControlKind::from_tag(tag, text("type")))
+        {
+            Some(kind) => FormControl {
+                kind: Some(kind),
+                value: text("value").map(str::to_string),
+                checked: flag("checked"),
+                selected: flag("selected"),
+                disabled: flag("disabled"),
+                name: text("name").map(str::to_string),
+                placeholder: text("placeholder").map(str::to_string),
+                step: text("step").and_then(|s| s.trim().parse().ok()),
+            },
+            None => FormControl::default(),
+        };
+        if *self != new {
+            *self = new;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+const CONTROL_ATTRS: &[&str] = &sorted_str_slice!([
+    "checked",
+    "disabled",
+    "name",
+    "placeholder",
+    "selected",
+    "step",
+    "type",
+    "value"
+]);
+
+/// What the user changed in a form control.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct ControlState {
+    pub editor: Editor,
+    pub checked: bool,
+    /// The index of the selected option of a `select`.
+    pub selected: usize,
+    /// The value when the control was focused, to send a `change` event when it loses focus.
+    value_at_focus: Option<String>,
+    /// The attributes the state was last reset to.
+    synced: Option<FormControl>,
+}
+
+/// Reset the state of the controls in `changed` whose attributes changed.
+pub(crate) fn sync(dom: &mut Dom, changed: &FxHashSet<ElementId>) {
+    let mut selects = Vec::new();
+    for &id in changed {
+        let node = match dom.get_mut(id) {
+            Some(node) => node,
+            None => continue,
+        };
+        let control = node.state.control.clone();
+        let kind = match control.kind {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let state = &mut node.state.control_state;
+        let synced = state.synced.take().unwrap_or_default();
+
+        if kind.is_text() && (synced.value != control.value || synced.kind != control.kind) {
+            state
+                .editor
+                .set_text(control.value.as_deref().unwrap_or_default());
+        }
+        if synced.checked != control.checked || synced.kind != control.kind {
+            state.checked = control.checked;
+        }
+        match kind {
+            ControlKind::Select => selects.push(id),
+            ControlKind::SelectOption => selects.extend(node.parent),
+            _ => {}
+        }
+        node.state.control_state.synced = Some(control);
+    }
+
+    for id in selects {
+        if dom.get(id).map(|n| n.state.control.kind) != Some(Some(ControlKind::Select)) {
+            continue;
+        }
+        let options = options(dom, id);
+        let declared = dom[id].state.control.value.clone();
+        let selected = declared
+            .and_then(|v| options.iter().position(|o| option_value(dom, *o) == v))
+            .or_else(|| options.iter().position(|o| dom[*o].state.control.selected));
+        let state = &mut dom[id].state.control_state;
+        if let Some(selected) = selected {
+            state.selected = selected;
+        }
+        state.selected = state.selected.min(options.len().saturating_sub(1));
+    }
+}
+
+fn children(node: &Node) -> &[ElementId] {
+    match &node.node_type {
+        NodeType::Element { children, .. } => children,
+        _ => &[],
+    }
+}
+
+/// Get the `option` elements of a `select`.
+fn options(dom: &Dom, select: ElementId) -> Vec<ElementId> {
+    children(&dom[select])
+        .iter()
+        .copied()
+        .filter(|c| dom[*c].state.control.kind == Some(ControlKind::SelectOption))
+        .collect()
+}
+
+/// Get the text inside of an element.
+fn label(dom: &Dom, id: ElementId) -> String {
+    children(&dom[id])
+        .iter()
+        .filter_map(|c| match &dom[*c].node_type {
+            NodeType::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn option_value(dom: &Dom, option: ElementId) -> String {
+    dom[option]
+        .state
+        .control
+        .value
+        .clone()
+        .unwrap_or_else(|| label(dom, option))
+}
+
+/// Get the label of the selected option of a `select`.
+pub(crate) fn selected_label(dom: &Dom, select: ElementId) -> String {
+    let options = options(dom, select);
+    options
+        .get(dom[select].state.control_state.selected)
+        .map(|o| label(dom, *o))
+        .unwrap_or_default()
+}
+
+/// Get the value of a control like the web renderer reports it.
+fn value(dom: &Dom, id: ElementId) -> String {
+    let node = &dom[id];
+    let state = &node.state.control_state;
+    match node.state.control.kind {
+        Some(ControlKind::Checkbox) => state.checked.to_string(),
+        Some(ControlKind::Radio) => node
+            .state
+            .control
+            .value
+            .clone()
+            .unwrap_or_else(|| "on".into()),
+        Some(ControlKind::Select) => options(dom, id)
+            .get(state.selected)
+            .map(|o| option_value(dom, *o))
+            .unwrap_or_default(),
+        Some(kind) if kind.is_text() => state.editor.text.clone(),
+        _ => node.state.control.value.clone().unwrap_or_default(),
+    }
+}
+
+/// Get the element `id` is in that has the tag `tag`.
+fn ancestor(dom: &Dom, id: ElementId, tag: &str) -> Option<ElementId> {
+    let mut current = dom[id].parent;
+    while let Some(id) = current {
+        if let NodeType::Element { tag: t, .. } = &dom[id].node_type {
+            if t == tag {
+                return Some(id);
+            }
+        }
+        current = dom[id].parent;
+    }
+    None
+}
+
+/// Call `f` with every node inside of `id`.
+fn descendants(dom: &Dom, id: ElementId, f: &mut impl FnMut(&Node)) {
+    for c in children(&dom[id]) {
+        f(&dom[*c]);
+        descendants(dom, *c, f);
+    }
+}
+
+/// Get the values of the named controls in the form `id` is in.
+fn form_values(dom: &Dom, id: ElementId) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    if let Some(form) = ancestor(dom, id, "form") {
+        descendants(dom, form, &mut |node| {
+            let control = &node.state.control;
+            if let (Some(kind), Some(name)) = (control.kind, &control.name) {
+                let skip = kind == ControlKind::SelectOption
+                    || (kind == ControlKind::Radio && !node.state.control_state.checked);
+                if !skip {
+                    values.insert(name.clone(), value(dom, node.id));
+                }
+            }
+        });
+    }
+    values
+}
+
+fn form_events(dom: &Dom, id: ElementId, names: &[&'static str], events: &mut Vec<UserEvent>) {
+    let data = Arc::new(FormData {
+        value: value(dom, id),
+        values: form_values(dom, id),
+    });
+    for name in names {
+        events.push(UserEvent {
+            scope_id: None,
+            priority: EventPriority::Medium,
+            name,
+            element: Some(id),
+            data: data.clone(),
+            bubbles: event_bubbles(name),
+        });
+    }
+}
+
+fn click_event(id: ElementId, events: &mut Vec<UserEvent>) {
+    let data = MouseData::new(
+        Coordinates::new(
+            ScreenPoint::zero(),
+            ClientPoint::zero(),
+            ElementPoint::zero(),
+            PagePoint::zero(),
+        ),
+        None,
+        MouseButtonSet::empty(),
+        Modifiers::empty(),
+    );
+    events.push(UserEvent {
+        scope_id: None,
+        priority: EventPriority::Medium,
+        name: "click",
+        element: Some(id),
+        data: Arc::new(data),
+        bubbles: event_bubbles("click"),
+    });
+}
+
+fn toggle(dom: &mut Dom, id: ElementId, events: &mut Vec<UserEvent>) {
+    match dom[id].state.control.kind {
+        Some(ControlKind::Checkbox) => {
+            let state = &mut dom[id].state.control_state;
+            state.checked = !state.checked;
+        }
+        Some(ControlKind::Radio) => {
+            if dom[id].state.control_state.checked {
+                return;
+            }
+            // uncheck the other radio buttons in the group
+            let name = dom[id].state.control.name.clone();
+            let scope = ancestor(dom, id, "form").unwrap_or(ElementId(0));
+            let mut group = Vec::new();
+            descendants(dom, scope, &mut |node| {
+                let control = &node.state.control;
+                if control.kind == Some(ControlKind::Radio)
+                    && name.is_some()
+                    && control.name == name
+                {
+                    group.push(node.id);
+                }
+            });
+            for other in group {
+                dom[other].state.control_state.checked = false;
+            }
+            dom[id].state.control_state.checked = true;
+        }
+        _ => return,
+    }
+    form_events(dom, id, &["input", "change"], events);
+}
+
+fn select_option(dom: &mut Dom, id: ElementId, offset: isize, events: &mut Vec<UserEvent>) {
+    let count = options(dom, id).len() as isize;
+    if count == 0 {
+        return;
+    }
+    let state = &mut dom[id].state.control_state;
+    state.selected = (state.selected as isize + offset).rem_euclid(count) as usize;
+    form_events(dom, id, &["input", "change"], events);
+}
+
+/// Handle a key pressed while the control `id` is focused. Returns `true` if the control used the
+/// key.
+pub(crate) fn key(
+    dom: &mut Dom,
+    id: ElementId,
+    data: &KeyboardData,
+    events: &mut Vec<UserEvent>,
+) -> bool {
+    let control = &dom[id].state.control;
+    let kind = match control.kind {
+        Some(kind) if !control.disabled => kind,
+        _ => return false,
+    };
+    let step = control.step.unwrap_or(1.0);
+    let key = data.key();
+    let modifiers = data.modifiers();
+    let ctrl = modifiers.contains(Modifiers::CONTROL);
+    let shift = modifiers.contains(Modifiers::SHIFT);
+    let is_space = matches!(&key, Key::Character(c) if c == " ");
+
+    match kind {
+        ControlKind::Checkbox | ControlKind::Radio if is_space => {
+            toggle(dom, id, events);
+            true
+        }
+        ControlKind::Select => {
+            let offset = match key {
+                Key::ArrowUp => -1,
+                Key::ArrowDown | Key::Enter => 1,
+                _ if is_space => 1,
+                _ => return false,
+            };
+            select_option(dom, id, offset, events);
+            true
+        }
+        ControlKind::Button | ControlKind::InputButton if is_space || key == Key::Enter => {
+            click_event(id, events);
+            true
+        }
+        _ if kind.is_text() => {
+            let editor = &mut dom[id].state.control_state.editor;
+            let before = editor.text.clone();
+            match key {
+                Key::Character(c) if c == "a" && ctrl => editor.select_all(),
+                Key::Character(c) if !ctrl => {
+                    let allowed = kind != ControlKind::Number
+                        || c.chars()
+                            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e'));
+                    if !allowed {
+                        return true;
+                    }
+                    editor.insert(&c);
+                }
+                Key::Backspace => {
+                    editor.backspace(ctrl);
+                }
+                Key::Delete => {
+                    editor.delete(ctrl);
+                }
+                Key::ArrowLeft => editor.left(ctrl, shift),
+                Key::ArrowRight => editor.right(ctrl, shift),
+                Key::Home => editor.move_to(editor.line_start(), shift),
+                Key::End => editor.move_to(editor.line_end(), shift),
+                Key::ArrowUp | Key::ArrowDown if kind == ControlKind::Number => {
+                    let direction = if key == Key::ArrowUp { 1.0 } else { -1.0 };
+                    let number: f64 = editor.text.trim().parse().unwrap_or(0.0);
+                    editor.set_text(&(number + direction * step).to_string());
+                }
+                Key::ArrowUp | Key::ArrowDown if kind == ControlKind::TextArea => {
+                    let direction = if key == Key::ArrowUp { -1 } else { 1 };
+                    if !editor.vertical(direction, shift) {
+                        return false;
+                    }
+                }
+                Key::Enter if kind == ControlKind::TextArea => editor.insert("\n"),
+                Key::Enter => {
+                    let state = &mut dom[id].state.control_state;
+                    if state.value_at_focus.as_ref() != Some(&state.editor.text) {
+                        state.value_at_focus = Some(state.editor.text.clone());
+                        form_events(dom, id, &["change"], events);
+                    }
+                }
+                _ => return false,
+            }
+            if dom[id].state.control_state.editor.text != before {
+                form_events(dom, id, &["input"], events);
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Handle a click on the control `id` at `position`, relative to the top left corner of the node,
+/// which has the size `size`.
+pub(crate) fn click(
+    dom: &mut Dom,
+    id: ElementId,
+    position: [usize; 2],
+    size: [usize; 2],
+    events: &mut Vec<UserEvent>,
+) {
+    let control = &dom[id].state.control;
+    let kind = match control.kind {
+        Some(kind) if !control.disabled => kind,
+        _ => return,
+    };
+    match kind {
+        ControlKind::Checkbox | ControlKind::Radio => toggle(dom, id, events),
+        ControlKind::Select => select_option(dom, id, 1, events),
+        _ if kind.is_text() => {
+            let node = &dom[id];
+            let [left, top, ..] = insets(&node.state.layout.style);
+            let (x, y) = (
+                position[0].saturating_sub(left as usize),
+                position[1].saturating_sub(top as usize),
+            );
+            let cursor = render::position_at(node, x, y, size);
+            dom[id].state.control_state.editor.move_to(cursor, false);
+        }
+        _ => {}
+    }
+}
+
+/// Handle the focus moving from `old` to `new`.
+pub(crate) fn focus_changed(
+    dom: &mut Dom,
+    old: Option<ElementId>,
+    new: Option<ElementId>,
+    events: &mut Vec<UserEvent>,
+) {
+    if let Some(old) = old.filter(|id| dom.get(*id).is_some()) {
+        let state = &mut dom[old].state.control_state;
+        if let Some(value) = state.value_at_focus.take() {
+            if value != state.editor.text {
+                form_events(dom, old, &["change"], events);
+            }
+        }
+    }
+    if let Some(new) = new {
+        if dom[new]
+            .state
+            .control
+            .kind
+            .filter(ControlKind::is_text)
+            .is_some()
+        {
+            let state = &mut dom[new].state.control_state;
+            state.value_at_focus = Some(state.editor.text.clone());
+        }
+    }
+}
+
+/// Get the space between the edge of a node with the layout `style` and its content:
+/// `[left, top, right, bottom]`.
+pub(crate) fn insets(style: &Style) -> [u16; 4] {
+    fn points(d: Dimension) -> u16 {
+        match d {
+            Dimension::Points(p) => p.max(0.0) as u16,
+            _ => 0,
+        }
+    }
+    let sum =
+        |r: fn(&Rect<Dimension>) -> Dimension| points(r(&style.padding)) + points(r(&style.border));
+    [
+        sum(|r| r.start),
+        sum(|r| r.top),
+        sum(|r| r.end),
+        sum(|r| r.bottom),
+    ]
+}
+
+/// Get the size a control has if it isn't set with `width` or `height`, like the default size of
+/// controls in a browser.
+pub(crate) fn default_size(
+    tag: &str,
+    attribute: impl Fn(&str) -> Option<String>,
+    options_width: usize,
+) -> Option<[f32; 2]> {
+    let number = |name, default: f32| {
+        attribute(name)
+            .and_then(|v| v.trim().parse::<f32>().ok())
+            .unwrap_or(default)
+    };
+    let kind = ControlKind::from_tag(tag, attribute("type").as_deref())?;
+    Some(match kind {
+        ControlKind::Text | ControlKind::Password | ControlKind::Number => {
+            [number("size", 20.0), 1.0]
+        }
+        ControlKind::TextArea => [number("cols", 20.0), number("rows", 2.0)],
+        ControlKind::Checkbox | ControlKind::Radio => [3.0, 1.0],
+        ControlKind::Select => [options_width as f32 + 2.0, 1.0],
+        ControlKind::InputButton => [
+            attribute("value").map_or(0, |v| text::width(&v)) as f32 + 2.0,
+            1.0,
+        ],
+        ControlKind::SelectOption | ControlKind::Button => return None,
+    })
+}
@@ -0,0 +1,226 @@
+use tui::{layout::Rect, style::Modifier};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::{insets, selected_label, ControlKind};
+use crate::{
+    style::RinkStyle,
+    text,
+    widget::{RinkBuffer, RinkCell, RinkWidget},
+    Dom, Node,
+};
+
+/// A grapheme of the text in a control.
+struct Glyph<'a> {
+    symbol: &'a str,
+    start: usize,
+    end: usize,
+    width: usize,
+}
+
+/// Split the text of a text control into lines of glyphs. Every line starts with the byte offset
+/// it starts at.
+fn rows(text: &str, password: bool) -> Vec<(usize, Vec<Glyph<'_>>)> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    for line in text.split('\n') {
+        let glyphs = line
+            .grapheme_indices(true)
+            .map(|(i, g)| Glyph {
+                symbol: if password { "•" } else { g },
+                start: start + i,
+                end: start + i + g.len(),
+                width: if password { 1 } else { g.width() },
+            })
+            .collect();
+        rows.push((start, glyphs));
+        start += line.len() + 1;
+    }
+    rows
+}
+
+/// Get the row and column of the cursor.
+fn cursor_cell(rows: &[(usize, Vec<Glyph>)], cursor: usize) -> [usize; 2] {
+    let row = rows
+        .iter()
+        .rposition(|(start, _)| *start <= cursor)
+        .unwrap_or(0);
+    let column = rows[row]
+        .1
+        .iter()
+        .take_while(|g| g.end <= cursor)
+        .map(|g| g.width)
+        .sum();
+    [row, column]
+}
+
+/// Get the first row and column that is shown so the cursor is visible in `size`.
+fn view(cursor: [usize; 2], size: [usize; 2]) -> [usize; 2] {
+    let first = |position: usize, size: usize| (position + 1).saturating_sub(size.max(1));
+    [first(cursor[0], size[1]), first(cursor[1], size[0])]
+}
+
+/// Get the size of the content of a control with the border box `size`.
+fn content_size(node: &Node, size: [usize; 2]) -> [usize; 2] {
+    let [left, top, right, bottom] = insets(&node.state.layout.style).map(|i| i as usize);
+    [
+        size[0].saturating_sub(left + right),
+        size[1].saturating_sub(top + bottom),
+    ]
+}
+
+/// Get the position in the text of a text control at the cell `x`, `y` of its content, when the
+/// node has the size `size`.
+pub(crate) fn position_at(node: &Node, x: usize, y: usize, size: [usize; 2]) -> usize {
+    let state = &node.state.control_state;
+    let password = node.state.control.kind == Some(ControlKind::Password);
+    let rows = rows(&state.editor.text, password);
+    let [row0, column0] = view(
+        cursor_cell(&rows, state.editor.cursor),
+        content_size(node, size),
+    );
+    let (start, glyphs) = &rows[(row0 + y).min(rows.len() - 1)];
+
+    let mut column = 0;
+    for g in glyphs {
+        if column + g.width > column0 + x {
+            return g.start;
+        }
+        column += g.width;
+    }
+    glyphs.last().map_or(*start, |g| g.end)
+}
+
+/// Draws the content of a form control.
+pub(crate) struct ControlWidget<'a> {
+    node: &'a Node,
+    kind: ControlKind,
+    select_label: String,
+}
+
+impl<'a> ControlWidget<'a> {
+    /// Create the widget for `node` if it is a control that draws its content.
+    pub fn new(node: &'a Node, dom: &Dom) -> Option<Self> {
+        let kind = node.state.control.kind?;
+        if matches!(kind, ControlKind::Button | ControlKind::SelectOption) {
+            return None;
+        }
+        let select_label = if kind == ControlKind::Select {
+            selected_label(dom, node.id)
+        } else {
+            String::new()
+        };
+        Some(ControlWidget {
+            node,
+            kind,
+            select_label,
+        })
+    }
+}
+
+fn draw_str(buf: &mut RinkBuffer, text: &str, x: u16, y: u16, max_x: u16, style: RinkStyle) {
+    let mut x = x;
+    for g in text.graphemes(true) {
+        let width = g.width() as u16;
+        if width == 0 {
+            continue;
+        }
+        if x + width > max_x {
+            break;
+        }
+        let mut cell = RinkCell::default();
+        cell.set_style(style);
+        cell.symbol = g.to_string();
+        buf.set(x, y, cell);
+        x += width;
+    }
+}
+
+impl RinkWidget for ControlWidget<'_> {
+    fn render(self, area: Rect, mut buf: RinkBuffer<'_>) {
+        let control = &self.node.state.control;
+        let state = &self.node.state.control_state;
+        let focused = self.node.state.focused;
+        let [left, top, right, bottom] = insets(&self.node.state.layout.style);
+        let x = area.left() + left;
+        let y = area.top() + top;
+        let width = area.width.saturating_sub(left + right);
+        let height = area.height.saturating_sub(top + bottom);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let max_x = x + width;
+
+        let mut style = self.node.state.style.core;
+        if control.disabled {
+            style = style.add_modifier(Modifier::DIM);
+        }
+        let highlighted = style.add_modifier(Modifier::REVERSED);
+
+        match self.kind {
+            ControlKind::Checkbox => {
+                let symbol = if state.checked { "[x]" } else { "[ ]" };
+                draw_str(&mut buf, symbol, x, y, max_x, style);
+            }
+            ControlKind::Radio => {
+                let symbol = if state.checked { "(•)" } else { "( )" };
+                draw_str(&mut buf, symbol, x, y, max_x, style);
+            }
+            ControlKind::Select => {
+                draw_str(&mut buf, &self.select_label, x, y, max_x - 1, style);
+                draw_str(&mut buf, "▾", max_x - 1, y, max_x, style);
+            }
+            ControlKind::InputButton => {
+                let label = control.value.as_deref().unwrap_or_default();
+                let free = width.saturating_sub(text::width(label) as u16);
+                draw_str(&mut buf, label, x + free / 2, y, max_x, style);
+            }
+            _ => {
+                let editor = &state.editor;
+                if editor.text.is_empty() && !focused {
+                    if let Some(placeholder) = &control.placeholder {
+                        let dim = style.add_modifier(Modifier::DIM);
+                        draw_str(&mut buf, placeholder, x, y, max_x, dim);
+                    }
+                    return;
+                }
+
+                let rows = rows(&editor.text, self.kind == ControlKind::Password);
+                let cursor = cursor_cell(&rows, editor.cursor);
+                let [row0, column0] = view(cursor, [width as usize, height as usize]);
+                let selection = editor.selection().filter(|_| focused);
+
+                for (i, (_, glyphs)) in rows.iter().skip(row0).take(height as usize).enumerate() {
+                    let mut column = 0;
+                    for g in glyphs {
+                        let visible =
+                            column >= column0 && column + g.width <= column0 + width as usize;
+                        if visible {
+                            let selected =
+                                matches!(selection, Some((s, e)) if g.start >= s && g.end <= e);
+                            let mut cell = RinkCell::default();
+                            cell.set_style(if selected { highlighted } else { style });
+                            cell.symbol = g.symbol.to_string();
+                            buf.set(x + (column - column0) as u16, y + i as u16, cell);
+                        }
+                        column += g.width;
+                    }
+                }
+
+                if focused {
+                    // draw the cursor over the character after it
+                    let [row, column] = cursor;
+                    let symbol = rows[row]
+                        .1
+                        .iter()
+                        .find(|g| g.start == editor.cursor)
+                        .map_or(" ", |g| g.symbol);
+                    let mut cell = RinkCell::default();
+                    cell.set_style(highlighted);
+                    cell.symbol = symbol.to_string();
+                    buf.set(x + (column - column0) as u16, y + (row - row0) as u16, cell);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! Rendering into an in-memory buffer instead of a terminal, to test what an app looks like.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crossterm::{
+    event::Event as TermEvent,
+    queue,
+    style::{Attribute, Color as CColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use dioxus_core::{Component, ElementId, VirtualDom};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    FutureExt, StreamExt,
+};
+use fxhash::FxHashSet;
+use taffy::Taffy;
+use tui::{
+    backend::TestBackend,
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier, Style},
+    Terminal,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    draw, handle_keybinding, handle_selection, init, is_quit, keymap::Keymap, query::Query,
+    render::Screen, stylesheet::Stylesheets, update, Clipboard, Config, Dom, InputEvent,
+    RinkInputHandler, TuiContext,
+};
+
+/// An app rendered into an in-memory buffer of a fixed size, for snapshot tests.
+///
+/// Nothing happens until [`Headless::step`] is called: it sends the injected events to the app,
+/// lets it update and draws it into the buffer.
+///
+/// ```rust, ignore
+/// let mut app = Headless::new(app, 20, 3);
+/// app.step();
+/// app.inject_event(Event::Key(KeyCode::Tab.into()));
+/// app.step();
+/// assert_eq!(app.text(), "...");
+/// ```
+pub struct Headless {
+    vdom: VirtualDom,
+    cfg: Config,
+    handler: RinkInputHandler,
+    rdom: Rc<RefCell<Dom>>,
+    taffy: Rc<RefCell<Taffy>>,
+    stylesheets: Rc<RefCell<Stylesheets>>,
+    keymap: Rc<RefCell<Keymap>>,
+    clipboard: Rc<RefCell<Box<dyn Clipboard>>>,
+    register_event: Box<dyn FnMut(TermEvent)>,
+    event_tx: UnboundedSender<InputEvent>,
+    event_rx: UnboundedReceiver<InputEvent>,
+    terminal: Terminal<TestBackend>,
+    screen: Screen,
+    /// The nodes that changed since the last draw.
+    changed: FxHashSet<ElementId>,
+    /// Set if something changed that requires repainting everything.
+    repaint: bool,
+    runtime: tokio::runtime::Runtime,
+    running: bool,
+}
+
+impl Headless {
+    /// Build `app` and draw it into a buffer with `width` columns and `height` rows.
+    pub fn new(app: Component<()>, width: u16, height: u16) -> Self {
+        Self::new_with_cfg(app, Config::default(), width, height)
+    }
+
+    /// Build `app` with `cfg` and draw it into a buffer with `width` columns and `height` rows.
+    pub fn new_with_cfg(app: Component<()>, cfg: Config, width: u16, height: u16) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _guard = runtime.enter();
+
+        let mut vdom = VirtualDom::new(app);
+        let (event_tx, event_rx) = unbounded();
+        let (handler, rdom, taffy, stylesheets, keymap, clipboard, register_event) =
+            init(&mut vdom, event_tx.clone());
+        // there is no terminal to copy to, unless the test sets a clipboard
+        *clipboard.borrow_mut() = Box::new(|_: &str| {});
+
+        let mut headless = Self {
+            vdom,
+            cfg,
+            handler,
+            rdom,
+            taffy,
+            stylesheets,
+            keymap,
+            clipboard,
+            register_event: Box::new(register_event),
+            event_tx,
+            event_rx,
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            screen: Screen::default(),
+            changed: FxHashSet::default(),
+            repaint: true,
+            runtime,
+            running: true,
+        };
+        headless.draw();
+        headless
+    }
+
+    /// Get the context the app uses to quit and inject events.
+    pub fn context(&self) -> TuiContext {
+        TuiContext {
+            tx: self.event_tx.clone(),
+            stylesheets: self.stylesheets.clone(),
+            keymap: self.keymap.clone(),
+            clipboard: self.clipboard.clone(),
+        }
+    }
+
+    /// Get the query the app uses to read the layout of its nodes.
+    pub fn query(&self) -> Query {
+        self.vdom.base_scope().consume_context().unwrap()
+    }
+
+    /// Queue an event, as if it came from the terminal. It is handled in the next step.
+    pub fn inject_event(&self, event: TermEvent) {
+        self.context().inject_event(event);
+    }
+
+    /// Change the size of the buffer. The app is laid out again in the next step.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.inject_event(TermEvent::Resize(width, height));
+    }
+
+    /// Check whether the app is still running, i.e. it didn't call [`TuiContext::quit`].
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    /// Handle the queued events, let the app's tasks make progress, apply the changes the app
+    /// made and draw it. Returns `false` if the app quit.
+    ///
+    /// Step doesn't wait for the tasks: a timer in a task fires in the first step after its time
+    /// has passed.
+    pub fn step(&mut self) -> bool {
+        if !self.running {
+            return false;
+        }
+        let _guard = self.runtime.enter();
+
+        while let Some(Some(evt)) = self.event_rx.next().now_or_never() {
+            match evt {
+                InputEvent::UserInput(evt) => {
+                    if is_quit(&evt, &self.cfg) {
+                        self.running = false;
+                        return false;
+                    }
+                    if let TermEvent::Resize(width, height) = evt {
+                        self.terminal.backend_mut().resize(width, height);
+                    }
+                    // every event is handled on its own, like they are when they come from a terminal
+                    self.repaint |=
+                        handle_selection(&evt, &mut self.screen, &self.clipboard, &self.cfg);
+                    if !handle_keybinding(&evt, &self.keymap) {
+                        (self.register_event)(evt);
+                    }
+                    self.update();
+                }
+                // there are no lines above the buffer
+                InputEvent::Print(_) => {}
+                InputEvent::Close => {
+                    self.running = false;
+                    return false;
+                }
+            }
+        }
+
+        // poll the tasks, let the runtime fire the timers and IO that are due when the tasks yield,
+        // and poll the tasks they woke, without waiting for anything else
+        let vdom = &mut self.vdom;
+        self.runtime.block_on(async {
+            let _ = vdom.wait_for_work().now_or_never();
+            tokio::task::yield_now().await;
+            let _ = vdom.wait_for_work().now_or_never();
+        });
+        self.vdom.process_all_messages();
+        self.update();
+
+        self.draw();
+        true
+    }
+
+    /// Repaint the whole app in the next step, instead of only the parts of it that changed.
+    pub fn invalidate(&mut self) {
+        self.repaint = true;
+    }
+
+    fn update(&mut self) {
+        let (changed, repaint) = update(
+            &mut self.vdom,
+            &self.handler,
+            &self.rdom,
+            &self.taffy,
+            &self.stylesheets,
+            &self.keymap,
+            &self.screen,
+        );
+        self.changed.extend(changed);
+        self.repaint |= repaint;
+    }
+
+    fn draw(&mut self) {
+        let changed = std::mem::take(&mut self.changed);
+        draw(
+            &mut self.terminal,
+            0,
+            &self.rdom.borrow(),
+            &self.taffy,
+            self.cfg,
+            &mut self.screen,
+            if std::mem::take(&mut self.repaint) {
+                None
+            } else {
+                Some(&changed)
+            },
+        )
+        .unwrap();
+    }
+
+    /// Get the text the user selected with the mouse, if selection is enabled with
+    /// [`Config::with_selection`].
+    pub fn selection(&self) -> Option<String> {
+        self.screen.selected_text()
+    }
+
+    /// Get the buffer the app was drawn into.
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// Get the text in the buffer without styles. Lines are separated by `\n`, and spaces at the
+    /// end of a line are removed.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for (i, line) in lines(self.buffer()).enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            let line: String = line.map(|c| c.symbol.as_str()).collect();
+            text.push_str(line.trim_end());
+        }
+        text
+    }
+
+    /// Get the text in the buffer with its colors and modifiers as ANSI escape codes. Every line
+    /// ends with a reset, so lines can be compared on their own.
+    pub fn ansi(&self) -> String {
+        let mut ansi = Vec::new();
+        for (i, line) in lines(self.buffer()).enumerate() {
+            if i > 0 {
+                ansi.push(b'\n');
+            }
+            let mut style = None;
+            for cell in line {
+                if style != Some(cell.style()) {
+                    write_style(&mut ansi, cell.style());
+                    style = Some(cell.style());
+                }
+                ansi.extend_from_slice(cell.symbol.as_bytes());
+            }
+            queue!(ansi, SetAttribute(Attribute::Reset)).unwrap();
+        }
+        String::from_utf8(ansi).unwrap()
+    }
+}
+
+/// Iterate over the lines of `buffer`, skipping the cells that are covered by wide characters.
+fn lines(buffer: &Buffer) -> impl Iterator<Item = impl Iterator<Item = &Cell>> {
+    let width = buffer.area().width as usize;
+    buffer.content().chunks(width.max(1)).map(|line| {
+        let mut covered = 0;
+        line.iter().filter(move |cell| {
+            if covered > 0 {
+                covered -= 1;
+                return false;
+            }
+            covered = cell.symbol.width().saturating_sub(1);
+            true
+        })
+    })
+}
+
+fn write_style(out: &mut Vec<u8>, style: Style) {
+    queue!(out, SetAttribute(Attribute::Reset)).unwrap();
+    let modifier = style.add_modifier;
+    for (flag, attribute) in [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::SLOW_BLINK, Attribute::SlowBlink),
+        (Modifier::RAPID_BLINK, Attribute::RapidBlink),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::HIDDEN, Attribute::Hidden),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ] {
+        if modifier.contains(flag) {
+            queue!(out, SetAttribute(attribute)).unwrap();
+        }
+    }
+    if let Some(fg) = style.fg {
+        queue!(out, SetForegroundColor(color(fg))).unwrap();
+    }
+    if let Some(bg) = style.bg {
+        queue!(out, SetBackgroundColor(color(bg))).unwrap();
+    }
+}
+
+fn color(color: Color) -> CColor {
+    match color {
+        Color::Reset => CColor::Reset,
+        Color::Black => CColor::Black,
+        Color::Red => CColor::DarkRed,
+        Color::Green => CColor::DarkGreen,
+        Color::Yellow => CColor::DarkYellow,
+        Color::Blue => CColor::DarkBlue,
+        Color::Magenta => CColor::DarkMagenta,
+        Color::Cyan => CColor::DarkCyan,
+        Color::Gray => CColor::Grey,
+        Color::DarkGray => CColor::DarkGrey,
+        Color::LightRed => CColor::Red,
+        Color::LightGreen => CColor::Green,
+        Color::LightBlue => CColor::Blue,
+        Color::LightYellow => CColor::Yellow,
+        Color::LightMagenta => CColor::Magenta,
+        Color::LightCyan => CColor::Cyan,
+        Color::White => CColor::White,
+        Color::Indexed(i) => CColor::AnsiValue(i),
+        Color::Rgb(r, g, b) => CColor::Rgb { r, g, b },
+    }
+}
@@ -4,7 +4,6 @@ use crossterm::event::{
 use dioxus_core::*;
 use fxhash::{FxHashMap, FxHashSet};
 
-use dioxus_html::geometry::euclid::{Point2D, Rect, Size2D};
 use dioxus_html::geometry::{
     ClientPoint, Coordinates, ElementPoint, PagePoint, ScreenPoint, WheelDelta,
 };
@@ -19,11 +18,14 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use taffy::geometry::{Point, Size};
+use taffy::geometry::Point;
 use taffy::{prelude::Layout, Taffy};
 
+use crate::form;
+use crate::render::Screen;
+use crate::scroll;
 use crate::FocusState;
-use crate::{Dom, Node};
+use crate::{Dom, Node, PreventDefault};
 
 // a wrapper around the input state for easier access
 // todo: fix loop
@@ -88,6 +90,12 @@ pub struct InnerInputState {
     last_key_pressed: Option<(KeyboardData, Instant)>,
     screen: Option<(u16, u16)>,
     pub(crate) focus_state: FocusState,
+    /// Nodes that scrolled since the last `scroll` events were sent.
+    pub(crate) scrolled: FxHashSet<ElementId>,
+    /// The `input`, `change` and `click` events form controls created.
+    form_events: Vec<UserEvent>,
+    /// Set when the state of a form control changed and it needs to be redrawn.
+    pub(crate) form_dirty: bool,
     // subscribers: Vec<Rc<dyn Fn() + 'static>>,
 }
 
@@ -100,6 +108,9 @@ impl InnerInputState {
             screen: None,
             // subscribers: Vec::new(),
             focus_state: FocusState::default(),
+            scrolled: FxHashSet::default(),
+            form_events: Vec::new(),
+            form_dirty: false,
         }
     }
 
@@ -166,6 +177,7 @@ impl InnerInputState {
         resolved_events: &mut Vec<UserEvent>,
         layout: &Taffy,
         dom: &mut Dom,
+        screen: &Screen,
     ) {
         let previous_mouse = self.mouse.clone();
 
@@ -185,11 +197,25 @@ impl InnerInputState {
 
         for e in evts.iter_mut() {
             self.apply_event(e);
+            if let EventData::Keyboard(k) = &e.1 {
+                if !self.form_key(k, dom) {
+                    self.scroll_for_key(k.code(), layout, dom);
+                }
+            }
         }
+        self.scroll_for_wheel(layout, dom, screen);
 
-        self.resolve_mouse_events(previous_mouse, resolved_events, layout, dom);
+        self.resolve_mouse_events(previous_mouse, resolved_events, layout, dom, screen);
 
         if old_focus != self.focus_state.last_focused_id {
+            // the change event of a text input is sent before it loses focus
+            form::focus_changed(
+                dom,
+                old_focus,
+                self.focus_state.last_focused_id,
+                resolved_events,
+            );
+            self.form_dirty = true;
             if let Some(id) = self.focus_state.last_focused_id {
                 resolved_events.push(UserEvent {
                     scope_id: None,
@@ -231,15 +257,8 @@ impl InnerInputState {
         resolved_events: &mut Vec<UserEvent>,
         layout: &Taffy,
         dom: &mut Dom,
+        screen: &Screen,
     ) {
-        fn layout_contains_point(layout: &Layout, point: ScreenPoint) -> bool {
-            let Point { x, y } = layout.location;
-            let Size { width, height } = layout.size;
-
-            let layout_rect = Rect::new(Point2D::new(x, y), Size2D::new(width, height));
-            layout_rect.contains(point.cast())
-        }
-
         fn try_create_event(
             name: &'static str,
             data: Arc<dyn Any + Send + Sync>,
@@ -309,16 +328,20 @@ impl InnerInputState {
                 .map_or(false, |data| !data.delta().is_zero());
             let wheel_data = &self.wheel;
 
+            // the mouse is over the topmost element at its position and the elements it is inside of
+            let hovered = screen.elements_at(dom, new_pos.x, new_pos.y);
+            let previously_hovered = old_pos
+                .map(|pos| screen.elements_at(dom, pos.x, pos.y))
+                .unwrap_or_default();
+
             {
                 // mousemove
                 if old_pos != Some(new_pos) {
                     let mut will_bubble = FxHashSet::default();
                     for node in dom.get_listening_sorted("mousemove") {
                         let node_layout = get_abs_layout(node, dom, layout);
-                        let previously_contained = old_pos
-                            .filter(|pos| layout_contains_point(&node_layout, *pos))
-                            .is_some();
-                        let currently_contains = layout_contains_point(&node_layout, new_pos);
+                        let previously_contained = previously_hovered.contains(&node.id);
+                        let currently_contains = hovered.contains(&node.id);
 
                         if currently_contains && previously_contained {
                             try_create_event(
@@ -338,11 +361,8 @@ impl InnerInputState {
                 // mouseenter
                 let mut will_bubble = FxHashSet::default();
                 for node in dom.get_listening_sorted("mouseenter") {
-                    let node_layout = get_abs_layout(node, dom, layout);
-                    let previously_contained = old_pos
-                        .filter(|pos| layout_contains_point(&node_layout, *pos))
-                        .is_some();
-                    let currently_contains = layout_contains_point(&node_layout, new_pos);
+                    let previously_contained = previously_hovered.contains(&node.id);
+                    let currently_contains = hovered.contains(&node.id);
 
                     if currently_contains && !previously_contained {
                         try_create_event(
@@ -362,10 +382,8 @@ impl InnerInputState {
                 let mut will_bubble = FxHashSet::default();
                 for node in dom.get_listening_sorted("mouseover") {
                     let node_layout = get_abs_layout(node, dom, layout);
-                    let previously_contained = old_pos
-                        .filter(|pos| layout_contains_point(&node_layout, *pos))
-                        .is_some();
-                    let currently_contains = layout_contains_point(&node_layout, new_pos);
+                    let previously_contained = previously_hovered.contains(&node.id);
+                    let currently_contains = hovered.contains(&node.id);
 
                     if currently_contains && !previously_contained {
                         try_create_event(
@@ -385,7 +403,7 @@ impl InnerInputState {
                 let mut will_bubble = FxHashSet::default();
                 for node in dom.get_listening_sorted("mousedown") {
                     let node_layout = get_abs_layout(node, dom, layout);
-                    let currently_contains = layout_contains_point(&node_layout, new_pos);
+                    let currently_contains = hovered.contains(&node.id);
 
                     if currently_contains {
                         try_create_event(
@@ -406,7 +424,7 @@ impl InnerInputState {
                     let mut will_bubble = FxHashSet::default();
                     for node in dom.get_listening_sorted("mouseup") {
                         let node_layout = get_abs_layout(node, dom, layout);
-                        let currently_contains = layout_contains_point(&node_layout, new_pos);
+                        let currently_contains = hovered.contains(&node.id);
 
                         if currently_contains {
                             try_create_event(
@@ -428,7 +446,7 @@ impl InnerInputState {
                     let mut will_bubble = FxHashSet::default();
                     for node in dom.get_listening_sorted("click") {
                         let node_layout = get_abs_layout(node, dom, layout);
-                        let currently_contains = layout_contains_point(&node_layout, new_pos);
+                        let currently_contains = hovered.contains(&node.id);
 
                         if currently_contains {
                             try_create_event(
@@ -451,7 +469,7 @@ impl InnerInputState {
                     let mut will_bubble = FxHashSet::default();
                     for node in dom.get_listening_sorted("contextmenu") {
                         let node_layout = get_abs_layout(node, dom, layout);
-                        let currently_contains = layout_contains_point(&node_layout, new_pos);
+                        let currently_contains = hovered.contains(&node.id);
 
                         if currently_contains {
                             try_create_event(
@@ -473,9 +491,7 @@ impl InnerInputState {
                     if was_scrolled {
                         let mut will_bubble = FxHashSet::default();
                         for node in dom.get_listening_sorted("wheel") {
-                            let node_layout = get_abs_layout(node, dom, layout);
-
-                            let currently_contains = layout_contains_point(&node_layout, new_pos);
+                            let currently_contains = hovered.contains(&node.id);
 
                             if currently_contains {
                                 try_create_event(
@@ -497,10 +513,8 @@ impl InnerInputState {
                 let mut will_bubble = FxHashSet::default();
                 for node in dom.get_listening_sorted("mouseleave") {
                     let node_layout = get_abs_layout(node, dom, layout);
-                    let previously_contained = old_pos
-                        .filter(|pos| layout_contains_point(&node_layout, *pos))
-                        .is_some();
-                    let currently_contains = layout_contains_point(&node_layout, new_pos);
+                    let previously_contained = previously_hovered.contains(&node.id);
+                    let currently_contains = hovered.contains(&node.id);
 
                     if !currently_contains && previously_contained {
                         try_create_event(
@@ -520,10 +534,8 @@ impl InnerInputState {
                 let mut will_bubble = FxHashSet::default();
                 for node in dom.get_listening_sorted("mouseout") {
                     let node_layout = get_abs_layout(node, dom, layout);
-                    let previously_contained = old_pos
-                        .filter(|pos| layout_contains_point(&node_layout, *pos))
-                        .is_some();
-                    let currently_contains = layout_contains_point(&node_layout, new_pos);
+                    let previously_contained = previously_hovered.contains(&node.id);
+                    let currently_contains = hovered.contains(&node.id);
 
                     if !currently_contains && previously_contained {
                         try_create_event(
@@ -540,22 +552,122 @@ impl InnerInputState {
 
             // update focus
             if was_released {
-                let mut focus_id = None;
-                dom.traverse_depth_first(|node| {
-                    let node_layout = layout.layout(node.state.layout.node.unwrap()).unwrap();
-                    let currently_contains = layout_contains_point(node_layout, new_pos);
-
-                    if currently_contains && node.state.focus.level.focusable() {
-                        focus_id = Some(node.id);
+                // focus the innermost focusable element under the mouse
+                let mut focus_id = screen.element_at(dom, new_pos.x, new_pos.y);
+                while let Some(id) = focus_id {
+                    if dom[id].state.focus.level.focusable() {
+                        break;
                     }
-                });
+                    focus_id = dom[id].parent;
+                }
                 if let Some(id) = focus_id {
                     self.focus_state.set_focus(dom, id);
+
+                    let node = &dom[id];
+                    if node.state.control.kind.is_some()
+                        && node.state.prevent_default != PreventDefault::Click
+                    {
+                        let node_layout = get_abs_layout(node, dom, layout);
+                        let position = [
+                            (new_pos.x - node_layout.location.x as f64).max(0.0) as usize,
+                            (new_pos.y - node_layout.location.y as f64).max(0.0) as usize,
+                        ];
+                        let size = [
+                            node_layout.size.width as usize,
+                            node_layout.size.height as usize,
+                        ];
+                        form::click(dom, id, position, size, &mut self.form_events);
+                        self.form_dirty = true;
+                    }
                 }
             }
         }
     }
 
+    /// Let the focused form control handle a key. Returns `true` if the control used the key.
+    fn form_key(&mut self, data: &KeyboardData, dom: &mut Dom) -> bool {
+        let id = match self.focus_state.last_focused_id {
+            Some(id) if dom[id].state.prevent_default != PreventDefault::KeyDown => id,
+            _ => return false,
+        };
+        let used = form::key(dom, id, data, &mut self.form_events);
+        self.form_dirty |= used;
+        used
+    }
+
+    /// Scroll the container around the focused node with the arrow, page and home/end keys.
+    fn scroll_for_key(&mut self, code: Code, layout: &Taffy, dom: &mut Dom) {
+        if let Some(id) = self.focus_state.last_focused_id {
+            if dom[id].state.prevent_default == PreventDefault::KeyDown {
+                return;
+            }
+            if let Some(scrolled) = scroll::scroll_for_key(dom, layout, id, code) {
+                self.scrolled.insert(scrolled);
+            }
+        }
+    }
+
+    /// Scroll the innermost container under the mouse that can scroll in the direction of the wheel.
+    fn scroll_for_wheel(&mut self, layout: &Taffy, dom: &mut Dom, screen: &Screen) {
+        let (wheel, mouse) = match (&self.wheel, &self.mouse) {
+            (Some(wheel), Some(mouse)) if !wheel.delta().is_zero() => (wheel, mouse),
+            _ => return,
+        };
+        let delta = wheel.delta().strip_units();
+        let delta = Point {
+            x: delta.x as f32,
+            y: delta.y as f32,
+        };
+        let pos = mouse.screen_coordinates();
+
+        let hovered = screen.element_at(dom, pos.x, pos.y);
+
+        if let Some(id) = hovered.and_then(|id| scroll::scroll_target(dom, layout, id, delta)) {
+            if dom[id].state.prevent_default != PreventDefault::Wheel
+                && scroll::scroll_by(dom, layout, id, delta)
+            {
+                self.scrolled.insert(id);
+            }
+        }
+    }
+
+    /// Get the position of the mouse on the screen, if it moved over the terminal.
+    pub(crate) fn mouse_position(&self) -> Option<ScreenPoint> {
+        self.mouse.as_ref().map(|m| m.screen_coordinates())
+    }
+
+    /// Create `scroll` events for the nodes that scrolled since the last call.
+    pub(crate) fn scroll_events(&mut self, dom: &Dom) -> Vec<UserEvent> {
+        if self.scrolled.is_empty() {
+            return Vec::new();
+        }
+        let scrolled = std::mem::take(&mut self.scrolled);
+        let data = MouseData::new(
+            Coordinates::new(
+                ScreenPoint::zero(),
+                ClientPoint::zero(),
+                ElementPoint::zero(),
+                PagePoint::zero(),
+            ),
+            None,
+            MouseButtonSet::empty(),
+            Modifiers::empty(),
+        );
+        let data: Arc<dyn Any + Send + Sync> = Arc::new(data);
+        dom.get_listening_sorted("scroll")
+            .into_iter()
+            .filter(|node| scrolled.contains(&node.id))
+            .map(|node| UserEvent {
+                scope_id: None,
+                priority: EventPriority::Medium,
+                name: "scroll",
+                element: Some(node.id),
+                data: data.clone(),
+                bubbles: event_bubbles("scroll"),
+            })
+            .collect()
+    }
+
     // fn subscribe(&mut self, f: Rc<dyn Fn() + 'static>) {
     //     self.subscribers.push(f)
     // }
@@ -569,8 +681,9 @@ fn get_abs_layout(node: &Node, dom: &Dom, taffy: &Taffy) -> Layout {
         let parent = &dom[parent_id];
         current = parent;
         let parent_layout = taffy.layout(parent.state.layout.node.unwrap()).unwrap();
-        node_layout.location.x += parent_layout.location.x;
-        node_layout.location.y += parent_layout.location.y;
+        let offset = scroll::offset(parent, dom, taffy);
+        node_layout.location.x += parent_layout.location.x - offset.x;
+        node_layout.location.y += parent_layout.location.y - offset.y;
     }
     node_layout
 }
@@ -615,7 +728,12 @@ impl RinkInputHandler {
         self.state.borrow_mut().focus_state.prune(mutations, rdom);
     }
 
-    pub(crate) fn get_events(&self, layout: &Taffy, dom: &mut Dom) -> Vec<UserEvent> {
+    pub(crate) fn get_events(
+        &self,
+        layout: &Taffy,
+        dom: &mut Dom,
+        screen: &Screen,
+    ) -> Vec<UserEvent> {
         let mut resolved_events = Vec::new();
 
         (*self.state).borrow_mut().update(
@@ -623,6 +741,7 @@ impl RinkInputHandler {
             &mut resolved_events,
             layout,
             dom,
+            screen,
         );
 
         let events = self
@@ -673,6 +792,9 @@ impl RinkInputHandler {
             }
         }
 
+        // the controls change after the keys are pressed
+        resolved_events.append(&mut self.state.borrow_mut().form_events);
+
         resolved_events
     }
 
@@ -0,0 +1,133 @@
+//! Rendering into a region below the cursor instead of on the alternate screen, for command line
+//! tools that show a prompt or progress inline with their regular output.
+
+use std::io::{self, Stdout, Write};
+
+use crossterm::{
+    cursor::{self, MoveTo},
+    queue,
+    style::Print,
+    terminal::{self, Clear, ClearType},
+};
+use tui::{
+    backend::CrosstermBackend,
+    layout::Rect,
+    terminal::{TerminalOptions, Viewport},
+    Terminal,
+};
+
+use crate::{text, InlineHeight};
+
+/// The region of the terminal an inline app is drawn in.
+pub(crate) struct Inline {
+    height: InlineHeight,
+    /// The first row of the region.
+    top: u16,
+    rows: u16,
+    width: u16,
+}
+
+impl Inline {
+    /// Reserve the lines below the cursor. If the cursor isn't at the start of a line, the region
+    /// starts on the next line.
+    pub fn new(height: InlineHeight) -> io::Result<Self> {
+        let (column, row) = cursor::position()?;
+        let (width, _) = terminal::size()?;
+        let mut inline = Inline {
+            height,
+            top: row + (column > 0) as u16,
+            rows: 0,
+            width,
+        };
+        let rows = match height {
+            InlineHeight::Fixed(rows) => rows,
+            InlineHeight::Content => 1,
+        };
+        inline.reserve(rows)?;
+        Ok(inline)
+    }
+
+    /// Get the number of rows the region should have if the content is `content_height` rows high.
+    pub fn rows_for(&self, content_height: impl FnOnce(u16) -> f32) -> u16 {
+        match self.height {
+            InlineHeight::Fixed(rows) => rows,
+            InlineHeight::Content => content_height(self.width).ceil() as u16,
+        }
+    }
+
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// Make room for `rows` lines at the top of the region and clear them. If they don't fit below
+    /// the top, the terminal is scrolled up.
+    pub fn reserve(&mut self, rows: u16) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let rows = rows.clamp(1, height.max(1));
+        let mut stdout = io::stdout();
+        let overflow = (self.top + rows).saturating_sub(height);
+        if overflow > 0 {
+            // new lines at the bottom of the terminal move the previous output into the scrollback
+            queue!(stdout, MoveTo(0, height - 1))?;
+            for _ in 0..overflow {
+                queue!(stdout, Print("\n"))?;
+            }
+            self.top -= overflow;
+        }
+        self.rows = rows;
+        self.width = width;
+        queue!(
+            stdout,
+            MoveTo(0, self.top),
+            Clear(ClearType::FromCursorDown)
+        )?;
+        stdout.flush()
+    }
+
+    /// Create a terminal that draws into the region. The region must be cleared before, because
+    /// the terminal only draws the cells that are not empty the first time.
+    pub fn terminal(&self) -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
+        Terminal::with_options(
+            CrosstermBackend::new(io::stdout()),
+            TerminalOptions {
+                viewport: Viewport::fixed(Rect::new(0, self.top, self.width, self.rows)),
+            },
+        )
+    }
+
+    /// Print `text` in the place of the region, and move the region below it.
+    pub fn print_above(&mut self, text: &str) -> io::Result<()> {
+        let (_, height) = terminal::size()?;
+        let mut stdout = io::stdout();
+        queue!(
+            stdout,
+            MoveTo(0, self.top),
+            Clear(ClearType::FromCursorDown)
+        )?;
+        let mut printed = 0;
+        for line in text.split('\n') {
+            queue!(stdout, Print(line), Print("\r\n"))?;
+            // lines wider than the terminal wrap
+            let width = text::width(line) as u16;
+            let w = self.width.max(1);
+            printed += (width.max(1) + w - 1) / w;
+        }
+        stdout.flush()?;
+        // the terminal scrolls when the last line is reached
+        self.top = (self.top + printed).min(height.saturating_sub(1));
+        self.reserve(self.rows)
+    }
+
+    /// Move the region to fit into a terminal that was resized.
+    pub fn resize(&mut self, height: u16) -> io::Result<()> {
+        self.top = self.top.min(height.saturating_sub(self.rows));
+        self.reserve(self.rows)
+    }
+
+    /// Leave the last frame in the terminal and move the cursor below it.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        queue!(stdout, MoveTo(0, self.top + self.rows - 1), Print("\r\n"))?;
+        stdout.flush()
+    }
+}
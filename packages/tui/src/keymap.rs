@@ -0,0 +1,496 @@
+//! Key bindings that run an action when a key, or a sequence of keys, is pressed.
+
+use std::{cell::RefCell, cmp::Reverse, fmt, rc::Rc, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use dioxus_core::{ElementId, ScopeId, ScopeState, VNode, VirtualDom};
+use dioxus_native_core::traversable::Traversable;
+use fxhash::FxHashMap;
+
+use crate::{Dom, TuiContext};
+
+/// A key and the modifiers that are held down with it, like `ctrl+s`.
+///
+/// Keys are parsed from their name, like `enter`, `esc`, `f5` or `a`, prefixed by the modifiers
+/// `ctrl+`, `alt+` and `shift+`. Characters ignore `shift`, because the character already tells
+/// if it was held down: `shift+a` is the same key as `A`, and `?` is bound instead of `shift+/`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                // terminals don't report shift together with control
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else {
+                    code
+                }
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                code
+            }
+            _ => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Check whether a text input uses the key to edit its value.
+    fn edits_text(&self) -> bool {
+        !self
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            && matches!(
+                self.code,
+                KeyCode::Char(_)
+                    | KeyCode::Enter
+                    | KeyCode::Backspace
+                    | KeyCode::Delete
+                    | KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Up
+                    | KeyCode::Down
+                    | KeyCode::Home
+                    | KeyCode::End
+            )
+    }
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for KeyPress {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseKeyError { key: s.to_string() };
+
+        // the key is after the last `+`, unless the key is `+` itself
+        let (modifier_names, key) = match s.strip_suffix('+') {
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(rest), "+")
+            }
+            _ => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        if !modifier_names.is_empty() {
+            for name in modifier_names.split('+') {
+                modifiers |= match name.to_ascii_lowercase().as_str() {
+                    "ctrl" | "control" => KeyModifiers::CONTROL,
+                    "alt" => KeyModifiers::ALT,
+                    "shift" => KeyModifiers::SHIFT,
+                    _ => return Err(err()),
+                };
+            }
+        }
+
+        let lower = key.to_ascii_lowercase();
+        let mut code = match lower.as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            _ => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=24) => KeyCode::F(n),
+                _ => {
+                    let mut chars = key.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => KeyCode::Char(c),
+                        _ => return Err(err()),
+                    }
+                }
+            },
+        };
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            code = match code {
+                KeyCode::Char(c) => KeyCode::Char(c.to_ascii_uppercase()),
+                KeyCode::Tab => KeyCode::BackTab,
+                _ => code,
+            };
+        }
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl+"),
+            (KeyModifiers::ALT, "alt+"),
+            (KeyModifiers::SHIFT, "shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::BackTab => f.write_str("shift+tab"),
+            code => f.write_str(&format!("{:?}", code).to_ascii_lowercase()),
+        }
+    }
+}
+
+/// The keys that are pressed one after another to trigger a binding, separated by spaces like
+/// `g g` or `ctrl+x ctrl+s`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct KeySequence(pub Vec<KeyPress>);
+
+impl FromStr for KeySequence {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err(ParseKeyError { key: s.to_string() });
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, key) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", key)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseKeyError {
+    key: String,
+}
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid key `{}`", self.key)
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+/// Where the focus has to be for a binding to be used.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Region {
+    Global,
+    Element(ElementId),
+    Component(ScopeId),
+}
+
+/// A sequence of keys bound to an action, with a description to show in a list of the keys an
+/// app uses.
+///
+/// If the keys of several active bindings are pressed, the binding with the highest priority
+/// is used. If they have the same priority, the binding with the innermost region around the
+/// focused element is used, and global bindings are used last. If that is the same too, the
+/// binding that was added last is used.
+///
+/// A binding is used as soon as its keys were pressed, so a binding for `g` hides a binding for
+/// `g g`. The keys a binding uses are not sent to the app.
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyBinding {
+    keys: KeySequence,
+    description: String,
+    region: Region,
+    priority: i32,
+}
+
+impl KeyBinding {
+    /// Create a global binding for `keys`, like `ctrl+s` or `g g`.
+    ///
+    /// # Panics
+    ///
+    /// If the keys can't be parsed, see [`KeyPress`] for their names.
+    pub fn new(keys: &str, description: impl Into<String>) -> Self {
+        Self {
+            keys: keys.parse().unwrap_or_else(|e| panic!("{}", e)),
+            description: description.into(),
+            region: Region::Global,
+            priority: 0,
+        }
+    }
+
+    /// Only use the binding while `id` or an element inside of it is focused.
+    pub fn in_element(self, id: ElementId) -> Self {
+        Self {
+            region: Region::Element(id),
+            ..self
+        }
+    }
+
+    /// Only use the binding while an element that the component `scope` rendered is focused.
+    /// Pass `cx.scope_id()` to scope a binding to the component that adds it.
+    pub fn in_component(self, scope: ScopeId) -> Self {
+        Self {
+            region: Region::Component(scope),
+            ..self
+        }
+    }
+
+    /// Use the binding before the bindings with a lower priority. The default priority is 0.
+    pub fn with_priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+
+    pub fn keys(&self) -> &KeySequence {
+        &self.keys
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// Identifies a binding added with [`TuiContext::add_keybinding`], to remove it again.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct KeyBindingId(usize);
+
+pub(crate) type Action = Rc<dyn Fn()>;
+
+struct Entry {
+    id: KeyBindingId,
+    binding: KeyBinding,
+    action: Action,
+}
+
+/// The key bindings of an app, and the keys of a sequence that was started.
+#[derive(Default)]
+pub(crate) struct Keymap {
+    entries: Vec<Entry>,
+    next_id: usize,
+    /// The keys of a sequence that were pressed so far.
+    pending: Vec<KeyPress>,
+    /// The focused element and the elements it is inside of, from the innermost one.
+    focus_path: Vec<ElementId>,
+    /// The elements at the root of the components that bindings are scoped to.
+    component_roots: FxHashMap<ScopeId, Vec<ElementId>>,
+    /// Set if a text input is focused.
+    typing: bool,
+}
+
+impl Keymap {
+    pub fn add(&mut self, binding: KeyBinding, action: Action) -> KeyBindingId {
+        let id = KeyBindingId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            binding,
+            action,
+        });
+        id
+    }
+
+    /// Change the keys and the action of a binding, without changing its place in the order
+    /// bindings were added in.
+    pub fn replace(&mut self, id: KeyBindingId, binding: KeyBinding, action: Action) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) {
+            entry.binding = binding;
+            entry.action = action;
+        }
+    }
+
+    pub fn remove(&mut self, id: KeyBindingId) {
+        self.entries.retain(|e| e.id != id);
+    }
+
+    /// Remember where the focus is after the dom changed, to find the bindings that are active.
+    pub fn update(&mut self, vdom: &VirtualDom, rdom: &Dom, focused: Option<ElementId>) {
+        self.focus_path.clear();
+        let mut current = focused.filter(|id| rdom.get(*id).is_some());
+        while let Some(id) = current {
+            self.focus_path.push(id);
+            current = rdom[id].parent;
+        }
+        self.typing = matches!(
+            self.focus_path.first().and_then(|id| rdom[*id].state.control.kind),
+            Some(kind) if kind.is_text()
+        );
+
+        self.component_roots.clear();
+        for entry in &self.entries {
+            if let Region::Component(scope) = entry.binding.region {
+                self.component_roots.entry(scope).or_insert_with(|| {
+                    let mut roots = Vec::new();
+                    component_roots(vdom, scope, &mut roots);
+                    roots
+                });
+            }
+        }
+    }
+
+    /// Get how deep the region of `binding` is around the focused element, or `None` if the
+    /// binding is not active. Global bindings have the depth 0.
+    fn depth(&self, binding: &KeyBinding) -> Option<usize> {
+        let depth = |id: &ElementId| {
+            let i = self.focus_path.iter().position(|f| f == id)?;
+            Some(self.focus_path.len() - i)
+        };
+        match &binding.region {
+            Region::Global => Some(0),
+            Region::Element(id) => depth(id),
+            Region::Component(scope) => self
+                .component_roots
+                .get(scope)?
+                .iter()
+                .filter_map(depth)
+                .max(),
+        }
+    }
+
+    /// Get the active bindings, starting with the one that is used first.
+    fn active(&self) -> Vec<&Entry> {
+        let mut active: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|e| Some((e.binding.priority, self.depth(&e.binding)?, e)))
+            .collect();
+        active.sort_by_key(|(priority, depth, e)| Reverse((*priority, *depth, e.id.0)));
+        active.into_iter().map(|(_, _, e)| e).collect()
+    }
+
+    /// Get the active bindings, starting with the one that is used first. Bindings that are
+    /// hidden by another binding for the same keys are left out.
+    pub fn bindings(&self) -> Vec<KeyBinding> {
+        let mut bindings: Vec<KeyBinding> = Vec::new();
+        for entry in self.active() {
+            if bindings.iter().all(|b| b.keys != entry.binding.keys) {
+                bindings.push(entry.binding.clone());
+            }
+        }
+        bindings
+    }
+
+    /// Handle a key press. Returns whether a binding used the key, and the action to run if the
+    /// keys of a binding were pressed.
+    ///
+    /// While a text input is focused, keys it uses to edit its value only start the bindings of
+    /// the input itself.
+    pub fn press(&mut self, key: KeyPress) -> (bool, Option<Action>) {
+        enum Match {
+            Binding(Action),
+            Prefix,
+            Nothing,
+        }
+
+        let mut keys = std::mem::take(&mut self.pending);
+        keys.push(key);
+        loop {
+            let found = {
+                let active: Vec<_> = self
+                    .active()
+                    .into_iter()
+                    .filter(|e| {
+                        !self.typing
+                            || !e.binding.keys.0[0].edits_text()
+                            || self.depth(&e.binding) == Some(self.focus_path.len())
+                    })
+                    .collect();
+                if let Some(entry) = active.iter().find(|e| e.binding.keys.0 == keys) {
+                    Match::Binding(entry.action.clone())
+                } else if active.iter().any(|e| e.binding.keys.0.starts_with(&keys)) {
+                    Match::Prefix
+                } else {
+                    Match::Nothing
+                }
+            };
+            match found {
+                Match::Binding(action) => return (true, Some(action)),
+                Match::Prefix => {
+                    self.pending = keys;
+                    return (true, None);
+                }
+                Match::Nothing if keys.len() > 1 => {
+                    // the sequence was broken, but the key may start another one
+                    keys = vec![key];
+                }
+                Match::Nothing => return (false, None),
+            }
+        }
+    }
+}
+
+/// Collect the elements at the root of the component `scope`.
+fn component_roots(vdom: &VirtualDom, scope: ScopeId, roots: &mut Vec<ElementId>) {
+    if let Some(scope) = vdom.get_scope(scope) {
+        node_roots(vdom, scope.root_node(), roots);
+    }
+}
+
+fn node_roots(vdom: &VirtualDom, node: &VNode, roots: &mut Vec<ElementId>) {
+    match node {
+        VNode::Fragment(fragment) => {
+            for child in fragment.children {
+                node_roots(vdom, child, roots);
+            }
+        }
+        VNode::Component(component) => {
+            if let Some(scope) = component.scope.get() {
+                component_roots(vdom, scope, roots);
+            }
+        }
+        _ => roots.extend(node.try_mounted_id()),
+    }
+}
+
+/// Run `action` when the keys of `binding` are pressed, until the component is removed. The
+/// binding and the action are replaced every time the component renders.
+///
+/// ```rust, ignore
+/// let count = use_state(&cx, || 0);
+/// let count2 = count.clone();
+/// use_keybinding(&cx, KeyBinding::new("ctrl+r", "Reset the counter"), move || count2.set(0));
+/// ```
+pub fn use_keybinding(cx: &ScopeState, binding: KeyBinding, action: impl Fn() + 'static) {
+    struct Registration {
+        id: KeyBindingId,
+        keymap: Rc<RefCell<Keymap>>,
+    }
+
+    impl Drop for Registration {
+        fn drop(&mut self) {
+            self.keymap.borrow_mut().remove(self.id);
+        }
+    }
+
+    let action: Action = Rc::new(action);
+    let registration = cx.use_hook(|| {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let keymap = tui_ctx.keymap;
+        let id = keymap.borrow_mut().add(binding.clone(), action.clone());
+        Registration { id, keymap }
+    });
+    registration
+        .keymap
+        .borrow_mut()
+        .replace(registration.id, binding, action);
+}
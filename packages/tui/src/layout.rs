@@ -8,6 +8,10 @@ use dioxus_native_core::state::ChildDepState;
 use dioxus_native_core_macro::sorted_str_slice;
 use taffy::prelude::*;
 
+use crate::form;
+use crate::stylesheet::Stylesheets;
+use crate::text::{self, WhiteSpace};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum PossiblyUninitalized<T> {
     Uninitalized,
@@ -37,10 +41,16 @@ impl<T> Default for PossiblyUninitalized<T> {
 pub(crate) struct TaffyLayout {
     pub style: Style,
     pub node: PossiblyUninitalized<Node>,
+    /// The text of a text node. The parent element measures it with its `white-space`.
+    pub text: Option<String>,
+    /// How the text directly inside of this element wraps.
+    pub white_space: WhiteSpace,
+    /// The width of the text directly inside of this node, used to size form controls.
+    pub text_width: usize,
 }
 
 impl ChildDepState for TaffyLayout {
-    type Ctx = Rc<RefCell<Taffy>>;
+    type Ctx = (Rc<RefCell<Taffy>>, Rc<RefCell<Stylesheets>>);
     type DepState = Self;
     // use tag to force this to be called when a node is built
     const NODE_MASK: NodeMask =
@@ -53,42 +63,51 @@ impl ChildDepState for TaffyLayout {
         &mut self,
         node: NodeView,
         children: impl Iterator<Item = &'a Self::DepState>,
-        ctx: &Self::Ctx,
+        (taffy, stylesheets): &Self::Ctx,
     ) -> bool
     where
         Self::DepState: 'a,
     {
         let mut changed = false;
-        let mut taffy = ctx.borrow_mut();
+        let mut taffy = taffy.borrow_mut();
         let mut style = Style::default();
         if let Some(text) = node.text() {
-            let char_len = text.chars().count();
-
-            style = Style {
-                size: Size {
-                    // characters are 1 point tall
-                    height: Dimension::Points(1.0),
-
-                    // text is as long as it is declared
-                    width: Dimension::Points(char_len as f32),
-                },
-                ..Default::default()
-            };
+            // text is sized by a measure function, so it can wrap to the width it is given
             if let PossiblyUninitalized::Initialized(n) = self.node {
-                if self.style != style {
-                    taffy.set_style(n, style).unwrap();
+                if self.text.as_deref() != Some(text) {
+                    taffy
+                        .set_measure(
+                            n,
+                            Some(text::measure(text.to_string(), WhiteSpace::default())),
+                        )
+                        .unwrap();
+                    changed = true;
                 }
             } else {
-                self.node = PossiblyUninitalized::Initialized(taffy.new_node(style, &[]).unwrap());
+                self.node = PossiblyUninitalized::Initialized(
+                    taffy
+                        .new_leaf(
+                            style,
+                            text::measure(text.to_string(), WhiteSpace::default()),
+                        )
+                        .unwrap(),
+                );
                 changed = true;
             }
+            if self.text.as_deref() != Some(text) {
+                self.text = Some(text.to_string());
+                self.text_width = text::width(text);
+            }
         } else {
-            // gather up all the styles from the attribute list
-            for Attribute { name, value, .. } in node.attributes() {
-                assert!(SORTED_LAYOUT_ATTRS.binary_search(name).is_ok());
-                if let Some(text) = value.as_text() {
-                    apply_layout_attributes(name, text, &mut style);
+            // gather up all the styles from the stylesheets and the attribute list
+            let stylesheets = stylesheets.borrow();
+            let declarations = stylesheets.declarations(&node);
+            let mut white_space = WhiteSpace::default();
+            for (name, value) in &declarations {
+                if *name == "white-space" {
+                    white_space = WhiteSpace::parse(value).unwrap_or_default();
                 }
+                apply_layout_attributes(name, value, &mut style);
             }
 
             // the root node fills the entire area
@@ -99,8 +118,75 @@ impl ChildDepState for TaffyLayout {
 
             // Set all direct nodes as our children
             let mut child_layout = vec![];
+            let mut child_styles = vec![];
+            let mut text_width = 0;
+            let mut options_width = 0;
             for l in children {
                 child_layout.push(l.node.unwrap());
+                child_styles.push(l.style);
+                if l.text.is_some() {
+                    text_width += l.text_width;
+                }
+                options_width = options_width.max(l.text_width);
+
+                // white-space is only read from the element the text is directly inside of
+                if let Some(text) = &l.text {
+                    taffy
+                        .set_measure(
+                            l.node.unwrap(),
+                            Some(text::measure(text.clone(), white_space)),
+                        )
+                        .unwrap();
+                }
+            }
+            if self.white_space != white_space {
+                self.white_space = white_space;
+                changed = true;
+            }
+            if self.text_width != text_width {
+                self.text_width = text_width;
+                changed = true;
+            }
+
+            // form controls have a default size like in a browser, and draw their content
+            // themselves instead of their children
+            let attribute = |name: &str| {
+                node.attributes()
+                    .find(|a| a.name == name)
+                    .and_then(|a| a.value.as_text())
+                    .map(str::to_string)
+            };
+            let tag = node.tag().unwrap_or_default();
+            if let Some([width, height]) = form::default_size(tag, attribute, options_width) {
+                let [left, top, right, bottom] = form::insets(&style).map(f32::from);
+                let unset = |d: Dimension| matches!(d, Dimension::Auto | Dimension::Undefined);
+                if unset(style.size.width) {
+                    style.size.width = Dimension::Points(width + left + right);
+                }
+                if unset(style.size.height) {
+                    style.size.height = Dimension::Points(height + top + bottom);
+                }
+            }
+            // Browsers don't shrink children below the size of their content, but taffy does. The
+            // content of a container that clips would be shrunk to fit instead of overflowing, so
+            // the children keep their size and the container can be scrolled.
+            let clips = declarations.iter().any(|(name, value)| {
+                matches!(*name, "overflow" | "overflow-x" | "overflow-y")
+                    && value.trim() != "visible"
+            });
+            let hides_children = matches!(tag, "select" | "textarea");
+            // the styles of the children depend on this element, so they are set again when it
+            // changes
+            for (child, mut child_style) in child_layout.iter().zip(child_styles) {
+                if hides_children {
+                    child_style.display = Display::None;
+                }
+                if clips {
+                    child_style.flex_shrink = 0.0;
+                }
+                if *taffy.style(*child).unwrap() != child_style {
+                    taffy.set_style(*child, child_style).unwrap();
+                }
             }
 
             if let PossiblyUninitalized::Initialized(n) = self.node {
@@ -179,6 +265,7 @@ const SORTED_LAYOUT_ATTRS: &[&str] = &sorted_str_slice!([
     "caption-side",
     "clear",
     "clip",
+    "cols",
     "column-count",
     "column-fill",
     "column-gap",
@@ -260,6 +347,8 @@ const SORTED_LAYOUT_ATTRS: &[&str] = &sorted_str_slice!([
     "quotes",
     "resize",
     "right",
+    "rows",
+    "size",
     "tab-size",
     "table-layout",
     "top",
@@ -271,6 +360,8 @@ const SORTED_LAYOUT_ATTRS: &[&str] = &sorted_str_slice!([
     "transition-duration",
     "transition-property",
     "transition-timing-function",
+    "type",
+    "value",
     "vertical-align",
     "visibility",
     "white-space",
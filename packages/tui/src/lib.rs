@@ -13,32 +13,55 @@ use futures::{
     channel::mpsc::{UnboundedReceiver, UnboundedSender},
     pin_mut, StreamExt,
 };
+use fxhash::FxHashSet;
+use inline::Inline;
+use keymap::Keymap;
 use query::Query;
+use render::Screen;
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::{io, time::Duration};
+use stylesheet::Stylesheets;
 use taffy::Taffy;
 pub use taffy::{geometry::Point, prelude::Size};
-use tui::{backend::CrosstermBackend, layout::Rect, Terminal};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    layout::Rect,
+    Terminal,
+};
 
 mod config;
 mod focus;
+mod form;
+mod headless;
 mod hooks;
+mod inline;
+mod keymap;
 mod layout;
 mod node;
 pub mod query;
 mod render;
+mod scroll;
+mod selection;
 mod style;
 mod style_attributes;
+mod stylesheet;
+mod text;
 mod widget;
 
 pub use config::*;
+pub use headless::Headless;
 pub use hooks::*;
+pub use keymap::{use_keybinding, KeyBinding, KeyBindingId, KeyPress, KeySequence, ParseKeyError};
 pub(crate) use node::*;
+pub use selection::{Clipboard, Osc52};
 
 #[derive(Clone)]
 pub struct TuiContext {
     tx: UnboundedSender<InputEvent>,
+    stylesheets: Rc<RefCell<Stylesheets>>,
+    keymap: Rc<RefCell<Keymap>>,
+    clipboard: Rc<RefCell<Box<dyn Clipboard>>>,
 }
 impl TuiContext {
     pub fn quit(&self) {
@@ -50,6 +73,52 @@ impl TuiContext {
             .unbounded_send(InputEvent::UserInput(event))
             .unwrap();
     }
+
+    /// Print `text` as lines above the app, where they scroll up with the rest of the terminal's
+    /// output. This only has an effect if the app is drawn inline, see [Config::with_inline].
+    pub fn print_above(&self, text: impl Into<String>) {
+        self.tx
+            .unbounded_send(InputEvent::Print(text.into()))
+            .unwrap();
+    }
+
+    /// Add a CSS stylesheet that styles elements by their type, class and id. Descendant and
+    /// child selectors, and the `:focus` and `:hover` pseudo-classes are supported. Rules that
+    /// can't be parsed are skipped and properties that are not supported are ignored, like in a
+    /// browser.
+    ///
+    /// The attributes of an element override the declarations from stylesheets, unless they are
+    /// `!important`. A stylesheet should be added once, not on every render:
+    ///
+    /// ```rust, ignore
+    /// cx.use_hook(|| tui_ctx.add_stylesheet(".selected { color: red; }"));
+    /// ```
+    pub fn add_stylesheet(&self, css: &str) {
+        self.stylesheets.borrow_mut().add(css);
+    }
+
+    /// Run `action` when the keys of `binding` are pressed, until the binding is removed. To add
+    /// a binding from a component, use [`use_keybinding`], which removes it with the component.
+    pub fn add_keybinding(&self, binding: KeyBinding, action: impl Fn() + 'static) -> KeyBindingId {
+        self.keymap.borrow_mut().add(binding, Rc::new(action))
+    }
+
+    pub fn remove_keybinding(&self, id: KeyBindingId) {
+        self.keymap.borrow_mut().remove(id);
+    }
+
+    /// Get the bindings that are active where the focus is, starting with the binding that is
+    /// used first. Bindings that are hidden by another binding for the same keys are left out.
+    /// This can be used to show the keys of the app in a help overlay.
+    pub fn keybindings(&self) -> Vec<KeyBinding> {
+        self.keymap.borrow().bindings()
+    }
+
+    /// Set where the text the user selects is copied to, if selection is enabled with
+    /// [Config::with_selection]. A closure that takes the text can be used as the clipboard.
+    pub fn set_clipboard(&self, clipboard: impl Clipboard + 'static) {
+        *self.clipboard.borrow_mut() = Box::new(clipboard);
+    }
 }
 
 pub fn launch(app: Component<()>) {
@@ -59,8 +128,6 @@ pub fn launch(app: Component<()>) {
 pub fn launch_cfg(app: Component<()>, cfg: Config) {
     let mut dom = VirtualDom::new(app);
 
-    let (handler, state, register_event) = RinkInputHandler::new();
-
     // Setup input handling
     let (event_tx, event_rx) = unbounded();
     let event_tx_clone = event_tx.clone();
@@ -78,37 +145,257 @@ pub fn launch_cfg(app: Component<()>, cfg: Config) {
         });
     }
 
+    let (handler, rdom, taffy, stylesheets, keymap, clipboard, register_event) =
+        init(&mut dom, event_tx_clone);
+
+    render_vdom(
+        &mut dom,
+        event_rx,
+        handler,
+        cfg,
+        rdom,
+        taffy,
+        stylesheets,
+        keymap,
+        clipboard,
+        register_event,
+    )
+    .unwrap();
+}
+
+/// Provide the root contexts and build the initial dom.
+#[allow(clippy::type_complexity)]
+fn init(
+    dom: &mut VirtualDom,
+    event_tx: UnboundedSender<InputEvent>,
+) -> (
+    RinkInputHandler,
+    Rc<RefCell<Dom>>,
+    Rc<RefCell<Taffy>>,
+    Rc<RefCell<Stylesheets>>,
+    Rc<RefCell<Keymap>>,
+    Rc<RefCell<Box<dyn Clipboard>>>,
+    impl FnMut(crossterm::event::Event),
+) {
+    let (handler, state, register_event) = RinkInputHandler::new();
+
     let cx = dom.base_scope();
     let rdom = Rc::new(RefCell::new(RealDom::new()));
     let taffy = Rc::new(RefCell::new(Taffy::new()));
-    cx.provide_root_context(state);
-    cx.provide_root_context(TuiContext { tx: event_tx_clone });
+    let stylesheets = Rc::new(RefCell::new(Stylesheets::default()));
+    let keymap = Rc::new(RefCell::new(Keymap::default()));
+    let clipboard: Rc<RefCell<Box<dyn Clipboard>>> = Rc::new(RefCell::new(Box::new(Osc52)));
+    cx.provide_root_context(state.clone());
+    cx.provide_root_context(TuiContext {
+        tx: event_tx,
+        stylesheets: stylesheets.clone(),
+        keymap: keymap.clone(),
+        clipboard: clipboard.clone(),
+    });
     cx.provide_root_context(Query {
         rdom: rdom.clone(),
         stretch: taffy.clone(),
+        input: state,
     });
 
     {
         let mut rdom = rdom.borrow_mut();
         let mutations = dom.rebuild();
-        let to_update = rdom.apply_mutations(vec![mutations]);
-        let mut any_map = AnyMap::new();
-        any_map.insert(taffy.clone());
-        let _to_rerender = rdom.update_state(&dom, to_update, any_map);
+        let mut to_update = rdom.apply_mutations(vec![mutations]);
+        stylesheets.borrow_mut().cascade(
+            dom,
+            &rdom,
+            &Screen::default(),
+            None,
+            None,
+            &mut to_update,
+        );
+        let to_rerender = rdom.update_state(dom, to_update, state_context(&taffy, &stylesheets));
+        form::sync(&mut rdom, &to_rerender);
+        keymap.borrow_mut().update(dom, &rdom, None);
     }
 
-    render_vdom(
-        &mut dom,
-        event_rx,
+    (
         handler,
-        cfg,
         rdom,
         taffy,
+        stylesheets,
+        keymap,
+        clipboard,
         register_event,
     )
-    .unwrap();
 }
 
+/// Get the context the state of the dom is computed with.
+fn state_context(taffy: &Rc<RefCell<Taffy>>, stylesheets: &Rc<RefCell<Stylesheets>>) -> AnyMap {
+    let mut any_map = AnyMap::new();
+    any_map.insert((taffy.clone(), stylesheets.clone()));
+    any_map.insert(stylesheets.clone());
+    any_map
+}
+
+/// Lay out the dom to fit into `dims`, leaving `margin` cells at the right and bottom edge empty.
+fn resize(dims: Rect, margin: u16, taffy: &mut Taffy, rdom: &Dom) {
+    let width = dims.width.saturating_sub(margin);
+    let height = dims.height.saturating_sub(margin);
+    let root_node = rdom[ElementId(0)].state.layout.node.unwrap();
+
+    taffy
+        .compute_layout(
+            root_node,
+            Size {
+                width: taffy::prelude::Number::Defined(width as f32),
+                height: taffy::prelude::Number::Defined(height as f32),
+            },
+        )
+        .unwrap();
+}
+
+/// Get the height of the content when the dom is laid out to be `width` cells wide.
+fn content_height(width: u16, taffy: &mut Taffy, rdom: &Dom) -> f32 {
+    let root_node = rdom[ElementId(0)].state.layout.node.unwrap();
+    taffy
+        .compute_layout(
+            root_node,
+            Size {
+                width: taffy::prelude::Number::Defined(width as f32),
+                height: taffy::prelude::Number::Undefined,
+            },
+        )
+        .unwrap();
+    taffy.layout(root_node).unwrap().size.height
+}
+
+/// Lay out the dom to fit into the terminal and draw it. Only the parts of the screen where the
+/// `changed` nodes are, or nodes moved, are repainted. If `changed` is `None`, everything is.
+fn draw<B: Backend>(
+    terminal: &mut Terminal<B>,
+    margin: u16,
+    rdom: &Dom,
+    taffy: &RefCell<Taffy>,
+    cfg: Config,
+    screen: &mut Screen,
+    changed: Option<&FxHashSet<ElementId>>,
+) -> io::Result<()> {
+    terminal.draw(|frame| {
+        // size is guaranteed to not change when rendering
+        let area = frame.size();
+        resize(area, margin, &mut taffy.borrow_mut(), rdom);
+        screen.update(area, &taffy.borrow(), rdom, cfg, changed);
+        // the terminal compares the frame to the last one, and only writes the cells that changed
+        frame.render_widget(&*screen, area);
+    })?;
+    Ok(())
+}
+
+/// Check whether an event from the terminal closes the app.
+fn is_quit(event: &TermEvent, cfg: &Config) -> bool {
+    match event {
+        TermEvent::Key(key) => {
+            matches!(key.code, KeyCode::Char('C' | 'c'))
+                && key.modifiers.contains(KeyModifiers::CONTROL)
+                && cfg.ctrl_c_quit
+        }
+        _ => false,
+    }
+}
+
+/// Let the key bindings handle an event from the terminal. Returns `true` if a binding used the
+/// event, so it is not sent to the app.
+fn handle_keybinding(event: &TermEvent, keymap: &RefCell<Keymap>) -> bool {
+    let key = match event {
+        TermEvent::Key(key) => KeyPress::from(*key),
+        _ => return false,
+    };
+    let (used, action) = keymap.borrow_mut().press(key);
+    // the action may change the bindings
+    if let Some(action) = action {
+        action();
+    }
+    used
+}
+
+/// Let the selection handle an event from the terminal, and copy the text if the user finished
+/// selecting it. Returns `true` if the selection changed. The event is still sent to the app.
+fn handle_selection(
+    event: &TermEvent,
+    screen: &mut Screen,
+    clipboard: &RefCell<Box<dyn Clipboard>>,
+    cfg: &Config,
+) -> bool {
+    if !cfg.selection {
+        return false;
+    }
+    let (changed, copy) = screen.select(event);
+    if let Some(text) = copy.filter(|text| !text.is_empty()) {
+        clipboard.borrow().copy(&text);
+    }
+    changed
+}
+
+/// Send the events that were registered to the VirtualDom, and apply the changes it made to the
+/// dom. Returns the nodes that changed, and whether the screen needs to be redrawn even if no
+/// nodes changed.
+fn update(
+    vdom: &mut VirtualDom,
+    handler: &RinkInputHandler,
+    rdom: &RefCell<Dom>,
+    taffy: &Rc<RefCell<Taffy>>,
+    stylesheets: &Rc<RefCell<Stylesheets>>,
+    keymap: &RefCell<Keymap>,
+    screen: &Screen,
+) -> (FxHashSet<ElementId>, bool) {
+    let mut updated = false;
+    let evts = {
+        let mut rdom = rdom.borrow_mut();
+        handler.get_events(&taffy.borrow(), &mut rdom, screen)
+    };
+    {
+        let mut state = handler.state();
+        updated |= state.focus_state.clean();
+        updated |= std::mem::take(&mut state.form_dirty);
+    }
+    for e in evts {
+        vdom.handle_message(SchedulerMsg::Event(e));
+    }
+    // send scroll events for user input and for scrolling from `Query`
+    let scroll_evts = {
+        let mut state = handler.state();
+        updated |= !state.scrolled.is_empty();
+        state.scroll_events(&rdom.borrow())
+    };
+    for e in scroll_evts {
+        vdom.handle_message(SchedulerMsg::Event(e));
+    }
+    let mut rdom = rdom.borrow_mut();
+    let mutations = vdom.work_with_deadline(|| false);
+    for m in &mutations {
+        handler.prune(m, &rdom);
+        stylesheets.borrow_mut().prune(m, &rdom);
+    }
+    // updates the dom's nodes
+    let mut to_update = rdom.apply_mutations(mutations);
+    // match the nodes that changed against the stylesheets
+    {
+        let state = handler.state();
+        stylesheets.borrow_mut().cascade(
+            vdom,
+            &rdom,
+            screen,
+            state.focus_state.last_focused_id,
+            state.mouse_position(),
+            &mut to_update,
+        );
+    }
+    // update the style and layout
+    let to_rerender = rdom.update_state(vdom, to_update, state_context(taffy, stylesheets));
+    form::sync(&mut rdom, &to_rerender);
+    let focused = handler.state().focus_state.last_focused_id;
+    keymap.borrow_mut().update(vdom, &rdom, focused);
+    (to_rerender, updated)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_vdom(
     vdom: &mut VirtualDom,
     mut event_reciever: UnboundedReceiver<InputEvent>,
@@ -116,26 +403,41 @@ fn render_vdom(
     cfg: Config,
     rdom: Rc<RefCell<Dom>>,
     taffy: Rc<RefCell<Taffy>>,
+    stylesheets: Rc<RefCell<Stylesheets>>,
+    keymap: Rc<RefCell<Keymap>>,
+    clipboard: Rc<RefCell<Box<dyn Clipboard>>>,
     mut register_event: impl FnMut(crossterm::event::Event),
 ) -> Result<()> {
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()?
         .block_on(async {
+            let mut inline = None;
             let mut terminal = (!cfg.headless).then(|| {
                 enable_raw_mode().unwrap();
-                let mut stdout = std::io::stdout();
-                execute!(stdout, EnterAlternateScreen, EnableMouseCapture).unwrap();
-                let backend = CrosstermBackend::new(io::stdout());
-                Terminal::new(backend).unwrap()
+                match cfg.inline {
+                    Some(height) => {
+                        let region = Inline::new(height).unwrap();
+                        let terminal = region.terminal().unwrap();
+                        inline = Some(region);
+                        terminal
+                    }
+                    None => {
+                        let mut stdout = std::io::stdout();
+                        execute!(stdout, EnterAlternateScreen, EnableMouseCapture).unwrap();
+                        let backend = CrosstermBackend::new(io::stdout());
+                        let mut terminal = Terminal::new(backend).unwrap();
+                        terminal.clear().unwrap();
+                        terminal
+                    }
+                }
             });
-            if let Some(terminal) = &mut terminal {
-                terminal.clear().unwrap();
-            }
 
-            let mut to_rerender: fxhash::FxHashSet<ElementId> =
-                vec![ElementId(0)].into_iter().collect();
+            let mut to_rerender: FxHashSet<ElementId> = vec![ElementId(0)].into_iter().collect();
             let mut updated = true;
+            // set if only the selection changed, which doesn't need to repaint anything
+            let mut selected = false;
+            let mut screen = Screen::default();
 
             loop {
                 /*
@@ -143,56 +445,52 @@ fn render_vdom(
                 -> wait for changes
                 -> resolve events
                 -> lazily update the layout and style based on nodes changed
-
-                use simd to compare lines for diffing?
-
-                todo: lazy re-rendering
                 */
 
-                if !to_rerender.is_empty() || updated {
+                if !to_rerender.is_empty() || updated || selected {
+                    // changes that are not in the nodes' state, like focus and resizing, repaint everything
+                    let changed = if updated { None } else { Some(&to_rerender) };
                     updated = false;
-                    fn resize(dims: Rect, taffy: &mut Taffy, rdom: &Dom) {
-                        let width = dims.width;
-                        let height = dims.height;
-                        let root_node = rdom[ElementId(0)].state.layout.node.unwrap();
-
-                        taffy
-                            .compute_layout(
-                                root_node,
-                                Size {
-                                    width: taffy::prelude::Number::Defined((width - 1) as f32),
-                                    height: taffy::prelude::Number::Defined((height - 1) as f32),
-                                },
-                            )
-                            .unwrap();
-                    }
-                    if let Some(terminal) = &mut terminal {
-                        terminal.draw(|frame| {
-                            let rdom = rdom.borrow();
-                            // size is guaranteed to not change when rendering
-                            resize(frame.size(), &mut taffy.borrow_mut(), &rdom);
-                            let root = &rdom[ElementId(0)];
-                            render::render_vnode(
-                                frame,
-                                &taffy.borrow(),
-                                &rdom,
-                                root,
-                                cfg,
-                                Point::zero(),
-                            );
-                        })?;
+                    selected = false;
+                    if let (Some(terminal), Some(inline)) = (&mut terminal, &mut inline) {
+                        let rows = inline.rows_for(|width| {
+                            content_height(width, &mut taffy.borrow_mut(), &rdom.borrow())
+                        });
+                        if rows != inline.rows() {
+                            inline.reserve(rows)?;
+                            *terminal = inline.terminal()?;
+                        }
+                        draw(
+                            terminal,
+                            0,
+                            &rdom.borrow(),
+                            &taffy,
+                            cfg,
+                            &mut screen,
+                            changed,
+                        )?;
+                    } else if let Some(terminal) = &mut terminal {
+                        // the last column and row are left empty
+                        draw(
+                            terminal,
+                            1,
+                            &rdom.borrow(),
+                            &taffy,
+                            cfg,
+                            &mut screen,
+                            changed,
+                        )?;
                     } else {
                         let rdom = rdom.borrow();
-                        resize(
-                            Rect {
-                                x: 0,
-                                y: 0,
-                                width: 100,
-                                height: 100,
-                            },
-                            &mut taffy.borrow_mut(),
-                            &rdom,
-                        );
+                        let area = Rect {
+                            x: 0,
+                            y: 0,
+                            width: 100,
+                            height: 100,
+                        };
+                        resize(area, 1, &mut taffy.borrow_mut(), &rdom);
+                        // nothing is shown, but mouse events are hit tested against the screen
+                        screen.update(area, &taffy.borrow(), &rdom, cfg, changed);
                     }
                 }
 
@@ -207,60 +505,67 @@ fn render_vdom(
                         }
                         Either::Right((evt, _o)) => {
                             match evt.as_ref().unwrap() {
-                                InputEvent::UserInput(event) => match event {
-                                    TermEvent::Key(key) => {
-                                        if matches!(key.code, KeyCode::Char('C' | 'c'))
-                                            && key.modifiers.contains(KeyModifiers::CONTROL)
-                                            && cfg.ctrl_c_quit
+                                InputEvent::UserInput(event) => {
+                                    if is_quit(event, &cfg) {
+                                        break;
+                                    }
+                                    if let TermEvent::Resize(_, height) = event {
+                                        if let (Some(terminal), Some(inline)) =
+                                            (&mut terminal, &mut inline)
                                         {
-                                            break;
+                                            inline.resize(*height)?;
+                                            *terminal = inline.terminal()?;
                                         }
+                                        updated = true;
                                     }
-                                    TermEvent::Resize(_, _) => updated = true,
-                                    TermEvent::Mouse(_) => {}
-                                },
+                                }
+                                InputEvent::Print(text) => {
+                                    if let (Some(terminal), Some(inline)) =
+                                        (&mut terminal, &mut inline)
+                                    {
+                                        inline.print_above(text)?;
+                                        *terminal = inline.terminal()?;
+                                        updated = true;
+                                    }
+                                }
                                 InputEvent::Close => break,
                             };
 
                             if let InputEvent::UserInput(evt) = evt.unwrap() {
-                                register_event(evt);
+                                selected |= handle_selection(&evt, &mut screen, &clipboard, &cfg);
+                                if !handle_keybinding(&evt, &keymap) {
+                                    register_event(evt);
+                                }
                             }
                         }
                     }
                 }
 
-                {
-                    let evts = {
-                        let mut rdom = rdom.borrow_mut();
-                        handler.get_events(&taffy.borrow(), &mut rdom)
-                    };
-                    {
-                        updated |= handler.state().focus_state.clean();
-                    }
-                    for e in evts {
-                        vdom.handle_message(SchedulerMsg::Event(e));
-                    }
-                    let mut rdom = rdom.borrow_mut();
-                    let mutations = vdom.work_with_deadline(|| false);
-                    for m in &mutations {
-                        handler.prune(m, &rdom);
-                    }
-                    // updates the dom's nodes
-                    let to_update = rdom.apply_mutations(mutations);
-                    // update the style and layout
-                    let mut any_map = AnyMap::new();
-                    any_map.insert(taffy.clone());
-                    to_rerender = rdom.update_state(vdom, to_update, any_map);
-                }
+                let (changed, needs_redraw) = update(
+                    vdom,
+                    &handler,
+                    &rdom,
+                    &taffy,
+                    &stylesheets,
+                    &keymap,
+                    &screen,
+                );
+                to_rerender = changed;
+                updated |= needs_redraw;
             }
 
             if let Some(terminal) = &mut terminal {
                 disable_raw_mode()?;
-                execute!(
-                    terminal.backend_mut(),
-                    LeaveAlternateScreen,
-                    DisableMouseCapture
-                )?;
+                if let Some(inline) = &inline {
+                    // the last frame stays in the terminal
+                    inline.finish()?;
+                } else {
+                    execute!(
+                        terminal.backend_mut(),
+                        LeaveAlternateScreen,
+                        DisableMouseCapture
+                    )?;
+                }
                 terminal.show_cursor()?;
             }
 
@@ -271,5 +576,6 @@ fn render_vdom(
 #[derive(Debug)]
 enum InputEvent {
     UserInput(TermEvent),
+    Print(String),
     Close,
 }
@@ -1,5 +1,7 @@
 use crate::focus::Focus;
+use crate::form::{ControlState, FormControl};
 use crate::layout::TaffyLayout;
+use crate::scroll::{Overflow, ScrollOffset};
 use crate::style_attributes::StyleModifier;
 use dioxus_native_core::{real_dom::RealDom, state::*};
 use dioxus_native_core_macro::{sorted_str_slice, State};
@@ -9,15 +11,21 @@ pub(crate) type Node = dioxus_native_core::real_dom::Node<NodeState>;
 
 #[derive(Debug, Clone, State, Default)]
 pub(crate) struct NodeState {
-    #[child_dep_state(layout, RefCell<Stretch>)]
+    #[child_dep_state(layout, (Rc<RefCell<Taffy>>, Rc<RefCell<Stylesheets>>))]
     pub layout: TaffyLayout,
-    #[parent_dep_state(style)]
+    #[parent_dep_state(style, Rc<RefCell<Stylesheets>>)]
     pub style: StyleModifier,
     #[node_dep_state()]
     pub prevent_default: PreventDefault,
     #[node_dep_state()]
     pub focus: Focus,
+    #[node_dep_state(NONE, Rc<RefCell<Stylesheets>>)]
+    pub overflow: Overflow,
+    #[node_dep_state()]
+    pub control: FormControl,
     pub focused: bool,
+    pub scroll: ScrollOffset,
+    pub control_state: ControlState,
 }
 
 #[derive(PartialEq, Debug, Clone)]
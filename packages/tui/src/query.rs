@@ -10,7 +10,7 @@ use taffy::{
     Taffy,
 };
 
-use crate::Dom;
+use crate::{scroll, Dom, InnerInputState};
 
 /// Allows querying the layout of nodes after rendering. It will only provide a correct value after a node is rendered.
 /// Provided as a root context for all tui applictions.
@@ -47,12 +47,47 @@ use crate::Dom;
 pub struct Query {
     pub(crate) rdom: Rc<RefCell<Dom>>,
     pub(crate) stretch: Rc<RefCell<Taffy>>,
+    pub(crate) input: Rc<RefCell<InnerInputState>>,
 }
 
 impl Query {
     pub fn get(&self, id: ElementId) -> ElementRef {
         ElementRef::new(self.rdom.borrow(), self.stretch.borrow(), id)
     }
+
+    /// Scroll the content of a node with `overflow` set so `offset` is in the top left corner.
+    /// The offset is limited to the size of the content. Returns `true` if the node scrolled.
+    ///
+    /// Panics if an [`ElementRef`] is alive.
+    pub fn scroll_to(&self, id: ElementId, offset: Point<f32>) -> bool {
+        let scrolled = scroll::scroll_to(
+            &mut self.rdom.borrow_mut(),
+            &self.stretch.borrow(),
+            id,
+            offset,
+        );
+        if scrolled {
+            self.input.borrow_mut().scrolled.insert(id);
+        }
+        scrolled
+    }
+
+    /// Scroll the content of a node with `overflow` set by `delta`.
+    /// The offset is limited to the size of the content. Returns `true` if the node scrolled.
+    ///
+    /// Panics if an [`ElementRef`] is alive.
+    pub fn scroll_by(&self, id: ElementId, delta: Point<f32>) -> bool {
+        let scrolled = scroll::scroll_by(
+            &mut self.rdom.borrow_mut(),
+            &self.stretch.borrow(),
+            id,
+            delta,
+        );
+        if scrolled {
+            self.input.borrow_mut().scrolled.insert(id);
+        }
+        scrolled
+    }
 }
 
 pub struct ElementRef<'a> {
@@ -77,6 +112,22 @@ impl<'a> ElementRef<'a> {
         })
     }
 
+    /// The offset the node scrolled its content by.
+    pub fn scroll_offset(&self) -> Point<u32> {
+        let offset = scroll::offset(&self.inner[self.id], &self.inner, &self.stretch);
+        Point {
+            x: offset.x as u32,
+            y: offset.y as u32,
+        }
+    }
+
+    /// The size of the content of the node, including the part scrolled out of view.
+    pub fn scroll_size(&self) -> Option<Size<u32>> {
+        self.layout()?;
+        let size = scroll::content_size(&self.inner[self.id], &self.inner, &self.stretch);
+        Some(size.map(|v| v as u32))
+    }
+
     pub fn layout(&self) -> Option<&Layout> {
         self.stretch
             .layout(self.inner[self.id].state.layout.node.ok()?)
@@ -1,42 +1,42 @@
-use std::io::Stdout;
+use crossterm::event::Event as TermEvent;
+use dioxus_core::ElementId;
+use dioxus_native_core::{real_dom::NodeType, traversable::Traversable};
+use fxhash::{FxHashMap, FxHashSet};
 use taffy::{
     geometry::Point,
     prelude::{Dimension, Layout, Size},
+    style::PositionType,
     Taffy,
 };
-use tui::{backend::CrosstermBackend, layout::Rect, style::Color};
+use tui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::{
+    form::ControlWidget,
+    scroll::{self, Scrollbars},
+    selection::{self, CellKind, Selection, UserSelect},
     style::{RinkColor, RinkStyle},
     style_attributes::{BorderEdge, BorderStyle},
+    text::{self, TextAlign, TextOverflow, WhiteSpace},
     widget::{RinkBuffer, RinkCell, RinkWidget, WidgetWithContext},
     Config, Dom, Node,
 };
 
 const RADIUS_MULTIPLIER: [f32; 2] = [1.0, 0.5];
 
-pub(crate) fn render_vnode(
-    frame: &mut tui::Frame<CrosstermBackend<Stdout>>,
-    layout: &Taffy,
+/// Draw the background, border, text or form control of `node`. Its children are drawn
+/// separately, in the order [`paint_order`] returns them.
+fn render_node(
+    buf: &mut Buffer,
     rdom: &Dom,
     node: &Node,
     cfg: Config,
-    parent_location: Point<f32>,
+    placement: &Placement,
+    clip: Rect,
 ) {
-    use dioxus_native_core::real_dom::NodeType;
-
-    if let NodeType::Placeholder = &node.node_type {
-        return;
-    }
-
-    let Layout {
-        mut location, size, ..
-    } = layout.layout(node.state.layout.node.unwrap()).unwrap();
-    location.x += parent_location.x;
-    location.y += parent_location.y;
-
-    let Point { x, y } = location;
-    let Size { width, height } = size;
+    let Placement { origin, size, .. } = *placement;
+    let area = clip_area(placement.area, clip);
 
     match &node.node_type {
         NodeType::Text { text } => {
@@ -44,44 +44,558 @@ pub(crate) fn render_vnode(
             struct Label<'a> {
                 text: &'a str,
                 style: RinkStyle,
+                white_space: WhiteSpace,
+                align: Option<TextAlign>,
+                overflow: TextOverflow,
             }
 
             impl<'a> RinkWidget for Label<'a> {
                 fn render(self, area: Rect, mut buf: RinkBuffer) {
-                    for (i, c) in self.text.char_indices() {
-                        let mut new_cell = RinkCell::default();
-                        new_cell.set_style(self.style);
-                        new_cell.symbol = c.to_string();
-                        buf.set(area.left() + i as u16, area.top(), new_cell);
+                    let width = area.width as usize;
+                    let lines = text::lines(self.text, self.white_space, Some(width));
+                    for (y, line) in lines.into_iter().enumerate() {
+                        let line = match self.overflow {
+                            TextOverflow::Clip => line,
+                            TextOverflow::Ellipsis => line.truncate(width, true),
+                        };
+                        let free = width.saturating_sub(line.width);
+                        let mut x = match self.align {
+                            None | Some(TextAlign::Left) => 0,
+                            Some(TextAlign::Center) => free / 2,
+                            Some(TextAlign::Right) => free,
+                        };
+                        for g in line.text.graphemes(true) {
+                            let w = g.width();
+                            // zero width graphemes can't be drawn on their own
+                            if w == 0 {
+                                continue;
+                            }
+                            let mut new_cell = RinkCell::default();
+                            new_cell.set_style(self.style);
+                            new_cell.symbol = g.to_string();
+                            buf.set(area.left() + x as u16, area.top() + y as u16, new_cell);
+                            x += w;
+                        }
                     }
                 }
             }
 
+            // white-space and text-overflow are read from the element the text is in
+            let parent = node.parent.map(|p| &rdom[p].state);
             let label = Label {
                 text,
                 style: node.state.style.core,
+                white_space: parent.map(|p| p.layout.white_space).unwrap_or_default(),
+                align: node.state.style.modifier.text_align,
+                overflow: parent
+                    .map(|p| p.style.modifier.text_overflow)
+                    .unwrap_or_default(),
             };
-            let area = Rect::new(x as u16, y as u16, *width as u16, *height as u16);
 
             // the renderer will panic if a node is rendered out of range even if the size is zero
             if area.width > 0 && area.height > 0 {
-                frame.render_widget(WidgetWithContext::new(label, cfg), area);
+                WidgetWithContext::new(label, cfg, origin, size).render(area, buf);
             }
         }
-        NodeType::Element { children, .. } => {
-            let area = Rect::new(x as u16, y as u16, *width as u16, *height as u16);
-
+        NodeType::Element { .. } => {
             // the renderer will panic if a node is rendered out of range even if the size is zero
             if area.width > 0 && area.height > 0 {
-                frame.render_widget(WidgetWithContext::new(node, cfg), area);
+                WidgetWithContext::new(node, cfg, origin, size).render(area, buf);
+                if let Some(control) = ControlWidget::new(node, rdom) {
+                    WidgetWithContext::new(control, cfg, origin, size).render(area, buf);
+                }
             }
+        }
+        NodeType::Placeholder => {}
+    }
+}
+
+/// Something that is drawn on the screen.
+enum Paint<'a> {
+    /// The background, border, text or form control of a node.
+    Node(&'a Node, Placement),
+    /// The scrollbars of an element, drawn over its children.
+    Scrollbars(&'a Node, Placement),
+}
+
+impl Paint<'_> {
+    /// Get the part of the screen the paint can draw on.
+    fn area(&self) -> Rect {
+        match self {
+            Paint::Node(_, placement) | Paint::Scrollbars(_, placement) => placement.area,
+        }
+    }
+
+    /// Draw the part of the paint inside of `clip`.
+    fn render(&self, buf: &mut Buffer, layout: &Taffy, rdom: &Dom, cfg: Config, clip: Rect) {
+        match self {
+            Paint::Node(node, placement) => render_node(buf, rdom, node, cfg, placement, clip),
+            Paint::Scrollbars(node, placement) => {
+                let area = clip_area(placement.area, clip);
+                if area.area() > 0 {
+                    if let Some(scrollbars) = Scrollbars::new(node, rdom, layout) {
+                        WidgetWithContext::new(scrollbars, cfg, placement.origin, placement.size)
+                            .render(area, buf);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get everything that is drawn for `node` and its descendants, from the bottom to the top.
+///
+/// Elements with a `z-index` or an absolute position are painted in their own layer, together
+/// with their descendants. The layers inside of an element are painted over the rest of its
+/// content in the order of their `z-index`, or under it if the `z-index` is negative. Layers with
+/// the same `z-index` are painted in the order they are in the tree.
+///
+/// Absolutely positioned elements are clipped to `positioned_clip`, the area the children of
+/// their nearest positioned ancestor are clipped to, instead of the area of their parent.
+fn paint_order<'a>(
+    layout: &Taffy,
+    rdom: &'a Dom,
+    node: &'a Node,
+    parent_location: Point<f32>,
+    clip: Rect,
+    positioned_clip: Rect,
+) -> Vec<Paint<'a>> {
+    let mut content = Vec::new();
+    let mut layers = Vec::new();
+    add_content(
+        layout,
+        rdom,
+        node,
+        parent_location,
+        clip,
+        positioned_clip,
+        &mut content,
+        &mut layers,
+    );
+
+    // the sort is stable, so layers with the same z-index stay in the order of the tree
+    layers.sort_by_key(|(z, _)| *z);
+    let below = layers.iter().take_while(|(z, _)| *z < 0).count();
+    let mut layers = layers.into_iter().map(|(_, layer)| layer);
+
+    // the background of the element is always at the bottom
+    let rest = content.split_off(content.len().min(1));
+    let mut paints = content;
+    layers
+        .by_ref()
+        .take(below)
+        .for_each(|layer| paints.extend(layer));
+    paints.extend(rest);
+    layers.for_each(|layer| paints.extend(layer));
+    paints
+}
 
+/// Add the paints of `node` and the descendants that are not in their own layer to `content`,
+/// and the layers of its other descendants to `layers`.
+#[allow(clippy::too_many_arguments)]
+fn add_content<'a>(
+    layout: &Taffy,
+    rdom: &'a Dom,
+    node: &'a Node,
+    parent_location: Point<f32>,
+    clip: Rect,
+    positioned_clip: Rect,
+    content: &mut Vec<Paint<'a>>,
+    layers: &mut Vec<(i32, Vec<Paint<'a>>)>,
+) {
+    if let NodeType::Placeholder = &node.node_type {
+        return;
+    }
+    // nodes that were just created are not laid out yet
+    let placement = match Placement::new(layout, node, parent_location, clip) {
+        Some(placement) => placement,
+        None => return,
+    };
+    content.push(Paint::Node(node, placement));
+
+    if let NodeType::Element { children, .. } = &node.node_type {
+        if let Some((location, clip)) = children_area(layout, rdom, node, placement.location, clip)
+        {
+            let positioned_clip = if node.state.style.modifier.positioned {
+                clip
+            } else {
+                positioned_clip
+            };
             for c in children {
-                render_vnode(frame, layout, rdom, &rdom[*c], cfg, location);
+                let child = &rdom[*c];
+                let clip = if is_absolute(child) {
+                    positioned_clip
+                } else {
+                    clip
+                };
+                match z_index(child) {
+                    Some(z) => {
+                        let layer =
+                            paint_order(layout, rdom, child, location, clip, positioned_clip);
+                        layers.push((z, layer));
+                    }
+                    None => add_content(
+                        layout,
+                        rdom,
+                        child,
+                        location,
+                        clip,
+                        positioned_clip,
+                        content,
+                        layers,
+                    ),
+                }
+            }
+        }
+
+        if node.state.overflow.clips() && placement.area.area() > 0 && !hides_children(node) {
+            content.push(Paint::Scrollbars(node, placement));
+        }
+    }
+}
+
+/// Get the `z-index` of the layer `node` is painted in, if it has its own layer. Absolutely
+/// positioned elements without a `z-index` are painted as if it was 0.
+fn z_index(node: &Node) -> Option<i32> {
+    node.state
+        .style
+        .modifier
+        .z_index
+        .or_else(|| if is_absolute(node) { Some(0) } else { None })
+}
+
+fn is_absolute(node: &Node) -> bool {
+    node.state.layout.style.position_type == PositionType::Absolute
+}
+
+/// The last frame that was drawn, and the areas the nodes covered in it. The next frame only
+/// repaints the parts of the screen that changed, and the terminal only writes the cells that are
+/// different from the last frame.
+#[derive(Default)]
+pub(crate) struct Screen {
+    buffer: Buffer,
+    /// The visible area of every node in the last frame.
+    areas: FxHashMap<ElementId, Rect>,
+    /// The elements in the last frame and where they are visible, from the bottom to the top.
+    /// Hit tests use them instead of walking the dom again.
+    hits: Vec<(ElementId, Rect)>,
+    /// What the cells of the last frame show, row by row. Only set if selection is enabled.
+    kinds: Vec<CellKind>,
+    selection: Option<Selection>,
+}
+
+/// If more rectangles are damaged, the rectangle around all of them is repainted at once instead
+/// of walking the dom for each of them.
+const MAX_DAMAGED_RECTS: usize = 8;
+
+impl Screen {
+    /// Paint the dom into `area`. If `changed` is `None` or the area is different from the last
+    /// frame, everything is repainted. Otherwise only the old and new areas of the nodes that
+    /// changed or moved are.
+    pub fn update(
+        &mut self,
+        area: Rect,
+        layout: &Taffy,
+        rdom: &Dom,
+        cfg: Config,
+        changed: Option<&FxHashSet<ElementId>>,
+    ) {
+        if changed.filter(|changed| changed.is_empty()).is_some() && self.buffer.area == area {
+            // nothing moved or changed, so the last frame can be used again
+            return;
+        }
+        let root = &rdom[ElementId(0)];
+        let origin = Point {
+            x: area.x as f32,
+            y: area.y as f32,
+        };
+        let paints = paint_order(layout, rdom, root, origin, area, area);
+        let areas = paints
+            .iter()
+            .filter_map(|paint| match paint {
+                Paint::Node(node, placement) => Some((node.id, placement.area)),
+                Paint::Scrollbars(..) => None,
+            })
+            .collect();
+
+        let damaged = match changed {
+            Some(changed) if self.buffer.area == area => self.damage(rdom, &areas, changed),
+            _ => {
+                self.buffer = Buffer::empty(area);
+                vec![area]
+            }
+        };
+        for rect in &damaged {
+            for y in rect.top()..rect.bottom() {
+                for x in rect.left()..rect.right() {
+                    self.buffer.get_mut(x, y).reset();
+                }
             }
         }
-        NodeType::Placeholder => unreachable!(),
+        // only the paints that overlap a damaged rectangle are drawn, clipped to it
+        for paint in &paints {
+            let paint_area = paint.area();
+            for rect in &damaged {
+                if paint_area.intersects(*rect) {
+                    paint.render(&mut self.buffer, layout, rdom, cfg, *rect);
+                }
+            }
+        }
+        self.areas = areas;
+        self.hits = paints
+            .iter()
+            .filter_map(|paint| match paint {
+                Paint::Node(node, placement)
+                    if matches!(node.node_type, NodeType::Element { .. })
+                        && placement.area.area() > 0 =>
+                {
+                    Some((node.id, placement.area))
+                }
+                _ => None,
+            })
+            .collect();
+        if cfg.selection {
+            self.kinds = cell_kinds(area, &paints);
+        }
+    }
+
+    /// Update the selection with an event from the terminal. Returns `true` if it changed, and
+    /// the text to copy if the user finished selecting it.
+    pub fn select(&mut self, event: &TermEvent) -> (bool, Option<String>) {
+        Selection::handle(&mut self.selection, event, &self.buffer, &self.kinds)
+    }
+
+    /// Get the topmost element that was drawn at `(x, y)` in the last frame, if it is still in
+    /// the dom.
+    pub fn element_at(&self, rdom: &Dom, x: f64, y: f64) -> Option<ElementId> {
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let (x, y) = (x as u16, y as u16);
+        self.hits
+            .iter()
+            .rev()
+            .find(|(id, area)| {
+                x >= area.left()
+                    && x < area.right()
+                    && y >= area.top()
+                    && y < area.bottom()
+                    && rdom.get(*id).is_some()
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Get the topmost element that was drawn at `(x, y)` in the last frame and the elements it
+    /// is inside of.
+    pub fn elements_at(&self, rdom: &Dom, x: f64, y: f64) -> FxHashSet<ElementId> {
+        let mut elements = FxHashSet::default();
+        let mut current = self.element_at(rdom, x, y);
+        while let Some(id) = current {
+            elements.insert(id);
+            current = rdom[id].parent;
+        }
+        elements
+    }
+
+    /// Get the text that is selected.
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection
+            .map(|selection| selection.text(&self.buffer, &self.kinds))
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Get the rectangles that need to be repainted, without overlaps.
+    fn damage(
+        &self,
+        rdom: &Dom,
+        areas: &FxHashMap<ElementId, Rect>,
+        changed: &FxHashSet<ElementId>,
+    ) -> Vec<Rect> {
+        let mut damaged = Vec::new();
+        for (id, area) in areas {
+            // text is drawn with the white-space and text-overflow of the element it is in
+            let node = &rdom[*id];
+            let changed = changed.contains(id)
+                || matches!(node.node_type, NodeType::Text { .. })
+                    && node.parent.filter(|p| changed.contains(p)).is_some();
+            match self.areas.get(id) {
+                Some(old) if old == area && !changed => {}
+                old => {
+                    damaged.extend(old);
+                    damaged.push(*area);
+                }
+            }
+        }
+        // removed nodes leave their old area behind
+        for (id, old) in &self.areas {
+            if !areas.contains_key(id) {
+                damaged.push(*old);
+            }
+        }
+        damaged.retain(|rect| rect.area() > 0);
+
+        let mut merged: Vec<Rect> = Vec::new();
+        while let Some(mut rect) = damaged.pop() {
+            // a union can overlap rectangles that didn't overlap its parts
+            while let Some(i) = merged.iter().position(|m| m.intersects(rect)) {
+                rect = rect.union(merged.swap_remove(i));
+            }
+            merged.push(rect);
+        }
+        if merged.len() > MAX_DAMAGED_RECTS {
+            let all = merged.iter().fold(merged[0], |all, rect| all.union(*rect));
+            merged = vec![all];
+        }
+        merged
+    }
+}
+
+/// Get what each cell in `area` shows after painting `paints`, row by row. Text covers what is
+/// beneath it, like opaque backgrounds do.
+fn cell_kinds(area: Rect, paints: &[Paint]) -> Vec<CellKind> {
+    let mut kinds = vec![CellKind::Empty; area.area() as usize];
+    for paint in paints {
+        let (node, placement) = match paint {
+            Paint::Node(node, placement) => (node, placement),
+            Paint::Scrollbars(..) => continue,
+        };
+        let style = &node.state.style;
+        let selectable = style.modifier.user_select != Some(UserSelect::None);
+        let opaque = style.core.bg.filter(|bg| bg.alpha == 255).is_some();
+        let kind = match (&node.node_type, selectable) {
+            (_, false) => CellKind::Unselectable,
+            (NodeType::Text { .. }, true) => CellKind::Text,
+            _ if opaque => CellKind::Empty,
+            _ => continue,
+        };
+        let covered = clip_area(placement.area, area);
+        for y in covered.top()..covered.bottom() {
+            for x in covered.left()..covered.right() {
+                kinds[selection::index(area, x, y)] = kind;
+            }
+        }
+    }
+    kinds
+}
+
+/// Copies the last frame into the terminal's buffer, and highlights the selection.
+impl Widget for &Screen {
+    fn render(self, _: Rect, buf: &mut Buffer) {
+        buf.content.clone_from(&self.buffer.content);
+        if let Some(selection) = &self.selection {
+            selection.highlight(buf, &self.kinds);
+        }
+    }
+}
+
+/// Where a node is drawn on the screen.
+#[derive(Clone, Copy)]
+struct Placement {
+    /// The position of the node, used to place its children.
+    location: Point<f32>,
+    origin: [i32; 2],
+    size: [u16; 2],
+    /// The part of the node that is on screen.
+    area: Rect,
+}
+
+impl Placement {
+    fn new(layout: &Taffy, node: &Node, parent_location: Point<f32>, clip: Rect) -> Option<Self> {
+        let Layout {
+            mut location, size, ..
+        } = layout.layout(node.state.layout.node.ok()?).ok()?;
+        location.x += parent_location.x;
+        location.y += parent_location.y;
+
+        let Point { x, y } = location;
+        let Size { width, height } = size;
+        let origin = [x as i32, y as i32];
+        let size = [*width as u16, *height as u16];
+        Some(Self {
+            location,
+            origin,
+            size,
+            area: visible_area(origin, size, clip),
+        })
+    }
+}
+
+fn hides_children(node: &Node) -> bool {
+    matches!(node.state.control.kind, Some(kind) if kind.hides_children())
+}
+
+/// Get where the children of an element at `location` are placed, and the area they are clipped
+/// to. Returns `None` if the children are not drawn.
+fn children_area(
+    layout: &Taffy,
+    rdom: &Dom,
+    node: &Node,
+    location: Point<f32>,
+    clip: Rect,
+) -> Option<(Point<f32>, Rect)> {
+    if hides_children(node) {
+        // the control draws its content instead
+        return None;
+    }
+    if !node.state.overflow.clips() {
+        return Some((location, clip));
+    }
+
+    // children are clipped to the box inside of the border
+    let Layout { size, .. } = layout.layout(node.state.layout.node.unwrap()).unwrap();
+    let style = &node.state.layout.style;
+    let inset = |d: Dimension| match d {
+        Dimension::Points(p) => p as i32,
+        Dimension::Percent(p) => (p * size.width / 100.0) as i32,
+        _ => 0,
+    };
+    let (left, top) = (inset(style.border.start), inset(style.border.top));
+    let (right, bottom) = (inset(style.border.end), inset(style.border.bottom));
+    let inner = visible_area(
+        [location.x as i32 + left, location.y as i32 + top],
+        [
+            (size.width as i32 - left - right).max(0) as u16,
+            (size.height as i32 - top - bottom).max(0) as u16,
+        ],
+        clip,
+    );
+
+    let offset = scroll::offset(node, rdom, layout);
+    let scrolled = Point {
+        x: location.x - offset.x,
+        y: location.y - offset.y,
+    };
+    if inner.area() > 0 {
+        Some((scrolled, inner))
+    } else {
+        None
+    }
+}
+
+/// Get the part of `area` that lies inside of `clip`.
+fn clip_area(area: Rect, clip: Rect) -> Rect {
+    visible_area(
+        [area.x as i32, area.y as i32],
+        [area.width, area.height],
+        clip,
+    )
+}
+
+/// Get the part of a node at `origin` with the size `size` that lies inside of `clip`.
+fn visible_area(origin: [i32; 2], size: [u16; 2], clip: Rect) -> Rect {
+    let left = origin[0].max(clip.left() as i32);
+    let top = origin[1].max(clip.top() as i32);
+    let right = (origin[0] + size[0] as i32).min(clip.right() as i32);
+    let bottom = (origin[1] + size[1] as i32).min(clip.bottom() as i32);
+    if right <= left || bottom <= top {
+        return Rect::default();
     }
+    Rect::new(
+        left as u16,
+        top as u16,
+        (right - left) as u16,
+        (bottom - top) as u16,
+    )
 }
 
 impl RinkWidget for &Node {
@@ -0,0 +1,386 @@
+use crate::{stylesheet::Stylesheets, Dom, Node};
+use dioxus_core::ElementId;
+use dioxus_html::input_data::keyboard_types::Code;
+use dioxus_native_core::{
+    node_ref::{AttributeMask, NodeMask, NodeView},
+    real_dom::NodeType,
+    state::NodeDepState,
+};
+use dioxus_native_core_macro::sorted_str_slice;
+use std::{cell::RefCell, rc::Rc};
+use taffy::{
+    geometry::Point,
+    prelude::{Dimension, Size},
+    Taffy,
+};
+use tui::{layout::Rect, symbols::line};
+
+use crate::widget::{RinkBuffer, RinkCell, RinkWidget};
+
+/// How a node handles content that doesn't fit into it, along one axis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum OverflowKind {
+    /// The content is drawn outside of the node.
+    Visible,
+    /// The content is clipped, and can only be scrolled programmatically.
+    Hidden,
+    /// The content is clipped, and can be scrolled. A scrollbar is always drawn.
+    Scroll,
+    /// The content is clipped, and can be scrolled. A scrollbar is drawn if the content overflows.
+    Auto,
+}
+
+impl Default for OverflowKind {
+    fn default() -> Self {
+        OverflowKind::Visible
+    }
+}
+
+impl OverflowKind {
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value.trim() {
+            "visible" => OverflowKind::Visible,
+            "hidden" | "clip" => OverflowKind::Hidden,
+            "scroll" => OverflowKind::Scroll,
+            "auto" | "overlay" => OverflowKind::Auto,
+            _ => return None,
+        })
+    }
+
+    pub fn clips(&self) -> bool {
+        *self != OverflowKind::Visible
+    }
+
+    /// Check whether the user can scroll the content with the mouse or keyboard.
+    pub fn user_scrollable(&self) -> bool {
+        matches!(self, OverflowKind::Scroll | OverflowKind::Auto)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct Overflow {
+    pub x: OverflowKind,
+    pub y: OverflowKind,
+    /// Set by `scrollbar-width: none`.
+    pub hide_scrollbars: bool,
+}
+
+impl Overflow {
+    pub fn clips(&self) -> bool {
+        self.x.clips() || self.y.clips()
+    }
+}
+
+impl NodeDepState<()> for Overflow {
+    type Ctx = Rc<RefCell<Stylesheets>>;
+    const NODE_MASK: NodeMask = NodeMask::new_with_attrs(AttributeMask::Static(OVERFLOW_ATTRS));
+
+    fn reduce(&mut self, node: NodeView<'_>, _sibling: (), stylesheets: &Self::Ctx) -> bool {
+        let mut new = Overflow::default();
+        let stylesheets = stylesheets.borrow();
+        let declarations = stylesheets.declarations(&node);
+        // the last declaration of a property wins
+        let attribute = |name| {
+            declarations
+                .iter()
+                .rev()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| *value)
+        };
+
+        if let Some(value) = attribute("overflow") {
+            let mut values = value.split_whitespace().filter_map(OverflowKind::parse);
+            if let Some(x) = values.next() {
+                new.x = x;
+                new.y = values.next().unwrap_or(x);
+            }
+        }
+        if let Some(x) = attribute("overflow-x").and_then(OverflowKind::parse) {
+            new.x = x;
+        }
+        if let Some(y) = attribute("overflow-y").and_then(OverflowKind::parse) {
+            new.y = y;
+        }
+        new.hide_scrollbars = attribute("scrollbar-width").map(str::trim) == Some("none");
+
+        if *self != new {
+            *self = new;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub(crate) const OVERFLOW_ATTRS: &[&str] =
+    &sorted_str_slice!(["overflow", "overflow-x", "overflow-y", "scrollbar-width"]);
+
+/// The offset a node scrolled its content by.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct ScrollOffset {
+    pub x: f32,
+    pub y: f32,
+}
+
+fn points(dimension: Dimension, parent: f32) -> f32 {
+    match dimension {
+        Dimension::Points(p) => p,
+        Dimension::Percent(p) => p * parent / 100.0,
+        _ => 0.0,
+    }
+}
+
+/// Get the size of the content of `node`, including the padding and border after it.
+pub(crate) fn content_size(node: &Node, rdom: &Dom, taffy: &Taffy) -> Size<f32> {
+    let layout = match node.state.layout.node.ok() {
+        Some(n) => *taffy.layout(n).unwrap(),
+        None => return Size::zero(),
+    };
+    let mut size = layout.size;
+
+    if let NodeType::Element { children, .. } = &node.node_type {
+        let style = &node.state.layout.style;
+        let end = points(style.padding.end, layout.size.width)
+            + points(style.border.end, layout.size.width);
+        let bottom = points(style.padding.bottom, layout.size.width)
+            + points(style.border.bottom, layout.size.width);
+
+        for child in children {
+            if let Some(n) = rdom[*child].state.layout.node.ok() {
+                let child = taffy.layout(n).unwrap();
+                size.width = size.width.max(child.location.x + child.size.width + end);
+                size.height = size
+                    .height
+                    .max(child.location.y + child.size.height + bottom);
+            }
+        }
+    }
+    size
+}
+
+/// Get the largest offset `node` can scroll its content by.
+pub(crate) fn max_offset(node: &Node, rdom: &Dom, taffy: &Taffy) -> ScrollOffset {
+    let overflow = node.state.overflow;
+    if !overflow.clips() {
+        return ScrollOffset::default();
+    }
+    let size = match node.state.layout.node.ok() {
+        Some(n) => taffy.layout(n).unwrap().size,
+        None => return ScrollOffset::default(),
+    };
+    let content = content_size(node, rdom, taffy);
+    ScrollOffset {
+        x: if overflow.x.clips() {
+            (content.width - size.width).max(0.0)
+        } else {
+            0.0
+        },
+        y: if overflow.y.clips() {
+            (content.height - size.height).max(0.0)
+        } else {
+            0.0
+        },
+    }
+}
+
+/// Get the offset `node` scrolls its content by, limited to the current size of the content.
+pub(crate) fn offset(node: &Node, rdom: &Dom, taffy: &Taffy) -> ScrollOffset {
+    let offset = node.state.scroll;
+    if offset == ScrollOffset::default() {
+        return offset;
+    }
+    let max = max_offset(node, rdom, taffy);
+    ScrollOffset {
+        x: offset.x.min(max.x),
+        y: offset.y.min(max.y),
+    }
+}
+
+/// Scroll `id` to `target`, limited to the size of its content. Returns `true` if the offset
+/// changed.
+pub(crate) fn scroll_to(rdom: &mut Dom, taffy: &Taffy, id: ElementId, target: Point<f32>) -> bool {
+    let max = max_offset(&rdom[id], rdom, taffy);
+    let new = ScrollOffset {
+        x: target.x.clamp(0.0, max.x).round(),
+        y: target.y.clamp(0.0, max.y).round(),
+    };
+    let current = offset(&rdom[id], rdom, taffy);
+    rdom[id].state.scroll = new;
+    new != current
+}
+
+/// Scroll `id` by `delta`, limited to the size of its content. Returns `true` if the offset
+/// changed.
+pub(crate) fn scroll_by(rdom: &mut Dom, taffy: &Taffy, id: ElementId, delta: Point<f32>) -> bool {
+    let current = offset(&rdom[id], rdom, taffy);
+    let target = Point {
+        x: current.x + delta.x,
+        y: current.y + delta.y,
+    };
+    scroll_to(rdom, taffy, id, target)
+}
+
+/// Find the node the user scrolls by `delta` when starting from `id`: the closest node that can be
+/// scrolled further in that direction.
+pub(crate) fn scroll_target(
+    rdom: &Dom,
+    taffy: &Taffy,
+    id: ElementId,
+    delta: Point<f32>,
+) -> Option<ElementId> {
+    let mut current = Some(id);
+    while let Some(id) = current {
+        let node = &rdom[id];
+        let overflow = node.state.overflow;
+        if overflow.x.user_scrollable() || overflow.y.user_scrollable() {
+            let offset = offset(node, rdom, taffy);
+            let max = max_offset(node, rdom, taffy);
+            let can_scroll_x = overflow.x.user_scrollable()
+                && ((delta.x < 0.0 && offset.x > 0.0) || (delta.x > 0.0 && offset.x < max.x));
+            let can_scroll_y = overflow.y.user_scrollable()
+                && ((delta.y < 0.0 && offset.y > 0.0) || (delta.y > 0.0 && offset.y < max.y));
+            if can_scroll_x || can_scroll_y {
+                return Some(id);
+            }
+        }
+        current = node.parent;
+    }
+    None
+}
+
+/// Scroll the content around the focused node `focused` for a key press. Returns the node that
+/// scrolled.
+pub(crate) fn scroll_for_key(
+    rdom: &mut Dom,
+    taffy: &Taffy,
+    focused: ElementId,
+    code: Code,
+) -> Option<ElementId> {
+    // the page size is the height of the container, which is only known once it is found
+    const PAGE: f32 = f32::INFINITY;
+    let delta = match code {
+        Code::ArrowUp => Point { x: 0.0, y: -1.0 },
+        Code::ArrowDown => Point { x: 0.0, y: 1.0 },
+        Code::ArrowLeft => Point { x: -1.0, y: 0.0 },
+        Code::ArrowRight => Point { x: 1.0, y: 0.0 },
+        Code::PageUp => Point { x: 0.0, y: -PAGE },
+        Code::PageDown => Point { x: 0.0, y: PAGE },
+        Code::Home => Point {
+            x: 0.0,
+            y: -f32::MAX,
+        },
+        Code::End => Point {
+            x: 0.0,
+            y: f32::MAX,
+        },
+        _ => return None,
+    };
+
+    let id = scroll_target(rdom, taffy, focused, delta)?;
+    let delta = if delta.y.is_infinite() {
+        // keep one line of the previous page visible
+        let height = taffy
+            .layout(rdom[id].state.layout.node.unwrap())
+            .unwrap()
+            .size
+            .height;
+        Point {
+            x: 0.0,
+            y: delta.y.signum() * (height - 1.0).max(1.0),
+        }
+    } else {
+        delta
+    };
+    if scroll_by(rdom, taffy, id, delta) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// The scrollbars of a scroll container, drawn over the last column and row of the container.
+pub(crate) struct Scrollbars {
+    pub offset: ScrollOffset,
+    pub max: ScrollOffset,
+    pub vertical: bool,
+    pub horizontal: bool,
+    pub style: crate::style::RinkStyle,
+}
+
+impl Scrollbars {
+    pub fn new(node: &Node, rdom: &Dom, taffy: &Taffy) -> Option<Self> {
+        let overflow = node.state.overflow;
+        if overflow.hide_scrollbars {
+            return None;
+        }
+        let max = max_offset(node, rdom, taffy);
+        let shown = |kind, max: f32| match kind {
+            OverflowKind::Scroll => true,
+            OverflowKind::Auto => max > 0.0,
+            _ => false,
+        };
+        let vertical = shown(overflow.y, max.y);
+        let horizontal = shown(overflow.x, max.x);
+        (vertical || horizontal).then(|| Scrollbars {
+            offset: offset(node, rdom, taffy),
+            max,
+            vertical,
+            horizontal,
+            style: node.state.style.core,
+        })
+    }
+}
+
+/// Get the start and length of the thumb of a scrollbar `track` cells long. An empty track has
+/// no thumb.
+fn thumb(track: u16, offset: f32, max: f32) -> (u16, u16) {
+    if track == 0 {
+        return (0, 0);
+    }
+    let track_f = track as f32;
+    let content = track_f + max;
+    let len = ((track_f * track_f / content).round() as u16).clamp(1, track);
+    let start = if max > 0.0 {
+        ((offset / max) * (track - len) as f32).round() as u16
+    } else {
+        0
+    };
+    (start, len)
+}
+
+impl RinkWidget for Scrollbars {
+    fn render(self, area: Rect, mut buf: RinkBuffer<'_>) {
+        let mut cell = RinkCell::default();
+        cell.set_style(self.style);
+
+        if self.vertical && area.height > 0 {
+            let track = area.height - self.horizontal as u16;
+            let (start, len) = thumb(track, self.offset.y, self.max.y);
+            for y in 0..track {
+                let in_thumb = (start..start + len).contains(&y);
+                cell.symbol = if in_thumb {
+                    line::THICK_VERTICAL
+                } else {
+                    line::VERTICAL
+                }
+                .to_string();
+                buf.set(area.right() - 1, area.top() + y, cell.clone());
+            }
+        }
+
+        if self.horizontal && area.width > 0 {
+            let track = area.width - self.vertical as u16;
+            let (start, len) = thumb(track, self.offset.x, self.max.x);
+            for x in 0..track {
+                let in_thumb = (start..start + len).contains(&x);
+                cell.symbol = if in_thumb {
+                    line::THICK_HORIZONTAL
+                } else {
+                    line::HORIZONTAL
+                }
+                .to_string();
+                buf.set(area.left() + x, area.bottom() - 1, cell.clone());
+            }
+        }
+    }
+}
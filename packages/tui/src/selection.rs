@@ -0,0 +1,229 @@
+//! Selecting text with the mouse and copying it to the clipboard.
+//!
+//! Mouse capture keeps the terminal from selecting text on its own, so the selection is drawn and
+//! copied by the app instead. It is enabled with [`crate::Config::with_selection`].
+
+use std::io::{self, Write};
+
+use crossterm::event::{Event as TermEvent, MouseButton, MouseEventKind};
+use tui::{buffer::Buffer, layout::Rect, style::Modifier};
+use unicode_width::UnicodeWidthStr;
+
+/// Whether the text in an element can be selected, set with the `user-select` attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UserSelect {
+    Text,
+    None,
+}
+
+impl UserSelect {
+    /// Parse the value of the `user-select` attribute. `auto` is `None`, so the value of the
+    /// parent element is used.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "text" | "all" | "contain" => Some(UserSelect::Text),
+            "none" => Some(UserSelect::None),
+            _ => None,
+        }
+    }
+}
+
+/// Where the selected text is copied to when the mouse button is released. Set it with
+/// [`crate::TuiContext::set_clipboard`]; the default is [`Osc52`].
+pub trait Clipboard {
+    fn copy(&self, text: &str);
+}
+
+impl<F: Fn(&str)> Clipboard for F {
+    fn copy(&self, text: &str) {
+        self(text)
+    }
+}
+
+/// Copies text with the OSC 52 escape sequence, which asks the terminal to put it into the system
+/// clipboard. It works over ssh, but some terminals ignore it or need it to be turned on.
+pub struct Osc52;
+
+impl Clipboard for Osc52 {
+    fn copy(&self, text: &str) {
+        let mut stdout = io::stdout();
+        // there is nothing to do if the terminal is gone
+        let _ = write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()));
+        let _ = stdout.flush();
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, b)| group | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// What a cell on the screen shows, as far as selecting it is concerned.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum CellKind {
+    /// A background, border or form control.
+    Empty,
+    /// Text that can be selected.
+    Text,
+    /// Something in an element with `user-select: none`.
+    Unselectable,
+}
+
+/// The cells selected with the mouse, from where the button was pressed to where it is now.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Selection {
+    anchor: (u16, u16),
+    focus: (u16, u16),
+    /// Set while the button is held down.
+    dragging: bool,
+}
+
+impl Selection {
+    /// Update the selection with a mouse event. `kinds` are the kinds of the cells in `buffer`,
+    /// row by row. Returns `true` if the selection changed, and the selected text if the
+    /// selection is finished and should be copied.
+    pub fn handle(
+        selection: &mut Option<Selection>,
+        event: &TermEvent,
+        buffer: &Buffer,
+        kinds: &[CellKind],
+    ) -> (bool, Option<String>) {
+        let old = *selection;
+        let mut copy = None;
+        let area = buffer.area;
+        match event {
+            TermEvent::Mouse(mouse) if area.area() > 0 => {
+                let x = mouse.column.clamp(area.left(), area.right() - 1);
+                let y = mouse.row.clamp(area.top(), area.bottom() - 1);
+                let dragging = selection.as_mut().filter(|s| s.dragging);
+                match (mouse.kind, dragging) {
+                    (MouseEventKind::Down(MouseButton::Left), _) => {
+                        // selections can't start in text that can't be selected
+                        let kind = kinds.get(index(area, x, y));
+                        *selection = if kind != Some(&CellKind::Unselectable) {
+                            Some(Selection {
+                                anchor: (x, y),
+                                focus: (x, y),
+                                dragging: true,
+                            })
+                        } else {
+                            None
+                        };
+                    }
+                    (MouseEventKind::Drag(MouseButton::Left), Some(s)) => s.focus = (x, y),
+                    (MouseEventKind::Up(MouseButton::Left), Some(s)) => {
+                        s.dragging = false;
+                        s.focus = (x, y);
+                        if s.anchor == s.focus {
+                            // a click doesn't select anything
+                            *selection = None;
+                        } else {
+                            copy = Some(s.text(buffer, kinds));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // the selected cells don't show the same text anymore
+            TermEvent::Resize(..) => *selection = None,
+            _ => {}
+        }
+        (*selection != old, copy)
+    }
+
+    /// Get the first and last selected cell, in reading order.
+    fn range(&self) -> ((u16, u16), (u16, u16)) {
+        let [start, end] = {
+            let mut ends = [self.anchor, self.focus];
+            ends.sort_by_key(|(x, y)| (*y, *x));
+            ends
+        };
+        (start, end)
+    }
+
+    /// Get the selected text cells of each line, without the blank cells at the end of it.
+    fn lines(&self, buffer: &Buffer, kinds: &[CellKind]) -> Vec<Vec<(u16, u16)>> {
+        let area = buffer.area;
+        if self.anchor == self.focus {
+            return Vec::new();
+        }
+        let (start, end) = self.range();
+        (start.1..=end.1.min(area.bottom().saturating_sub(1)))
+            .map(|y| {
+                let from = if y == start.1 { start.0 } else { area.left() };
+                let to = if y == end.1 { end.0 + 1 } else { area.right() };
+                let mut cells = Vec::new();
+                let mut covered = 0;
+                for x in area.left()..area.right() {
+                    let cell = buffer.get(x, y);
+                    // wide characters cover the cells after them
+                    if covered > 0 {
+                        covered -= 1;
+                        continue;
+                    }
+                    covered = cell.symbol.width().saturating_sub(1);
+                    let kind = kinds.get(index(area, x, y));
+                    if (from..to).contains(&x) && kind == Some(&CellKind::Text) {
+                        cells.push((x, y));
+                    }
+                }
+                while let Some((x, y)) = cells.last() {
+                    if !buffer.get(*x, *y).symbol.trim().is_empty() {
+                        break;
+                    }
+                    cells.pop();
+                }
+                cells
+            })
+            .collect()
+    }
+
+    /// Get the selected text. Cells between selected text that are not text are copied as
+    /// spaces, so the text keeps its columns.
+    pub fn text(&self, buffer: &Buffer, kinds: &[CellKind]) -> String {
+        let lines: Vec<String> = self
+            .lines(buffer, kinds)
+            .into_iter()
+            .map(|cells| {
+                let mut line = String::new();
+                let mut next = None;
+                for (x, y) in cells {
+                    let symbol = &buffer.get(x, y).symbol;
+                    if let Some(next) = next {
+                        line.push_str(&" ".repeat(x.saturating_sub(next) as usize));
+                    }
+                    line.push_str(symbol);
+                    next = Some(x + symbol.width().max(1) as u16);
+                }
+                line
+            })
+            .collect();
+        lines.join("\n").trim_matches('\n').to_string()
+    }
+
+    /// Highlight the selected cells in `buffer`.
+    pub fn highlight(&self, buffer: &mut Buffer, kinds: &[CellKind]) {
+        for (x, y) in self.lines(buffer, kinds).into_iter().flatten() {
+            buffer.get_mut(x, y).modifier.toggle(Modifier::REVERSED);
+        }
+    }
+}
+
+/// Get the index of the cell at `(x, y)` in the cells of `area`, row by row.
+pub(crate) fn index(area: Rect, x: u16, y: u16) -> usize {
+    (y - area.top()) as usize * area.width as usize + (x - area.left()) as usize
+}
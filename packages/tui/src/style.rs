@@ -210,6 +210,10 @@ impl FromStr for RinkColor {
                 color: Color::White,
                 alpha: 255,
             }),
+            "transparent" => Ok(RinkColor {
+                color: Color::Black,
+                alpha: 0,
+            }),
             _ => {
                 if color.len() == 7 && color.starts_with('#') {
                     parse_hex(color).map(|c| RinkColor {
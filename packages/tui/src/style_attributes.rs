@@ -29,16 +29,20 @@
 - [ ] pub aspect_ratio: Number,
 */
 
-use dioxus_core::Attribute;
 use dioxus_native_core::{
     layout_attributes::parse_value,
     node_ref::{AttributeMask, NodeMask, NodeView},
     state::ParentDepState,
 };
 use dioxus_native_core_macro::sorted_str_slice;
+use std::cell::RefCell;
+use std::rc::Rc;
 use taffy::prelude::*;
 
+use crate::selection::UserSelect;
 use crate::style::{RinkColor, RinkStyle};
+use crate::stylesheet::Stylesheets;
+use crate::text::{TextAlign, TextOverflow};
 
 #[derive(Default, Clone, PartialEq, Debug)]
 pub struct StyleModifier {
@@ -47,13 +51,18 @@ pub struct StyleModifier {
 }
 
 impl ParentDepState for StyleModifier {
-    type Ctx = ();
+    type Ctx = Rc<RefCell<Stylesheets>>;
     type DepState = Self;
     // todo: seperate each attribute into it's own class
     const NODE_MASK: NodeMask =
         NodeMask::new_with_attrs(AttributeMask::Static(SORTED_STYLE_ATTRS)).with_element();
 
-    fn reduce(&mut self, node: NodeView, parent: Option<&Self::DepState>, _: &Self::Ctx) -> bool {
+    fn reduce(
+        &mut self,
+        node: NodeView,
+        parent: Option<&Self::DepState>,
+        stylesheets: &Self::Ctx,
+    ) -> bool {
         let mut new = StyleModifier::default();
         if parent.is_some() {
             new.core.fg = None;
@@ -78,11 +87,9 @@ impl ParentDepState for StyleModifier {
             }
         }
 
-        // gather up all the styles from the attribute list
-        for Attribute { name, value, .. } in node.attributes() {
-            if let Some(text) = value.as_text() {
-                apply_style_attributes(name, text, &mut new);
-            }
+        // gather up all the styles from the stylesheets and the attribute list
+        for (name, value) in stylesheets.borrow().declarations(&node) {
+            apply_style_attributes(name, value, &mut new);
         }
 
         // keep the text styling from the parent element
@@ -90,6 +97,8 @@ impl ParentDepState for StyleModifier {
             let mut new_style = new.core.merge(parent.core);
             new_style.bg = new.core.bg;
             new.core = new_style;
+            new.modifier.text_align = new.modifier.text_align.or(parent.modifier.text_align);
+            new.modifier.user_select = new.modifier.user_select.or(parent.modifier.user_select);
         }
         if &mut new != self {
             *self = new;
@@ -103,6 +112,16 @@ impl ParentDepState for StyleModifier {
 #[derive(Default, Clone, PartialEq, Debug)]
 pub struct TuiModifier {
     pub borders: Borders,
+    /// Inherited from the parent element if it is not set.
+    pub text_align: Option<TextAlign>,
+    pub text_overflow: TextOverflow,
+    /// The order the element is painted in. `None` if it is `auto`.
+    pub z_index: Option<i32>,
+    /// Set if `position` is not `static`. Absolutely positioned descendants are only clipped by
+    /// the overflow of positioned elements.
+    pub positioned: bool,
+    /// Inherited from the parent element if it is not set.
+    pub user_select: Option<UserSelect>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug)]
@@ -294,6 +313,11 @@ pub fn apply_style_attributes(
 
         "pointer-events" => {}
 
+        "position" => {
+            style.modifier.positioned =
+                matches!(value.trim(), "relative" | "absolute" | "fixed" | "sticky")
+        }
+
         "quotes" => {}
         "resize" => {}
         "tab-size" => {}
@@ -317,8 +341,10 @@ pub fn apply_style_attributes(
         | "transition-property"
         | "transition-timing-function" => apply_transition(name, value, style),
 
+        "user-select" => style.modifier.user_select = UserSelect::parse(value),
         "visibility" => {}
         "white-space" => {}
+        "z-index" => style.modifier.z_index = value.parse().ok(),
         _ => {}
     }
 }
@@ -343,8 +369,8 @@ fn apply_background(name: &str, value: &str, style: &mut StyleModifier) {
 }
 
 fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
-    fn parse_border_style(v: &str) -> BorderStyle {
-        match v {
+    fn parse_border_style(v: &str) -> Option<BorderStyle> {
+        Some(match v {
             "dotted" => BorderStyle::Dotted,
             "dashed" => BorderStyle::Dashed,
             "solid" => BorderStyle::Solid,
@@ -355,8 +381,9 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
             "outset" => BorderStyle::Outset,
             "none" => BorderStyle::None,
             "hidden" => BorderStyle::Hidden,
-            _ => todo!(),
-        }
+            // invalid values are ignored, like in a browser
+            _ => return None,
+        })
     }
     match name {
         "border" => {}
@@ -376,7 +403,11 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
                 style.modifier.borders.right.radius = v;
             }
         }
-        "border-bottom-style" => style.modifier.borders.bottom.style = parse_border_style(value),
+        "border-bottom-style" => {
+            if let Some(border_style) = parse_border_style(value) {
+                style.modifier.borders.bottom.style = border_style;
+            }
+        }
         "border-bottom-width" => {
             if let Some(v) = parse_value(value) {
                 style.modifier.borders.bottom.width = v;
@@ -417,7 +448,11 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
                 style.modifier.borders.left.color = Some(c);
             }
         }
-        "border-left-style" => style.modifier.borders.left.style = parse_border_style(value),
+        "border-left-style" => {
+            if let Some(border_style) = parse_border_style(value) {
+                style.modifier.borders.left.style = border_style;
+            }
+        }
         "border-left-width" => {
             if let Some(v) = parse_value(value) {
                 style.modifier.borders.left.width = v;
@@ -451,7 +486,11 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
                 style.modifier.borders.right.color = Some(c);
             }
         }
-        "border-right-style" => style.modifier.borders.right.style = parse_border_style(value),
+        "border-right-style" => {
+            if let Some(border_style) = parse_border_style(value) {
+                style.modifier.borders.right.style = border_style;
+            }
+        }
         "border-right-width" => {
             if let Some(v) = parse_value(value) {
                 style.modifier.borders.right.width = v;
@@ -461,19 +500,22 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
         "border-style" => {
             let values: Vec<_> = value.split(' ').collect();
             if values.len() == 1 {
-                let border_style = parse_border_style(values[0]);
-                style
-                    .modifier
-                    .borders
-                    .slice()
-                    .iter_mut()
-                    .for_each(|b| b.style = border_style);
+                if let Some(border_style) = parse_border_style(values[0]) {
+                    style
+                        .modifier
+                        .borders
+                        .slice()
+                        .iter_mut()
+                        .for_each(|b| b.style = border_style);
+                }
             } else {
                 for (v, b) in values
                     .into_iter()
                     .zip(style.modifier.borders.slice().iter_mut())
                 {
-                    b.style = parse_border_style(v);
+                    if let Some(border_style) = parse_border_style(v) {
+                        b.style = border_style;
+                    }
                 }
             }
         }
@@ -493,7 +535,11 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
                 style.modifier.borders.right.radius = v;
             }
         }
-        "border-top-style" => style.modifier.borders.top.style = parse_border_style(value),
+        "border-top-style" => {
+            if let Some(border_style) = parse_border_style(value) {
+                style.modifier.borders.top.style = border_style;
+            }
+        }
         "border-top-width" => {
             if let Some(v) = parse_value(value) {
                 style.modifier.borders.top.width = v;
@@ -553,7 +599,7 @@ fn apply_font(name: &str, value: &str, style: &mut StyleModifier) {
             "oblique" => style.core = style.core.add_modifier(Modifier::ITALIC),
             _ => (),
         },
-        "font-variant" => todo!(),
+        "font-variant" => {}
         "font-weight" => match value {
             "bold" => style.core = style.core.add_modifier(Modifier::BOLD),
             "normal" => style.core = style.core.remove_modifier(Modifier::BOLD),
@@ -567,8 +613,12 @@ fn apply_text(name: &str, value: &str, style: &mut StyleModifier) {
     use tui::style::Modifier;
 
     match name {
-        "text-align" => todo!(),
-        "text-align-last" => todo!(),
+        "text-align" => {
+            if let Some(align) = TextAlign::parse(value) {
+                style.modifier.text_align = Some(align);
+            }
+        }
+        "text-align-last" => {}
         "text-decoration" | "text-decoration-line" => {
             for v in value.split(' ') {
                 match v {
@@ -578,19 +628,23 @@ fn apply_text(name: &str, value: &str, style: &mut StyleModifier) {
                 }
             }
         }
-        "text-decoration-color" => todo!(),
-        "text-decoration-style" => todo!(),
-        "text-indent" => todo!(),
-        "text-justify" => todo!(),
-        "text-overflow" => todo!(),
-        "text-shadow" => todo!(),
-        "text-transform" => todo!(),
-        _ => todo!(),
+        "text-decoration-color" => {}
+        "text-decoration-style" => {}
+        "text-indent" => {}
+        "text-justify" => {}
+        "text-overflow" => {
+            if let Some(overflow) = TextOverflow::parse(value) {
+                style.modifier.text_overflow = overflow;
+            }
+        }
+        "text-shadow" => {}
+        "text-transform" => {}
+        _ => {}
     }
 }
 
 fn apply_transition(_name: &str, _value: &str, _style: &mut StyleModifier) {
-    todo!()
+    // transitions are not supported yet
 }
 
 const SORTED_STYLE_ATTRS: &[&str] = &sorted_str_slice!([
@@ -687,6 +741,7 @@ const SORTED_STYLE_ATTRS: &[&str] = &sorted_str_slice!([
     "perspective",
     "perspective-origin",
     "pointer-events",
+    "position",
     "quotes",
     "resize",
     "tab-size",
@@ -707,6 +762,7 @@ const SORTED_STYLE_ATTRS: &[&str] = &sorted_str_slice!([
     "transition-duration",
     "transition-property",
     "transition-timing-function",
+    "user-select",
     "visibility",
     "white-space",
     "background-color",
@@ -794,5 +850,6 @@ const SORTED_STYLE_ATTRS: &[&str] = &sorted_str_slice!([
     "text-justify",
     "text-overflow",
     "text-shadow",
-    "text-transform"
+    "text-transform",
+    "z-index"
 ]);
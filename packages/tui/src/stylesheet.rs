@@ -0,0 +1,549 @@
+//! Stylesheets that style elements by their tag, class and id, like the stylesheets of a browser.
+//!
+//! The nodes are matched against the rules before the state of the dom is updated. Only the
+//! nodes whose declarations changed are marked dirty, so their style and layout are computed
+//! again with the declarations from [`Stylesheets::declarations`].
+
+use std::rc::Rc;
+
+use cssparser::{
+    parse_important, AtRuleParser, CowRcStr, DeclarationListParser, DeclarationParser, ParseError,
+    Parser, ParserInput, ParserState, QualifiedRuleParser, RuleListParser, Token,
+};
+use dioxus_core::{Attribute, DomEdit, ElementId, Mutations, VNode, VirtualDom};
+use dioxus_html::geometry::ScreenPoint;
+use dioxus_native_core::{
+    node_ref::{AttributeMask, NodeMask, NodeView},
+    real_dom::NodeType,
+    state::{ChildDepState, NodeDepState, ParentDepState},
+    traversable::Traversable,
+};
+use dioxus_native_core_macro::sorted_str_slice;
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{
+    layout::TaffyLayout, render::Screen, scroll::Overflow, style_attributes::StyleModifier, Dom,
+};
+
+/// The parts of a node selectors match against.
+const SELECTOR_MASK: NodeMask =
+    NodeMask::new_with_attrs(AttributeMask::Static(&sorted_str_slice!(["class", "id"]))).with_tag();
+
+/// The rules of all stylesheets an app added, and the declarations they give each node.
+#[derive(Default)]
+pub struct Stylesheets {
+    rules: Vec<Rule>,
+    /// Set when a stylesheet was added, so every node is matched again.
+    dirty: bool,
+    uses_focus: bool,
+    uses_hover: bool,
+    /// If no selector depends on the ancestors of an element, the descendants of a changed
+    /// element don't need to be matched again.
+    uses_combinators: bool,
+    /// The declarations of the rules each element matches, in the order they are applied.
+    matched: FxHashMap<ElementId, Vec<Declaration>>,
+    focused: Option<ElementId>,
+    hovered: FxHashSet<ElementId>,
+}
+
+impl Stylesheets {
+    /// Parse `css` and add its rules after the rules of the stylesheets added before. Rules that
+    /// can't be parsed or use selectors that are not supported are skipped.
+    pub fn add(&mut self, css: &str) {
+        let mut input = ParserInput::new(css);
+        let mut input = Parser::new(&mut input);
+        for (selectors, declarations) in
+            RuleListParser::new_for_stylesheet(&mut input, RuleParser).flatten()
+        {
+            let declarations: Rc<[Declaration]> = declarations.into();
+            for selector in selectors {
+                self.uses_focus |= selector.uses(PseudoClass::Focus);
+                self.uses_hover |= selector.uses(PseudoClass::Hover);
+                self.uses_combinators |= !selector.ancestors.is_empty();
+                self.rules.push(Rule {
+                    specificity: selector.specificity(),
+                    selector,
+                    declarations: declarations.clone(),
+                });
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Get the declarations of a node from the stylesheets and its attributes, in the order they
+    /// are applied: attributes override the declarations of the stylesheets, unless they are
+    /// `!important`.
+    pub fn declarations<'a>(&'a self, node: &'a NodeView<'_>) -> Vec<(&'a str, &'a str)> {
+        let matched = self
+            .matched
+            .get(&node.id())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let declaration = |d: &'a Declaration| (d.name.as_str(), d.value.as_str());
+        matched
+            .iter()
+            .filter(|d| !d.important)
+            .map(declaration)
+            .chain(
+                node.attributes()
+                    .filter_map(|Attribute { name, value, .. }| {
+                        value.as_text().map(|value| (*name, value))
+                    }),
+            )
+            .chain(matched.iter().filter(|d| d.important).map(declaration))
+            .collect()
+    }
+
+    /// Match the nodes that changed, or whose focus or hover state changed, against the rules.
+    /// The elements under the mouse are the ones it is over in the last frame drawn on `screen`.
+    /// The nodes whose declarations changed are added to `to_update`.
+    pub fn cascade(
+        &mut self,
+        vdom: &VirtualDom,
+        rdom: &Dom,
+        screen: &Screen,
+        focused: Option<ElementId>,
+        mouse: Option<ScreenPoint>,
+        to_update: &mut Vec<(ElementId, NodeMask)>,
+    ) {
+        if self.rules.is_empty() {
+            return;
+        }
+
+        let mut changed = Vec::new();
+        // the descendants of a node only need to be matched again if selectors use combinators
+        let mut subtree = self.uses_combinators;
+        if std::mem::take(&mut self.dirty) {
+            changed.push(ElementId(0));
+            subtree = true;
+        } else {
+            for (id, mask) in to_update.iter() {
+                if mask.overlaps(&SELECTOR_MASK) {
+                    changed.push(*id);
+                }
+            }
+            if self.uses_focus && focused != self.focused {
+                changed.extend(self.focused);
+                changed.extend(focused);
+            }
+        }
+        self.focused = focused;
+        if self.uses_hover {
+            let hovered = mouse
+                .map(|mouse| screen.elements_at(rdom, mouse.x, mouse.y))
+                .unwrap_or_default();
+            changed.extend(self.hovered.symmetric_difference(&hovered));
+            self.hovered = hovered;
+        }
+
+        let mut visited = FxHashSet::default();
+        let mut dirty = Vec::new();
+        for id in changed {
+            self.rematch(vdom, rdom, id, subtree, &mut visited, &mut dirty);
+        }
+
+        if !dirty.is_empty() {
+            let mask = StyleModifier::NODE_MASK
+                .union(&TaffyLayout::NODE_MASK)
+                .union(&Overflow::NODE_MASK);
+            let mut positions: FxHashMap<ElementId, usize> = to_update
+                .iter()
+                .enumerate()
+                .map(|(i, (id, _))| (*id, i))
+                .collect();
+            for id in dirty {
+                if let Some(&i) = positions.get(&id) {
+                    to_update[i].1 = to_update[i].1.union(&mask);
+                } else {
+                    positions.insert(id, to_update.len());
+                    to_update.push((id, mask.clone()));
+                }
+            }
+        }
+    }
+
+    /// Forget the declarations of the nodes removed by `mutations`, whose ids are reused by later
+    /// nodes. Must be called before the mutations are applied to `rdom`.
+    pub(crate) fn prune(&mut self, mutations: &Mutations, rdom: &Dom) {
+        fn remove(stylesheets: &mut Stylesheets, rdom: &Dom, id: ElementId) {
+            stylesheets.matched.remove(&id);
+            stylesheets.hovered.remove(&id);
+            if let Some(NodeType::Element { children, .. }) = rdom.get(id).map(|n| &n.node_type) {
+                for child in children {
+                    remove(stylesheets, rdom, *child);
+                }
+            }
+        }
+
+        if self.matched.is_empty() && self.hovered.is_empty() {
+            return;
+        }
+        for edit in &mutations.edits {
+            match edit {
+                DomEdit::ReplaceWith { root, .. } | DomEdit::Remove { root } => {
+                    remove(self, rdom, ElementId(*root as usize))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Match a node against the rules, and its descendants if `subtree` is set.
+    fn rematch(
+        &mut self,
+        vdom: &VirtualDom,
+        rdom: &Dom,
+        id: ElementId,
+        subtree: bool,
+        visited: &mut FxHashSet<ElementId>,
+        dirty: &mut Vec<ElementId>,
+    ) {
+        let children = match rdom.get(id).map(|node| &node.node_type) {
+            Some(NodeType::Element { children, .. }) => children,
+            // only elements match rules
+            _ => {
+                if self.matched.remove(&id).is_some() {
+                    dirty.push(id);
+                }
+                return;
+            }
+        };
+        if !visited.insert(id) {
+            return;
+        }
+
+        let element = Element {
+            vdom,
+            rdom,
+            focused: self.focused,
+            hovered: &self.hovered,
+        };
+        let mut rules: Vec<_> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.selector.matches(&element, id))
+            .collect();
+        // later rules win over earlier rules with the same specificity
+        rules.sort_by_key(|(i, rule)| (rule.specificity, *i));
+        let mut declarations: Vec<_> = rules
+            .into_iter()
+            .flat_map(|(_, rule)| rule.declarations.iter().cloned())
+            .collect();
+        declarations.sort_by_key(|d| d.important);
+
+        let old = self.matched.get(&id).map(Vec::as_slice).unwrap_or_default();
+        if old != declarations.as_slice() {
+            if declarations.is_empty() {
+                self.matched.remove(&id);
+            } else {
+                self.matched.insert(id, declarations);
+            }
+            dirty.push(id);
+        }
+
+        if subtree {
+            for child in children {
+                self.rematch(vdom, rdom, *child, subtree, visited, dirty);
+            }
+        }
+    }
+}
+
+/// A declaration from a stylesheet, like `color: red`.
+#[derive(Clone, PartialEq, Debug)]
+struct Declaration {
+    name: String,
+    value: String,
+    important: bool,
+}
+
+struct Rule {
+    selector: Selector,
+    specificity: Specificity,
+    declarations: Rc<[Declaration]>,
+}
+
+/// The number of ids, the number of classes and pseudo-classes, and the number of types in a
+/// selector. Rules with more specific selectors override the others.
+type Specificity = (usize, usize, usize);
+
+/// A complex selector like `div.menu > .item:focus`. The compound selectors are stored from the
+/// right to the left, the order they are matched in.
+struct Selector {
+    subject: Compound,
+    /// The compound selectors left of the subject, with the combinator to their right.
+    ancestors: Vec<(Combinator, Compound)>,
+}
+
+impl Selector {
+    fn specificity(&self) -> Specificity {
+        std::iter::once(&self.subject)
+            .chain(self.ancestors.iter().map(|(_, c)| c))
+            .fold((0, 0, 0), |(ids, classes, types), c| {
+                (
+                    ids + c.ids.len(),
+                    classes + c.classes.len() + c.pseudo_classes.len(),
+                    types + c.tag.is_some() as usize,
+                )
+            })
+    }
+
+    fn uses(&self, pseudo_class: PseudoClass) -> bool {
+        std::iter::once(&self.subject)
+            .chain(self.ancestors.iter().map(|(_, c)| c))
+            .any(|c| c.pseudo_classes.contains(&pseudo_class))
+    }
+
+    fn matches(&self, element: &Element, id: ElementId) -> bool {
+        self.subject.matches(element, id) && self.matches_ancestors(element, id, 0)
+    }
+
+    /// Check if the ancestors of `id` match the compound selectors from the `i`th one on. If a
+    /// descendant combinator's ancestor doesn't match the rest, an ancestor further up may.
+    fn matches_ancestors(&self, element: &Element, id: ElementId, i: usize) -> bool {
+        let (combinator, compound) = match self.ancestors.get(i) {
+            Some(ancestor) => ancestor,
+            None => return true,
+        };
+        let mut parent = element.rdom[id].parent;
+        while let Some(id) = parent {
+            if compound.matches(element, id) && self.matches_ancestors(element, id, i + 1) {
+                return true;
+            }
+            if *combinator == Combinator::Child {
+                return false;
+            }
+            parent = element.rdom[id].parent;
+        }
+        false
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+}
+
+/// A compound selector like `div#id.class:hover`. A selector without a tag matches every tag.
+#[derive(Default, PartialEq, Debug)]
+struct Compound {
+    tag: Option<String>,
+    ids: Vec<String>,
+    classes: Vec<String>,
+    pseudo_classes: Vec<PseudoClass>,
+}
+
+impl Compound {
+    fn matches(&self, element: &Element, id: ElementId) -> bool {
+        let tag = match &element.rdom[id].node_type {
+            NodeType::Element { tag, .. } => tag,
+            _ => return false,
+        };
+        self.tag.iter().all(|t| t.eq_ignore_ascii_case(tag))
+            && self
+                .ids
+                .iter()
+                .all(|i| element.attribute(id, "id") == Some(i.as_str()))
+            && self.classes.iter().all(|c| {
+                element
+                    .attribute(id, "class")
+                    .into_iter()
+                    .flat_map(str::split_whitespace)
+                    .any(|class| class == c)
+            })
+            && self.pseudo_classes.iter().all(|p| match p {
+                PseudoClass::Focus => element.focused == Some(id),
+                PseudoClass::Hover => element.hovered.contains(&id),
+            })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PseudoClass {
+    Focus,
+    Hover,
+}
+
+/// The state of the dom selectors are matched against.
+struct Element<'a> {
+    vdom: &'a VirtualDom,
+    rdom: &'a Dom,
+    focused: Option<ElementId>,
+    hovered: &'a FxHashSet<ElementId>,
+}
+
+impl Element<'_> {
+    fn attribute(&self, id: ElementId, name: &str) -> Option<&str> {
+        match self.vdom.get_element(id) {
+            Some(VNode::Element(el)) => el
+                .attributes
+                .iter()
+                .find(|a| a.name == name)
+                .and_then(|a| a.value.as_text()),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the rules of a stylesheet. At-rules are not supported and skipped.
+struct RuleParser;
+
+impl<'i> QualifiedRuleParser<'i> for RuleParser {
+    type Prelude = Vec<Selector>;
+    type QualifiedRule = (Vec<Selector>, Vec<Declaration>);
+    type Error = ();
+
+    fn parse_prelude<'t>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        input.parse_comma_separated(parse_selector)
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        selectors: Self::Prelude,
+        _: &ParserState,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
+        // invalid declarations are skipped, the valid ones in the block still apply
+        let declarations = DeclarationListParser::new(input, DeclarationsParser)
+            .flatten()
+            .collect();
+        Ok((selectors, declarations))
+    }
+}
+
+impl<'i> AtRuleParser<'i> for RuleParser {
+    type Prelude = ();
+    type AtRule = (Vec<Selector>, Vec<Declaration>);
+    type Error = ();
+}
+
+/// Parses the declarations in the block of a rule. The values are kept as text and parsed
+/// when they are applied, like the values of attributes.
+struct DeclarationsParser;
+
+impl<'i> DeclarationParser<'i> for DeclarationsParser {
+    type Declaration = Declaration;
+    type Error = ();
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        let start = input.position();
+        let mut end = start;
+        let mut important = false;
+        loop {
+            if input.try_parse(parse_important).is_ok() {
+                input.expect_exhausted()?;
+                important = true;
+                break;
+            }
+            if input.next().is_err() {
+                break;
+            }
+            end = input.position();
+        }
+        let value = input.slice(start..end).trim();
+        if value.is_empty() {
+            return Err(input.new_custom_error(()));
+        }
+        Ok(Declaration {
+            name: name.to_ascii_lowercase(),
+            value: value.to_string(),
+            important,
+        })
+    }
+}
+
+impl<'i> AtRuleParser<'i> for DeclarationsParser {
+    type Prelude = ();
+    type AtRule = Declaration;
+    type Error = ();
+}
+
+/// Parse a complex selector. Selectors that are not supported are an error, so the rule is
+/// skipped like browsers skip rules with selectors they don't know.
+fn parse_selector<'i>(input: &mut Parser<'i, '_>) -> Result<Selector, ParseError<'i, ()>> {
+    // the compound selectors from the left to the right, with the combinator to their right
+    let mut compounds: Vec<(Compound, Combinator)> = Vec::new();
+    let mut current: Option<Compound> = None;
+    let mut combinator = None;
+    while let Ok(token) = input.next_including_whitespace() {
+        let token = token.clone();
+        match token {
+            Token::WhiteSpace(_) => {
+                if let Some(compound) = current.take() {
+                    compounds.push((compound, Combinator::Descendant));
+                    combinator = Some(Combinator::Descendant);
+                }
+            }
+            Token::Delim('>') => {
+                if let Some(compound) = current.take() {
+                    compounds.push((compound, Combinator::Child));
+                } else if let Some((_, c)) = compounds.last_mut() {
+                    *c = Combinator::Child;
+                } else {
+                    return Err(input.new_custom_error(()));
+                }
+                combinator = Some(Combinator::Child);
+            }
+            _ => {
+                let compound = current.get_or_insert_with(Compound::default);
+                match token {
+                    Token::Ident(tag) if *compound == Compound::default() => {
+                        compound.tag = Some(tag.to_ascii_lowercase());
+                    }
+                    Token::Delim('*') if *compound == Compound::default() => {}
+                    Token::IDHash(id) => compound.ids.push(id.to_string()),
+                    Token::Delim('.') => {
+                        let class = input.expect_ident_cloned()?;
+                        compound.classes.push(class.to_string());
+                    }
+                    Token::Colon => {
+                        let name = input.expect_ident_cloned()?;
+                        let pseudo_class = match &*name.to_ascii_lowercase() {
+                            "focus" => PseudoClass::Focus,
+                            "hover" => PseudoClass::Hover,
+                            _ => return Err(input.new_custom_error(())),
+                        };
+                        compound.pseudo_classes.push(pseudo_class);
+                    }
+                    _ => return Err(input.new_custom_error(())),
+                }
+                combinator = None;
+            }
+        }
+    }
+
+    match current {
+        Some(subject) => Ok(Selector {
+            subject,
+            ancestors: compounds
+                .into_iter()
+                .rev()
+                .map(|(compound, combinator)| (combinator, compound))
+                .collect(),
+        }),
+        // trailing whitespace is not a combinator
+        None if combinator == Some(Combinator::Descendant) => {
+            let (subject, _) = compounds.pop().unwrap();
+            Ok(Selector {
+                subject,
+                ancestors: compounds
+                    .into_iter()
+                    .rev()
+                    .map(|(compound, combinator)| (combinator, compound))
+                    .collect(),
+            })
+        }
+        None => Err(input.new_custom_error(())),
+    }
+}
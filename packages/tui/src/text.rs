@@ -0,0 +1,300 @@
+//! Measuring and wrapping text.
+//!
+//! Text is split into graphemes, and each grapheme takes up as many cells as its East Asian width
+//! (wide CJK characters and emoji take two cells, combining characters are part of the grapheme
+//! before them).
+
+use taffy::{
+    node::MeasureFunc,
+    number::OrElse,
+    prelude::{Number, Size},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// How white space in text is handled, set with the `white-space` attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum WhiteSpace {
+    Normal,
+    NoWrap,
+    Pre,
+    PreWrap,
+    PreLine,
+}
+
+impl Default for WhiteSpace {
+    fn default() -> Self {
+        WhiteSpace::Normal
+    }
+}
+
+impl WhiteSpace {
+    pub fn parse(value: &str) -> Option<Self> {
+        Some(match value.trim() {
+            "normal" => WhiteSpace::Normal,
+            "nowrap" => WhiteSpace::NoWrap,
+            "pre" => WhiteSpace::Pre,
+            "pre-wrap" | "break-spaces" => WhiteSpace::PreWrap,
+            "pre-line" => WhiteSpace::PreLine,
+            _ => return None,
+        })
+    }
+
+    fn collapses_spaces(self) -> bool {
+        matches!(
+            self,
+            WhiteSpace::Normal | WhiteSpace::NoWrap | WhiteSpace::PreLine
+        )
+    }
+
+    fn preserves_newlines(self) -> bool {
+        matches!(
+            self,
+            WhiteSpace::Pre | WhiteSpace::PreWrap | WhiteSpace::PreLine
+        )
+    }
+
+    fn wraps(self) -> bool {
+        matches!(
+            self,
+            WhiteSpace::Normal | WhiteSpace::PreWrap | WhiteSpace::PreLine
+        )
+    }
+}
+
+/// How lines are aligned in a text node, set with the `text-align` attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Some(match value.trim() {
+            "left" | "start" | "justify" => TextAlign::Left,
+            "center" => TextAlign::Center,
+            "right" | "end" => TextAlign::Right,
+            _ => return None,
+        })
+    }
+}
+
+/// How text that doesn't fit into a line is shown, set with the `text-overflow` attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextOverflow {
+    Clip,
+    Ellipsis,
+}
+
+impl Default for TextOverflow {
+    fn default() -> Self {
+        TextOverflow::Clip
+    }
+}
+
+impl TextOverflow {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Some(match value.trim() {
+            "clip" => TextOverflow::Clip,
+            "ellipsis" => TextOverflow::Ellipsis,
+            _ => return None,
+        })
+    }
+}
+
+const TAB_SIZE: usize = 8;
+const ELLIPSIS: &str = "…";
+
+/// Get the number of cells `text` takes up.
+pub(crate) fn width(text: &str) -> usize {
+    text.graphemes(true).map(UnicodeWidthStr::width).sum()
+}
+
+/// A line of text after wrapping.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Line {
+    pub text: String,
+    pub width: usize,
+}
+
+impl Line {
+    fn new() -> Self {
+        Line {
+            text: String::new(),
+            width: 0,
+        }
+    }
+
+    fn push(&mut self, text: &str, width: usize) {
+        self.text.push_str(text);
+        self.width += width;
+    }
+
+    fn trim_end(mut self) -> Self {
+        let trimmed = self.text.trim_end_matches(' ').len();
+        self.width -= self.text.len() - trimmed;
+        self.text.truncate(trimmed);
+        self
+    }
+
+    /// Cut the line to `max_width` cells, replacing the end with an ellipsis if `ellipsis` is set.
+    pub fn truncate(self, max_width: usize, ellipsis: bool) -> Self {
+        if self.width <= max_width {
+            return self;
+        }
+        let ellipsis = ellipsis && max_width > 0;
+        let available = max_width - ellipsis as usize;
+        let mut line = Line::new();
+        for g in self.text.graphemes(true) {
+            let w = g.width();
+            if line.width + w > available {
+                break;
+            }
+            line.push(g, w);
+        }
+        if ellipsis {
+            line.push(ELLIPSIS, 1);
+        }
+        line
+    }
+}
+
+/// Split `text` into paragraphs, and handle spaces and tabs the way `white_space` requires.
+fn paragraphs(text: &str, white_space: WhiteSpace) -> Vec<String> {
+    let paragraphs: Vec<&str> = if white_space.preserves_newlines() {
+        text.split('\n').collect()
+    } else {
+        vec![text]
+    };
+
+    paragraphs
+        .into_iter()
+        .map(|p| {
+            let mut out = String::with_capacity(p.len());
+            if white_space.collapses_spaces() {
+                let mut last_space = false;
+                for c in p.chars() {
+                    if c.is_whitespace() {
+                        if !last_space {
+                            out.push(' ');
+                        }
+                        last_space = true;
+                    } else {
+                        out.push(c);
+                        last_space = false;
+                    }
+                }
+            } else {
+                let mut column = 0;
+                for g in p.graphemes(true) {
+                    match g {
+                        "\t" => {
+                            let spaces = TAB_SIZE - column % TAB_SIZE;
+                            out.push_str(&" ".repeat(spaces));
+                            column += spaces;
+                        }
+                        "\r" => {}
+                        _ => {
+                            out.push_str(g);
+                            column += g.width();
+                        }
+                    }
+                }
+            }
+            out
+        })
+        .collect()
+}
+
+/// Split `text` into runs of spaces and runs of other characters.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let is_space = first == ' ';
+        let end = rest
+            .find(|c: char| (c == ' ') != is_space)
+            .unwrap_or(rest.len());
+        let (word, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(word)
+    })
+}
+
+/// Split `text` into lines. If `max_width` is set and `white_space` allows wrapping, lines are
+/// wrapped at spaces, and words longer than a line are broken between graphemes.
+pub(crate) fn lines(text: &str, white_space: WhiteSpace, max_width: Option<usize>) -> Vec<Line> {
+    let max_width = max_width.filter(|_| white_space.wraps());
+    let mut lines = Vec::new();
+
+    for paragraph in paragraphs(text, white_space) {
+        let max_width = match max_width {
+            Some(w) => w.max(1),
+            None => {
+                lines.push(Line {
+                    width: width(&paragraph),
+                    text: paragraph,
+                });
+                continue;
+            }
+        };
+
+        let mut line = Line::new();
+        let mut wrapped = false;
+        for segment in words(&paragraph) {
+            let segment_width = width(segment);
+            let is_space = segment.chars().all(|c| c == ' ');
+
+            if is_space {
+                // spaces at the start of a wrapped line are dropped
+                if !(wrapped && line.text.is_empty() && white_space.collapses_spaces()) {
+                    line.push(segment, segment_width);
+                }
+                continue;
+            }
+
+            if line.width + segment_width > max_width && !line.text.trim_end().is_empty() {
+                lines.push(std::mem::replace(&mut line, Line::new()).trim_end());
+                wrapped = true;
+            } else if line.width + segment_width > max_width {
+                // the line only contains spaces
+                line = Line::new();
+            }
+
+            if segment_width > max_width {
+                // break words that are longer than a line
+                for g in segment.graphemes(true) {
+                    let w = g.width();
+                    if line.width + w > max_width && line.width > 0 {
+                        lines.push(std::mem::replace(&mut line, Line::new()));
+                        wrapped = true;
+                    }
+                    line.push(g, w);
+                }
+            } else {
+                line.push(segment, segment_width);
+            }
+        }
+        lines.push(line.trim_end());
+    }
+
+    lines
+}
+
+/// Create a function that measures `text` for taffy.
+pub(crate) fn measure(text: String, white_space: WhiteSpace) -> MeasureFunc {
+    MeasureFunc::Boxed(Box::new(move |size: Size<Number>| {
+        let max_width = match size.width {
+            Number::Defined(w) => Some(w.max(0.0) as usize),
+            Number::Undefined => None,
+        };
+        let lines = lines(&text, white_space, max_width);
+        let content_width = lines.iter().map(|l| l.width).max().unwrap_or(0) as f32;
+        Size {
+            width: size.width.or_else(content_width),
+            height: size.height.or_else(lines.len() as f32),
+        }
+    }))
+}
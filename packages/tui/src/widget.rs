@@ -10,25 +10,48 @@ use crate::{
     Config,
 };
 
+/// A view into the terminal buffer for a single widget.
+///
+/// Widgets draw in their own coordinates, where `(0, 0)` is the top left corner of the widget.
+/// The widget may be partially scrolled out of view, so cells outside of the visible area are
+/// silently dropped.
 pub struct RinkBuffer<'a> {
     buf: &'a mut Buffer,
     cfg: Config,
+    origin: [i32; 2],
+    clip: Rect,
 }
 
 impl<'a> RinkBuffer<'a> {
-    fn new(buf: &'a mut Buffer, cfg: Config) -> RinkBuffer<'a> {
-        Self { buf, cfg }
+    fn new(buf: &'a mut Buffer, cfg: Config, origin: [i32; 2], clip: Rect) -> RinkBuffer<'a> {
+        let clip = clip.intersection(*buf.area());
+        Self {
+            buf,
+            cfg,
+            origin,
+            clip,
+        }
     }
 
     pub fn set(&mut self, x: u16, y: u16, new: RinkCell) {
-        let area = self.buf.area();
-        if x < area.x || x > area.width || y < area.y || y > area.height {
-            panic!("({x}, {y}) is not in {area:?}");
+        let x = self.origin[0] + x as i32;
+        let y = self.origin[1] + y as i32;
+        let clip = self.clip;
+        if x < clip.left() as i32
+            || x >= clip.right() as i32
+            || y < clip.top() as i32
+            || y >= clip.bottom() as i32
+        {
+            return;
         }
-        let mut cell = self.buf.get_mut(x, y);
+        let mut cell = self.buf.get_mut(x as u16, y as u16);
         cell.bg = convert(self.cfg.rendering_mode, new.bg.blend(cell.bg));
         if new.symbol.is_empty() {
-            if !cell.symbol.is_empty() {
+            if new.bg.alpha == 255 {
+                // opaque backgrounds hide what is beneath them
+                cell.symbol = " ".to_string();
+                cell.modifier = Modifier::empty();
+            } else if !cell.symbol.is_empty() {
                 // allows text to "shine through" transparent backgrounds
                 cell.fg = convert(self.cfg.rendering_mode, new.bg.blend(cell.fg));
             }
@@ -44,20 +67,38 @@ pub trait RinkWidget {
     fn render(self, area: Rect, buf: RinkBuffer);
 }
 
+/// A widget placed at `origin` with the size `size`. The area it is rendered into is the part of
+/// the widget that is visible.
 pub struct WidgetWithContext<T: RinkWidget> {
     widget: T,
     config: Config,
+    origin: [i32; 2],
+    size: [u16; 2],
 }
 
 impl<T: RinkWidget> WidgetWithContext<T> {
-    pub fn new(widget: T, config: Config) -> WidgetWithContext<T> {
-        WidgetWithContext { widget, config }
+    pub fn new(
+        widget: T,
+        config: Config,
+        origin: [i32; 2],
+        size: [u16; 2],
+    ) -> WidgetWithContext<T> {
+        WidgetWithContext {
+            widget,
+            config,
+            origin,
+            size,
+        }
     }
 }
 
 impl<T: RinkWidget> Widget for WidgetWithContext<T> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        self.widget.render(area, RinkBuffer::new(buf, self.config));
+        let [width, height] = self.size;
+        self.widget.render(
+            Rect::new(0, 0, width, height),
+            RinkBuffer::new(buf, self.config, self.origin, area),
+        );
     }
 }
 
@@ -0,0 +1,259 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use dioxus::prelude::*;
+use dioxus_tui::{Headless, TuiContext};
+use tui::style::Color;
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn fg(headless: &Headless, x: u16, y: u16) -> Color {
+    headless.buffer().get(x, y).fg
+}
+
+const RED: Color = Color::Rgb(255, 0, 0);
+const GREEN: Color = Color::Rgb(0, 128, 0);
+const BLUE: Color = Color::Rgb(0, 0, 255);
+/// The color of text without a style.
+const WHITE: Color = Color::Rgb(255, 255, 255);
+
+#[test]
+fn cascades_by_specificity() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| {
+            tui_ctx.add_stylesheet(
+                "
+                #main { color: green; }
+                p, div { color: blue; }
+                .warning { color: red; }
+                div { color: green; }
+                @media print { div { color: red; } }
+                .unsupported::before { color: red; }
+                ",
+            )
+        });
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                div { "a" }
+                div { class: "warning", "b" }
+                div { class: "warning", id: "main", "c" }
+                div { class: "warning", color: "blue", "d" }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 4, 4);
+    assert_eq!(headless.text(), "a\nb\nc\nd");
+    // the later of two rules with the same specificity wins
+    assert_eq!(fg(&headless, 0, 0), GREEN);
+    assert_eq!(fg(&headless, 0, 1), RED);
+    assert_eq!(fg(&headless, 0, 2), GREEN);
+    // attributes override stylesheets
+    assert_eq!(fg(&headless, 0, 3), BLUE);
+}
+
+#[test]
+fn important_overrides_attributes() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| tui_ctx.add_stylesheet(".a { color: red !important; }"));
+        cx.render(rsx! {
+            div { class: "a", color: "blue", "a" }
+        })
+    }
+
+    let headless = Headless::new(app, 1, 1);
+    assert_eq!(fg(&headless, 0, 0), RED);
+}
+
+#[test]
+fn styles_layout() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| {
+            tui_ctx.add_stylesheet(
+                ".box { width: 5px; height: 3px; border-style: solid; border-width: 1px; }
+                .row { flex-direction: row; justify-content: flex-end; }",
+            )
+        });
+        cx.render(rsx! {
+            div {
+                class: "row",
+                width: "100%",
+                div { class: "box", "x" }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 7, 3);
+    assert_eq!(headless.text(), "  ┌───┐\n  │x  │\n  └───┘");
+}
+
+#[test]
+fn ignores_unsupported_properties() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| {
+            tui_ctx.add_stylesheet(
+                ".btn { transition: color 1s; transform: rotate(45deg); color: red; }
+                div { text-transform: uppercase; text-shadow: 1px 1px black; font-variant: small-caps; }
+                .box { border-style: solid; border-style: inherit; border-width: 1px; }",
+            )
+        });
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                div { class: "btn", "a" }
+                div { class: "box", width: "3px", height: "3px", "b" }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 3, 4);
+    assert_eq!(headless.text(), "a\n┌─┐\n│b│\n└─┘");
+    assert_eq!(fg(&headless, 0, 0), RED);
+}
+
+#[test]
+fn matches_descendants_and_children() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| {
+            tui_ctx.add_stylesheet(
+                ".menu p { color: red; }
+                .menu > p { color: green; }
+                .menu>div>p{color:blue}",
+            )
+        });
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                div {
+                    class: "menu",
+                    flex_direction: "column",
+                    p { "a" }
+                    div { p { "b" } }
+                    div { div { p { "c" } } }
+                }
+                p { "d" }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 4, 4);
+    assert_eq!(headless.text(), "a\nb\nc\nd");
+    assert_eq!(fg(&headless, 0, 0), GREEN);
+    assert_eq!(fg(&headless, 0, 1), BLUE);
+    assert_eq!(fg(&headless, 0, 2), RED);
+    assert_eq!(fg(&headless, 0, 3), WHITE);
+}
+
+#[test]
+fn restyles_when_classes_change() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| {
+            tui_ctx.add_stylesheet(
+                ".selected { color: red; }
+                .selected span { color: green; }",
+            )
+        });
+        let selected = use_state(&cx, || false);
+        let class = if **selected { "selected" } else { "" };
+        cx.render(rsx! {
+            div {
+                tabindex: "0",
+                class: "{class}",
+                onkeydown: move |_| selected.modify(|s| !s),
+                "a"
+                span { "b" }
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 2, 1);
+    assert_eq!(fg(&headless, 0, 0), WHITE);
+    assert_eq!(fg(&headless, 1, 0), WHITE);
+    headless.inject_event(key(KeyCode::Tab));
+    headless.inject_event(key(KeyCode::Enter));
+    headless.step();
+    assert_eq!(fg(&headless, 0, 0), RED);
+    assert_eq!(fg(&headless, 1, 0), GREEN);
+    headless.inject_event(key(KeyCode::Enter));
+    headless.step();
+    assert_eq!(fg(&headless, 0, 0), WHITE);
+    assert_eq!(fg(&headless, 1, 0), WHITE);
+}
+
+#[test]
+fn matches_focus_and_hover() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| {
+            tui_ctx.add_stylesheet(
+                ".item:focus { color: red; }
+                .item:hover { color: green; }",
+            )
+        });
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                div { class: "item", tabindex: "0", "a" }
+                div { class: "item", tabindex: "0", "b" }
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 1, 2);
+    headless.inject_event(key(KeyCode::Tab));
+    headless.step();
+    assert_eq!(fg(&headless, 0, 0), RED);
+    assert_eq!(fg(&headless, 0, 1), WHITE);
+
+    headless.inject_event(Event::Mouse(MouseEvent {
+        column: 0,
+        row: 1,
+        kind: MouseEventKind::Moved,
+        modifiers: KeyModifiers::NONE,
+    }));
+    headless.step();
+    assert_eq!(fg(&headless, 0, 0), RED);
+    assert_eq!(fg(&headless, 0, 1), GREEN);
+
+    headless.inject_event(key(KeyCode::Tab));
+    headless.step();
+    assert_eq!(fg(&headless, 0, 0), WHITE);
+    // hover is declared after focus
+    assert_eq!(fg(&headless, 0, 1), GREEN);
+}
+
+#[test]
+fn forgets_removed_elements() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.use_hook(|| tui_ctx.add_stylesheet(".warning { color: red; }"));
+        let warning = use_state(&cx, || true);
+        cx.render(rsx! {
+            div {
+                tabindex: "0",
+                onkeydown: move |_| warning.modify(|w| !w),
+                warning.then(|| rsx! { div { class: "warning", "a" } }),
+                (!**warning).then(|| rsx! { "b" }),
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 1, 1);
+    assert_eq!(fg(&headless, 0, 0), RED);
+    headless.inject_event(key(KeyCode::Tab));
+    headless.inject_event(key(KeyCode::Enter));
+    headless.step();
+    // the text may reuse the id of the removed element, but not its declarations
+    assert_eq!(headless.text(), "b");
+    assert_eq!(fg(&headless, 0, 0), WHITE);
+}
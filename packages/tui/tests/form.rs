@@ -0,0 +1,184 @@
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use dioxus::prelude::*;
+use dioxus_core::ElementId;
+use dioxus_tui::query::Query;
+use dioxus_tui::{Size, TuiContext};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The tui renderer will look for any event that has occured or any future that has resolved in a loop.
+/// It will resolve at most one event per loop.
+/// This future will resolve after a certain number of polls. If the number of polls is greater than the number of events triggered, and the event has not been recieved there is an issue with the event system.
+struct PollN(usize);
+impl PollN {
+    fn new(n: usize) -> Self {
+        PollN(n)
+    }
+}
+impl Future for PollN {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 == 0 {
+            Poll::Ready(())
+        } else {
+            self.0 -= 1;
+            Poll::Pending
+        }
+    }
+}
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn child_id(cx: &ScopeState, i: usize) -> ElementId {
+    match cx.root_node() {
+        VNode::Element(el) => el.children[i].mounted_id(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn typing_into_input() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let render_count = use_state(&cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let render_count_handle = render_count.clone();
+        cx.spawn(async move {
+            PollN::new(10).await;
+            render_count_handle.modify(|x| *x + 1);
+        });
+        if *render_count.get() > 2 {
+            panic!("Event was not received");
+        }
+        tui_ctx.inject_event(key(KeyCode::Tab));
+        tui_ctx.inject_event(key(KeyCode::Char('h')));
+        tui_ctx.inject_event(key(KeyCode::Char('x')));
+        tui_ctx.inject_event(key(KeyCode::Backspace));
+        tui_ctx.inject_event(key(KeyCode::Char('i')));
+        cx.render(rsx! {
+            form {
+                input {
+                    name: "greeting",
+                    oninput: move |evt| {
+                        if evt.value == "hi" {
+                            assert_eq!(evt.values["greeting"], "hi");
+                            assert_eq!(evt.values["other"], "text");
+                            tui_ctx.quit();
+                        }
+                    },
+                }
+                input { name: "other", value: "text" }
+            }
+        })
+    }
+}
+
+#[test]
+fn toggling_checkbox() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let render_count = use_state(&cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let render_count_handle = render_count.clone();
+        cx.spawn(async move {
+            PollN::new(4).await;
+            render_count_handle.modify(|x| *x + 1);
+        });
+        if *render_count.get() > 2 {
+            panic!("Event was not received");
+        }
+        tui_ctx.inject_event(key(KeyCode::Tab));
+        tui_ctx.inject_event(key(KeyCode::Char(' ')));
+        cx.render(rsx! {
+            input {
+                r#type: "checkbox",
+                onchange: move |evt| {
+                    assert_eq!(evt.value, "true");
+                    tui_ctx.quit();
+                },
+            }
+        })
+    }
+}
+
+#[test]
+fn choosing_option() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let render_count = use_state(&cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let render_count_handle = render_count.clone();
+        cx.spawn(async move {
+            PollN::new(4).await;
+            render_count_handle.modify(|x| *x + 1);
+        });
+        if *render_count.get() > 2 {
+            panic!("Event was not received");
+        }
+        tui_ctx.inject_event(key(KeyCode::Tab));
+        tui_ctx.inject_event(key(KeyCode::Down));
+        cx.render(rsx! {
+            select {
+                onchange: move |evt| {
+                    assert_eq!(evt.value, "c");
+                    tui_ctx.quit();
+                },
+                option { value: "a", "A" }
+                option { value: "b", selected: "true", "B" }
+                option { value: "c", "C" }
+            }
+        })
+    }
+}
+
+#[test]
+fn default_sizes() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let query: Query = cx.consume_context().unwrap();
+        tui_ctx.inject_event(Event::Mouse(MouseEvent {
+            column: 0,
+            row: 0,
+            kind: MouseEventKind::Down(MouseButton::Left),
+            modifiers: KeyModifiers::NONE,
+        }));
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                align_items: "flex-start",
+                onmousedown: move |_| {
+                    let size = |i: usize| query.get(child_id(&cx, i)).size().unwrap();
+                    assert_eq!(size(0), Size { width: 20, height: 1 });
+                    assert_eq!(size(1), Size { width: 3, height: 1 });
+                    assert_eq!(size(2), Size { width: 10, height: 3 });
+                    // the longest option and the arrow
+                    assert_eq!(size(3), Size { width: 7, height: 1 });
+                    tui_ctx.quit();
+                },
+                input {}
+                input { r#type: "checkbox" }
+                textarea { cols: "10", rows: "3" }
+                select {
+                    option { "one" }
+                    option { "three" }
+                }
+            }
+        })
+    }
+}
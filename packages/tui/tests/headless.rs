@@ -0,0 +1,160 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use dioxus::prelude::*;
+use dioxus_tui::{Headless, TuiContext};
+use std::time::Duration;
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn renders_into_buffer() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                border_style: "solid",
+                border_width: "1px",
+                "hello"
+                div { "日本" }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 10, 5);
+    assert_eq!(headless.buffer().area().width, 10);
+    assert_eq!(
+        headless.text(),
+        "┌────────┐\n│hello   │\n│日本    │\n│        │\n└────────┘"
+    );
+}
+
+#[test]
+fn steps_with_events() {
+    fn app(cx: Scope) -> Element {
+        let count = use_state(&cx, || 0);
+        cx.render(rsx! {
+            div {
+                tabindex: "0",
+                onkeydown: move |_| count.modify(|c| c + 1),
+                "count: {count}"
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 12, 1);
+    assert_eq!(headless.text(), "count: 0");
+    headless.inject_event(key(KeyCode::Tab));
+    headless.inject_event(key(KeyCode::Char('a')));
+    headless.inject_event(key(KeyCode::Char('b')));
+    assert!(headless.step());
+    assert_eq!(headless.text(), "count: 2");
+}
+
+#[test]
+fn styled_output() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                font_weight: "bold",
+                color: "red",
+                "hi"
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 4, 1);
+    assert_eq!(
+        headless.ansi(),
+        "\u{1b}[0m\u{1b}[1m\u{1b}[38;2;255;0;0m\u{1b}[49mhi\u{1b}[0m\u{1b}[39m\u{1b}[49m  \u{1b}[0m"
+    );
+}
+
+#[test]
+fn resizes_and_quits() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                tabindex: "0",
+                justify_content: "flex-end",
+                onkeydown: move |_| tui_ctx.quit(),
+                "x"
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 4, 1);
+    assert_eq!(headless.text(), "   x");
+    headless.resize(6, 1);
+    assert!(headless.step());
+    assert_eq!(headless.text(), "     x");
+    headless.inject_event(key(KeyCode::Tab));
+    headless.inject_event(key(KeyCode::Enter));
+    assert!(!headless.step());
+    assert!(!headless.running());
+}
+
+#[test]
+fn repaints_changed_areas() {
+    fn app(cx: Scope) -> Element {
+        let count = use_state(&cx, || 0);
+        let width = 2 + *count.get() % 3;
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                tabindex: "0",
+                onkeydown: move |_| count.modify(|c| c + 1),
+                "count: {count}"
+                div { width: "{width}px", height: "1px", background_color: "red" }
+                (*count.get() % 2 == 0).then(|| rsx! {
+                    div { background_color: "blue", color: "white", "even" }
+                })
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 10, 4);
+    headless.inject_event(key(KeyCode::Tab));
+    headless.step();
+    for _ in 0..4 {
+        headless.inject_event(key(KeyCode::Char('a')));
+        headless.step();
+        let repainted = headless.ansi();
+        // painting everything again gives the same frame
+        headless.invalidate();
+        headless.step();
+        assert_eq!(repainted, headless.ansi());
+    }
+    assert_eq!(headless.text(), "count: 4\n\neven\n");
+}
+
+#[test]
+fn runs_timers_in_tasks() {
+    fn app(cx: Scope) -> Element {
+        let ticks = use_state(&cx, || 0);
+        use_future(&cx, (), move |_| {
+            let ticks = ticks.to_owned();
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                ticks.set(1);
+            }
+        });
+        cx.render(rsx! { "ticks: {ticks}" })
+    }
+
+    let mut headless = Headless::new(app, 10, 1);
+    headless.step();
+    assert_eq!(headless.text(), "ticks: 0");
+    std::thread::sleep(Duration::from_millis(20));
+    headless.step();
+    assert_eq!(headless.text(), "ticks: 1");
+}
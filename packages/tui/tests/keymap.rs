@@ -0,0 +1,254 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use dioxus::prelude::*;
+use dioxus_tui::{use_keybinding, Headless, KeyBinding, KeyPress, KeySequence, TuiContext};
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn ctrl(c: char) -> Event {
+    Event::Key(KeyEvent {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::CONTROL,
+    })
+}
+
+fn type_keys(headless: &mut Headless, keys: &str) {
+    for c in keys.chars() {
+        headless.inject_event(key(KeyCode::Char(c)));
+    }
+    headless.step();
+}
+
+#[test]
+fn parses_keys() {
+    let keys: KeySequence = "ctrl+x ctrl+S".parse().unwrap();
+    assert_eq!(
+        keys.0,
+        vec![
+            KeyPress::new(KeyCode::Char('x'), KeyModifiers::CONTROL),
+            KeyPress::new(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        ]
+    );
+    assert_eq!(keys.to_string(), "ctrl+x ctrl+s");
+
+    let key = |s: &str| s.parse::<KeyPress>().unwrap();
+    assert_eq!(key("shift+a"), key("A"));
+    assert_eq!(
+        key("shift+tab"),
+        KeyPress::new(KeyCode::BackTab, KeyModifiers::SHIFT)
+    );
+    assert_eq!(
+        key("?"),
+        KeyPress::new(KeyCode::Char('?'), KeyModifiers::SHIFT)
+    );
+    assert_eq!(key("alt++").code, KeyCode::Char('+'));
+    assert_eq!(key("F5").code, KeyCode::F(5));
+    for name in ["ctrl+alt+enter", "esc", "space", "pagedown", "g", "+"] {
+        assert_eq!(key(name).to_string(), name);
+    }
+
+    for invalid in ["", "ctrl+", "hyper+a", "ctrl+xy", "f25x"] {
+        assert!(invalid.parse::<KeySequence>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn runs_sequences() {
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let count = use_state(&cx, || 0);
+        let typed = use_state(&cx, String::new);
+        {
+            let count = count.clone();
+            use_keybinding(&cx, KeyBinding::new("g g", "Add one"), move || {
+                count.modify(|c| c + 1)
+            });
+        }
+        {
+            let count = count.clone();
+            use_keybinding(&cx, KeyBinding::new("ctrl+x r", "Reset"), move || {
+                count.set(0)
+            });
+        }
+        {
+            let tui_ctx = tui_ctx.clone();
+            use_keybinding(&cx, KeyBinding::new("q", "Quit"), move || tui_ctx.quit());
+        }
+        cx.render(rsx! {
+            div {
+                tabindex: "0",
+                onkeydown: move |evt| {
+                    let key = evt.key().to_string();
+                    typed.modify(|t| format!("{}{}", t, key));
+                },
+                "{count} {typed}"
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 10, 1);
+    headless.inject_event(key(KeyCode::Tab));
+    type_keys(&mut headless, "gg");
+    assert_eq!(headless.text(), "1");
+    // the keys of a broken sequence are lost, but the key that broke it is sent to the app
+    type_keys(&mut headless, "gaggg");
+    assert_eq!(headless.text(), "2 a");
+    headless.inject_event(ctrl('x'));
+    type_keys(&mut headless, "r");
+    assert_eq!(headless.text(), "0 a");
+    assert!(headless.running());
+    type_keys(&mut headless, "q");
+    assert!(!headless.running());
+}
+
+#[test]
+fn resolves_by_focus_and_priority() {
+    fn app(cx: Scope) -> Element {
+        let used = use_state(&cx, || "none");
+        let high = use_state(&cx, || false);
+        {
+            let used = used.clone();
+            use_keybinding(&cx, KeyBinding::new("x", "Global"), move || {
+                used.set("global")
+            });
+        }
+        {
+            let used = used.clone();
+            let priority = if **high { 1 } else { -1 };
+            use_keybinding(
+                &cx,
+                KeyBinding::new("x", "Prioritized").with_priority(priority),
+                move || used.set("prioritized"),
+            );
+        }
+        {
+            let high = high.clone();
+            use_keybinding(&cx, KeyBinding::new("p", "Raise priority"), move || {
+                high.set(true)
+            });
+        }
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                "{used}"
+                Panel { name: "outer", set: used.clone(), Panel { name: "inner", set: used.clone() } }
+            }
+        })
+    }
+
+    #[derive(Props)]
+    struct PanelProps<'a> {
+        name: &'static str,
+        set: UseState<&'static str>,
+        children: Element<'a>,
+    }
+
+    #[allow(non_snake_case)]
+    fn Panel<'a>(cx: Scope<'a, PanelProps<'a>>) -> Element<'a> {
+        let set = cx.props.set.clone();
+        let name = cx.props.name;
+        use_keybinding(
+            &cx,
+            KeyBinding::new("x", name).in_component(cx.scope_id()),
+            move || set.set(name),
+        );
+        cx.render(rsx! {
+            div {
+                tabindex: "0",
+                "{name}"
+                &cx.props.children
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 12, 2);
+    type_keys(&mut headless, "x");
+    assert_eq!(headless.text(), "global\nouterinner");
+    headless.inject_event(key(KeyCode::Tab));
+    type_keys(&mut headless, "x");
+    assert_eq!(headless.text(), "outer\nouterinner");
+    headless.inject_event(key(KeyCode::Tab));
+    type_keys(&mut headless, "x");
+    assert_eq!(headless.text(), "inner\nouterinner");
+    type_keys(&mut headless, "p");
+    type_keys(&mut headless, "x");
+    assert_eq!(headless.text(), "prioritized\nouterinner");
+}
+
+#[test]
+fn lists_active_bindings() {
+    fn app(cx: Scope) -> Element {
+        let show = use_state(&cx, || true);
+        use_keybinding(&cx, KeyBinding::new("?", "Help"), || {});
+        cx.render(rsx! {
+            div {
+                tabindex: "0",
+                onkeydown: move |_| show.set(false),
+                show.then(|| rsx! { Child {} })
+            }
+        })
+    }
+
+    #[allow(non_snake_case)]
+    fn Child(cx: Scope) -> Element {
+        use_keybinding(&cx, KeyBinding::new("?", "Child help"), || {});
+        use_keybinding(&cx, KeyBinding::new("g g", "Top").with_priority(-1), || {});
+        cx.render(rsx! { "child" })
+    }
+
+    let mut headless = Headless::new(app, 6, 1);
+    let list = |headless: &Headless| {
+        headless
+            .context()
+            .keybindings()
+            .iter()
+            .map(|b| format!("{} {}", b.keys(), b.description()))
+            .collect::<Vec<_>>()
+    };
+    // the binding of the child is added last, so it hides the one of the app
+    assert_eq!(list(&headless), ["? Child help", "g g Top"]);
+
+    headless.inject_event(key(KeyCode::Tab));
+    type_keys(&mut headless, "a");
+    assert_eq!(headless.text(), "");
+    // the bindings are removed with the component
+    assert_eq!(list(&headless), ["? Help"]);
+}
+
+#[test]
+fn does_not_steal_typed_keys() {
+    fn app(cx: Scope) -> Element {
+        let used = use_state(&cx, || 0);
+        {
+            let used = used.clone();
+            use_keybinding(&cx, KeyBinding::new("q", "Plain"), move || {
+                used.modify(|u| u + 1)
+            });
+        }
+        {
+            let used = used.clone();
+            use_keybinding(&cx, KeyBinding::new("ctrl+q", "Modified"), move || {
+                used.modify(|u| u + 10)
+            });
+        }
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                input { width: "5px", height: "1px" }
+                "{used}"
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 5, 2);
+    headless.inject_event(key(KeyCode::Tab));
+    type_keys(&mut headless, "qq");
+    assert_eq!(headless.text(), "qq\n0");
+    headless.inject_event(ctrl('q'));
+    headless.step();
+    assert_eq!(headless.text(), "qq\n10");
+}
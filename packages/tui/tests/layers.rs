@@ -0,0 +1,203 @@
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use dioxus::prelude::*;
+use dioxus_tui::Headless;
+use tui::style::Color;
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        column,
+        row,
+        kind,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn click(headless: &mut Headless, column: u16, row: u16) {
+    headless.inject_event(mouse(MouseEventKind::Down(MouseButton::Left), column, row));
+    headless.inject_event(mouse(MouseEventKind::Up(MouseButton::Left), column, row));
+    headless.step();
+}
+
+#[test]
+fn paints_by_z_index() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                div {
+                    position: "absolute",
+                    left: "0px",
+                    z_index: "1",
+                    "aaa"
+                }
+                div {
+                    position: "absolute",
+                    left: "1px",
+                    "bbb"
+                }
+                div {
+                    z_index: "-1",
+                    "cccccc"
+                }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 6, 1);
+    assert_eq!(headless.text(), "aaabcc");
+}
+
+#[test]
+fn positions_absolutely() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                "first"
+                "second"
+                div {
+                    position: "absolute",
+                    right: "0px",
+                    bottom: "0px",
+                    "x"
+                }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 6, 3);
+    assert_eq!(headless.text(), "first\nsecond\n     x");
+}
+
+#[test]
+fn blends_backgrounds() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                background_color: "white",
+                "hello"
+                "world"
+                div {
+                    position: "absolute",
+                    left: "1px",
+                    width: "3px",
+                    height: "1px",
+                    background_color: "blue",
+                }
+                div {
+                    position: "absolute",
+                    top: "1px",
+                    width: "2px",
+                    height: "1px",
+                    background_color: "rgba(255, 0, 0, 0.5)",
+                }
+                div {
+                    position: "absolute",
+                    top: "1px",
+                    left: "2px",
+                    width: "3px",
+                    height: "1px",
+                    background_color: "transparent",
+                }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 5, 2);
+    // opaque backgrounds hide the text beneath them
+    assert_eq!(headless.text(), "h   o\nworld");
+    assert_eq!(headless.buffer().get(1, 0).bg, Color::Rgb(0, 0, 255));
+    assert_eq!(headless.buffer().get(0, 1).bg, Color::Rgb(255, 128, 128));
+    assert_eq!(headless.buffer().get(3, 1).bg, Color::Rgb(255, 255, 255));
+}
+
+#[test]
+fn hit_tests_by_z_index() {
+    fn app(cx: Scope) -> Element {
+        let clicked = use_state(&cx, || "none");
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                div {
+                    height: "1px",
+                    onclick: move |_| clicked.set("button"),
+                    "{clicked}"
+                }
+                div {
+                    height: "1px",
+                    z_index: "-1",
+                    onclick: move |_| clicked.set("below"),
+                }
+                div {
+                    position: "absolute",
+                    top: "0px",
+                    left: "0px",
+                    width: "2px",
+                    height: "2px",
+                    z_index: "1",
+                    onclick: move |_| clicked.set("modal"),
+                }
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 6, 2);
+    click(&mut headless, 0, 0);
+    assert_eq!(headless.text(), "modal\n");
+    click(&mut headless, 4, 0);
+    assert_eq!(headless.text(), "button\n");
+    // the element with a negative z-index is painted under its parent
+    click(&mut headless, 4, 1);
+    assert_eq!(headless.text(), "button\n");
+    click(&mut headless, 1, 1);
+    assert_eq!(headless.text(), "modal\n");
+}
+
+#[test]
+fn clips_absolutely_by_positioned_ancestors() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                div {
+                    width: "2px",
+                    height: "1px",
+                    overflow: "hidden",
+                    "abcd"
+                    div {
+                        position: "absolute",
+                        top: "1px",
+                        "wxyz"
+                    }
+                }
+                div { height: "1px" }
+                div {
+                    position: "relative",
+                    width: "2px",
+                    height: "1px",
+                    overflow: "hidden",
+                    "cd"
+                    div {
+                        position: "absolute",
+                        left: "2px",
+                        "pq"
+                    }
+                }
+            }
+        })
+    }
+
+    let headless = Headless::new(app, 4, 3);
+    // the overflow of an element that is not positioned doesn't clip absolute descendants
+    assert_eq!(headless.text(), "ab\nwxyz\ncd");
+}
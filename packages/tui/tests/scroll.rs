@@ -0,0 +1,207 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use dioxus::prelude::*;
+use dioxus_tui::query::Query;
+use dioxus_tui::{Headless, Point, TuiContext};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The tui renderer will look for any event that has occured or any future that has resolved in a loop.
+/// It will resolve at most one event per loop.
+/// This future will resolve after a certain number of polls. If the number of polls is greater than the number of events triggered, and the event has not been recieved there is an issue with the event system.
+struct PollN(usize);
+impl PollN {
+    fn new(n: usize) -> Self {
+        PollN(n)
+    }
+}
+impl Future for PollN {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 == 0 {
+            Poll::Ready(())
+        } else {
+            self.0 -= 1;
+            Poll::Pending
+        }
+    }
+}
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn wheel_scroll() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let render_count = use_state(&cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let query: Query = cx.consume_context().unwrap();
+        let render_count_handle = render_count.clone();
+        cx.spawn(async move {
+            PollN::new(4).await;
+            render_count_handle.modify(|x| *x + 1);
+        });
+        if *render_count.get() > 2 {
+            panic!("Event was not received");
+        }
+        tui_ctx.inject_event(Event::Mouse(MouseEvent {
+            column: 1,
+            row: 1,
+            kind: MouseEventKind::Moved,
+            modifiers: KeyModifiers::NONE,
+        }));
+        tui_ctx.inject_event(Event::Mouse(MouseEvent {
+            column: 1,
+            row: 1,
+            kind: MouseEventKind::ScrollDown,
+            modifiers: KeyModifiers::NONE,
+        }));
+        cx.render(rsx! {
+            div {
+                width: "10px",
+                height: "5px",
+                flex_direction: "column",
+                overflow: "scroll",
+                onscroll: move |_| {
+                    let node = query.get(cx.root_node().mounted_id());
+                    assert_eq!(node.scroll_offset(), Point { x: 0, y: 1 });
+                    assert_eq!(node.scroll_size().unwrap().height, 20);
+                    tui_ctx.quit();
+                },
+                (0..20).map(|i| rsx! { div { key: "{i}", height: "1px", "{i}" } })
+            }
+        })
+    }
+}
+
+#[test]
+fn keyboard_scroll() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let render_count = use_state(&cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let query: Query = cx.consume_context().unwrap();
+        let render_count_handle = render_count.clone();
+        cx.spawn(async move {
+            PollN::new(4).await;
+            render_count_handle.modify(|x| *x + 1);
+        });
+        if *render_count.get() > 2 {
+            panic!("Event was not received");
+        }
+        // scroll containers are focusable
+        tui_ctx.inject_event(key(KeyCode::Tab));
+        tui_ctx.inject_event(key(KeyCode::PageDown));
+        cx.render(rsx! {
+            div {
+                width: "10px",
+                height: "5px",
+                flex_direction: "column",
+                overflow_y: "auto",
+                onscroll: move |_| {
+                    let node = query.get(cx.root_node().mounted_id());
+                    // one line of the previous page stays visible
+                    assert_eq!(node.scroll_offset(), Point { x: 0, y: 4 });
+                    tui_ctx.quit();
+                },
+                (0..20).map(|i| rsx! { div { key: "{i}", height: "1px", "{i}" } })
+            }
+        })
+    }
+}
+
+#[test]
+fn programmatic_scroll() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let render_count = use_state(&cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let query: Query = cx.consume_context().unwrap();
+        let render_count_handle = render_count.clone();
+        cx.spawn(async move {
+            PollN::new(4).await;
+            render_count_handle.modify(|x| *x + 1);
+        });
+        if *render_count.get() > 2 {
+            panic!("Event was not received");
+        }
+        tui_ctx.inject_event(key(KeyCode::Tab));
+        tui_ctx.inject_event(key(KeyCode::Char('a')));
+        let query2 = query.clone();
+        cx.render(rsx! {
+            div {
+                width: "10px",
+                height: "5px",
+                flex_direction: "column",
+                overflow: "hidden",
+                tabindex: "0",
+                onkeydown: move |_| {
+                    let id = cx.root_node().mounted_id();
+                    // the offset is limited to the size of the content
+                    assert!(query.scroll_to(id, Point { x: 5.0, y: 100.0 }));
+                    assert!(!query.scroll_by(id, Point { x: 0.0, y: 1.0 }));
+                },
+                onscroll: move |_| {
+                    let node = query2.get(cx.root_node().mounted_id());
+                    assert_eq!(node.scroll_offset(), Point { x: 0, y: 15 });
+                    tui_ctx.quit();
+                },
+                (0..20).map(|i| rsx! { div { key: "{i}", height: "1px", "{i}" } })
+            }
+        })
+    }
+}
+
+#[test]
+fn scrollbar_without_track() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "5px",
+                height: "1px",
+                overflow: "scroll",
+                "hello world"
+            }
+        })
+    }
+
+    // the horizontal scrollbar takes the only row, so the vertical one has no room
+    let headless = Headless::new(app, 5, 1);
+    assert_eq!(headless.text(), "━━──o");
+}
+
+#[test]
+fn shrinks_after_overflow_is_removed() {
+    fn app(cx: Scope) -> Element {
+        let clips = use_state(&cx, || true);
+        let overflow = if **clips { "hidden" } else { "visible" };
+        cx.render(rsx! {
+            div {
+                width: "6px",
+                height: "2px",
+                overflow: "{overflow}",
+                tabindex: "0",
+                onkeydown: move |_| clips.set(false),
+                div { width: "4px", "a a" }
+                div { width: "4px", "b b" }
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 6, 2);
+    // the children keep their size in a container that clips
+    assert_eq!(headless.text(), "a a b\n");
+    headless.inject_event(key(KeyCode::Tab));
+    headless.inject_event(key(KeyCode::Enter));
+    headless.step();
+    assert_eq!(headless.text(), "a ab b\n");
+}
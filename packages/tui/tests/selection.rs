@@ -0,0 +1,139 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use dioxus::prelude::*;
+use dioxus_tui::{Config, Headless};
+use tui::style::Modifier;
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        column,
+        row,
+        kind,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn drag(headless: &mut Headless, from: (u16, u16), to: (u16, u16)) {
+    let left = MouseButton::Left;
+    headless.inject_event(mouse(MouseEventKind::Down(left), from.0, from.1));
+    headless.inject_event(mouse(MouseEventKind::Drag(left), to.0, to.1));
+    headless.inject_event(mouse(MouseEventKind::Up(left), to.0, to.1));
+    headless.step();
+}
+
+fn selectable(app: Component<()>, width: u16, height: u16) -> Headless {
+    Headless::new_with_cfg(app, Config::new().with_selection(), width, height)
+}
+
+#[test]
+fn selects_and_copies_text() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                border_style: "solid",
+                border_width: "1px",
+                "hello world"
+                "second line"
+            }
+        })
+    }
+
+    let mut headless = selectable(app, 13, 4);
+    let copied = Rc::new(RefCell::new(Vec::new()));
+    {
+        let copied = copied.clone();
+        headless
+            .context()
+            .set_clipboard(move |text: &str| copied.borrow_mut().push(text.to_string()));
+    }
+
+    // the selection goes from the end of the first line to the start of the second one
+    drag(&mut headless, (7, 1), (6, 2));
+    assert_eq!(headless.selection().as_deref(), Some("world\nsecond"));
+    assert_eq!(*copied.borrow(), ["world\nsecond"]);
+    let reversed = |headless: &Headless, x, y| {
+        headless
+            .buffer()
+            .get(x, y)
+            .modifier
+            .contains(Modifier::REVERSED)
+    };
+    for (x, y) in [(7, 1), (11, 1), (1, 2), (6, 2)] {
+        assert!(reversed(&headless, x, y));
+    }
+    // borders and the space after the text are not selected
+    for (x, y) in [(6, 1), (12, 1), (0, 2), (7, 2)] {
+        assert!(!reversed(&headless, x, y));
+    }
+
+    // selecting backwards gives the same text
+    drag(&mut headless, (6, 2), (7, 1));
+    assert_eq!(headless.selection().as_deref(), Some("world\nsecond"));
+
+    // a click clears the selection without copying
+    drag(&mut headless, (3, 1), (3, 1));
+    assert_eq!(headless.selection(), None);
+    assert!(!reversed(&headless, 7, 1));
+    assert_eq!(copied.borrow().len(), 2);
+}
+
+#[test]
+fn honours_user_select() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                "ab"
+                div {
+                    user_select: "none",
+                    "cd"
+                    span { "ef" }
+                    span { user_select: "text", "gh" }
+                }
+                "ij"
+            }
+        })
+    }
+
+    let mut headless = selectable(app, 10, 1);
+    assert_eq!(headless.text(), "abcdefghij");
+    drag(&mut headless, (0, 0), (9, 0));
+    // the text that can't be selected is copied as spaces
+    assert_eq!(headless.selection().as_deref(), Some("ab    ghij"));
+
+    // a selection can't start in text that can't be selected
+    drag(&mut headless, (3, 0), (9, 0));
+    assert_eq!(headless.selection(), None);
+}
+
+#[test]
+fn is_disabled_by_default() {
+    fn app(cx: Scope) -> Element {
+        let clicks = use_state(&cx, || 0);
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                onmousedown: move |_| clicks.modify(|c| c + 1),
+                "clicks: {clicks}"
+            }
+        })
+    }
+
+    let mut headless = Headless::new(app, 10, 1);
+    drag(&mut headless, (0, 0), (8, 0));
+    assert_eq!(headless.selection(), None);
+    assert_eq!(headless.text(), "clicks: 1");
+
+    // the app still gets the mouse events when text is selected
+    let mut headless = selectable(app, 10, 1);
+    drag(&mut headless, (0, 0), (8, 0));
+    assert_eq!(headless.text(), "clicks: 1");
+    // the selection stays on the cells when their text changes
+    assert_eq!(headless.selection().as_deref(), Some("clicks: 1"));
+}
@@ -0,0 +1,83 @@
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use dioxus::prelude::*;
+use dioxus_core::ElementId;
+use dioxus_tui::query::Query;
+use dioxus_tui::{Size, TuiContext};
+
+/// Click the top left corner, which runs the assertions on the laid out nodes.
+fn click(cx: &ScopeState) {
+    let tui_ctx: TuiContext = cx.consume_context().unwrap();
+    tui_ctx.inject_event(Event::Mouse(MouseEvent {
+        column: 0,
+        row: 0,
+        kind: MouseEventKind::Down(MouseButton::Left),
+        modifiers: KeyModifiers::NONE,
+    }));
+}
+
+fn child_id(cx: &ScopeState, i: usize) -> ElementId {
+    match cx.root_node() {
+        VNode::Element(el) => el.children[i].mounted_id(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn wraps_at_spaces() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let query: Query = cx.consume_context().unwrap();
+        click(&cx);
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                align_items: "flex-start",
+                onmousedown: move |_| {
+                    let size = |i: usize| query.get(child_id(&cx, i)).size().unwrap();
+                    assert_eq!(size(0), Size { width: 5, height: 2 });
+                    assert_eq!(size(1), Size { width: 5, height: 1 });
+                    assert_eq!(size(2), Size { width: 5, height: 3 });
+                    tui_ctx.quit();
+                },
+                div { width: "5px", "hello world" }
+                div { width: "5px", white_space: "nowrap", "hello world" }
+                div { width: "5px", white_space: "pre", "a\nb\nc" }
+            }
+        })
+    }
+}
+
+#[test]
+fn wide_characters() {
+    dioxus_tui::launch_cfg(app, dioxus_tui::Config::new().with_headless());
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let query: Query = cx.consume_context().unwrap();
+        click(&cx);
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                align_items: "flex-start",
+                onmousedown: move |_| {
+                    let size = |i: usize| query.get(child_id(&cx, i)).size().unwrap();
+                    // each character is two cells wide
+                    assert_eq!(size(0), Size { width: 6, height: 1 });
+                    assert_eq!(size(1), Size { width: 4, height: 2 });
+                    // combining characters don't take up a cell
+                    assert_eq!(size(2), Size { width: 4, height: 1 });
+                    tui_ctx.quit();
+                },
+                div { "日本語" }
+                div { width: "4px", "日本語" }
+                div { "e\u{301}e\u{301}e\u{301}e\u{301}" }
+            }
+        })
+    }
+}